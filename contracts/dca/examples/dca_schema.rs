@@ -1,18 +1,88 @@
 use std::env::current_dir;
 use std::fs::create_dir_all;
+use std::path::Path;
 
+use astroport::asset::AssetInfo;
 use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 
-use astroport_dca::dca::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use astroport_dca::dca::{
+    BotInfo, Config, DcaQueryInfo, ExecuteMsg, InstantiateMsg, MigrateMsg, NftInfoResponse,
+    OperatorApproval, OrderLimitsResponse, OrderPerformanceResponse, OwnerOfResponse,
+    PurchaseRecord, QueryMsg, ReferrerStats, StatsResponse, TokensResponse, UserConfig, VaultInfo,
+    VaultPositionResponse, VaultPurchaseRecord,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
     out_dir.push("schema");
-    create_dir_all(&out_dir).unwrap();
-    remove_schemas(&out_dir).unwrap();
+    export_schemas(&out_dir);
+}
+
+/// Exports the schemas of the messages of the contract and of the response of each query to
+/// `out_dir`, replacing the schemas exported before.
+pub fn export_schemas(out_dir: &Path) {
+    create_dir_all(out_dir).unwrap();
+    remove_schemas(out_dir).unwrap();
+
+    export_schema_with_title(&schema_for!(InstantiateMsg), out_dir, "InstantiateMsg");
+    export_schema_with_title(&schema_for!(ExecuteMsg), out_dir, "ExecuteMsg");
+    export_schema_with_title(&schema_for!(QueryMsg), out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(MigrateMsg), out_dir, "MigrateMsg");
 
-    export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
-    export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
-    export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
-    export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
+    export_schema_with_title(
+        &schema_for!(Vec<DcaQueryInfo>),
+        out_dir,
+        "UserDcaOrdersResponse",
+    );
+    export_schema_with_title(&schema_for!(Config), out_dir, "ConfigResponse");
+    export_schema_with_title(&schema_for!(UserConfig), out_dir, "UserConfigResponse");
+    export_schema_with_title(
+        &schema_for!(ReferrerStats),
+        out_dir,
+        "ReferrerStatsResponse",
+    );
+    export_schema_with_title(&schema_for!(BotInfo), out_dir, "BotResponse");
+    export_schema_with_title(&schema_for!(Vec<BotInfo>), out_dir, "BotsResponse");
+    export_schema_with_title(&schema_for!(OwnerOfResponse), out_dir, "OwnerOfResponse");
+    export_schema_with_title(&schema_for!(NftInfoResponse), out_dir, "NftInfoResponse");
+    export_schema_with_title(&schema_for!(TokensResponse), out_dir, "TokensResponse");
+    export_schema_with_title(
+        &schema_for!(Vec<OperatorApproval>),
+        out_dir,
+        "OperatorsResponse",
+    );
+    export_schema_with_title(
+        &schema_for!(OrderLimitsResponse),
+        out_dir,
+        "OrderLimitsResponse",
+    );
+    export_schema_with_title(
+        &schema_for!(Vec<PurchaseRecord>),
+        out_dir,
+        "PurchaseHistoryResponse",
+    );
+    export_schema_with_title(
+        &schema_for!(Vec<AssetInfo>),
+        out_dir,
+        "WhitelistedTokensResponse",
+    );
+    export_schema_with_title(&schema_for!(VaultInfo), out_dir, "VaultResponse");
+    export_schema_with_title(&schema_for!(Vec<VaultInfo>), out_dir, "VaultsResponse");
+    export_schema_with_title(
+        &schema_for!(VaultPositionResponse),
+        out_dir,
+        "VaultPositionResponse",
+    );
+    export_schema_with_title(
+        &schema_for!(Vec<VaultPurchaseRecord>),
+        out_dir,
+        "VaultPurchaseHistoryResponse",
+    );
+    export_schema_with_title(&schema_for!(StatsResponse), out_dir, "GlobalStatsResponse");
+    export_schema_with_title(&schema_for!(StatsResponse), out_dir, "UserStatsResponse");
+    export_schema_with_title(
+        &schema_for!(OrderPerformanceResponse),
+        out_dir,
+        "OrderPerformanceResponse",
+    );
 }
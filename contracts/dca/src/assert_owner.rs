@@ -0,0 +1,24 @@
+use astroport::querier::query_factory_config;
+use cosmwasm_std::{Deps, MessageInfo};
+
+use crate::{error::ContractError, state::CONFIG};
+
+/// ## Description
+/// Checks that the sender of a message is the owner of the Astroport factory contract, which is
+/// the owner of the DCA contract.
+///
+/// Returns a [`ContractError::Unauthorized`] if the sender is not the owner.
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `info` - The [`MessageInfo`] of the message that requires owner permissions.
+pub fn assert_owner(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+
+    if info.sender != factory_config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
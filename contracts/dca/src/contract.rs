@@ -2,19 +2,37 @@ use std::str::FromStr;
 
 use crate::error::ContractError;
 use crate::handlers::{
-    add_bot_tip, cancel_dca_order, create_dca_order, modify_dca_order, perform_dca_purchase,
-    update_config, update_user_config, withdraw, ModifyDcaOrderParameters,
+    add_bot_tip, add_vault_tip, add_whitelisted_tokens, approve_operator, batch_settlement_reply,
+    cancel_dca_order, claim_bot_bond, claim_referral_rewards, claim_vault_proceeds,
+    create_dca_order, dca_purchase_reply, deposit_vault, modify_dca_order, pause_dca_order,
+    perform_batch_settlement, perform_dca_purchase, perform_vault_purchase, register_bot,
+    remove_whitelisted_tokens, resume_dca_order, revoke_operator, skip_next_purchase, slash_bot,
+    transfer_nft, unregister_bot, update_config, update_user_config, vault_purchase_reply,
+    whitelist_bot, withdraw, withdraw_vault, CreateDcaOrderParameters, DepositVaultParameters,
+    ModifyDcaOrderParameters, UpdateConfigParameters,
 };
-use crate::queries::{get_config, get_user_config, get_user_dca_orders};
-use crate::state::{Config, CONFIG};
+use crate::migrations;
+use crate::queries::{
+    get_bot, get_bots, get_config, get_global_stats, get_nft_info, get_operators, get_order_limits,
+    get_order_performance, get_owner_of, get_purchase_history, get_referrer_stats, get_tokens,
+    get_user_config, get_user_dca_orders, get_user_stats, get_vault, get_vault_position,
+    get_vault_purchase_history, get_vaults, get_whitelisted_tokens,
+};
+use crate::state::{
+    BATCH_SETTLEMENT_REPLY_ID, CONFIG, DCA_PURCHASE_REPLY_ID, VAULT_PURCHASE_REPLY_ID,
+    WHITELISTED_TOKENS,
+};
+use crate::validation::{validate_config, validate_min_dca_amounts, validate_whitelisted_tokens};
 
 use astroport::asset::addr_validate_to_lower;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    attr, entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdError, StdResult,
 };
 
-use astroport_dca::dca::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use cw2::set_contract_version;
+use astroport_dca::dca::{Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-dca";
@@ -48,34 +66,106 @@ pub fn instantiate(
     // validate that factory_addr and router_addr is an address
     let factory_addr = addr_validate_to_lower(deps.api, &msg.factory_addr)?;
     let router_addr = addr_validate_to_lower(deps.api, &msg.router_addr)?;
+    let fee_collector = addr_validate_to_lower(deps.api, &msg.fee_collector)?;
+
+    // validate that the whitelisted tokens are valid and unique
+    let whitelisted_tokens = validate_whitelisted_tokens(deps.api, msg.whitelisted_tokens)?;
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let config = Config {
         max_hops: msg.max_hops,
         per_hop_fee: msg.per_hop_fee,
-        whitelisted_tokens: msg.whitelisted_tokens,
+        base_tip: msg.base_tip,
+        tip_volume_rate: msg.tip_volume_rate,
+        max_tip: msg.max_tip,
         max_spread,
         factory_addr,
         router_addr,
+        fee_collector,
+        protocol_fee_bps: msg.protocol_fee_bps,
+        target_asset_fees: vec![],
+        fee_exempt_addresses: vec![],
+        referral_share: msg.referral_share,
+        referral_source: msg.referral_source,
+        bot_priority_window: msg.bot_priority_window,
+        min_bot_bond: msg.min_bot_bond,
+        bot_unbonding_period: msg.bot_unbonding_period,
+        bot_whitelist_enabled: msg.bot_whitelist_enabled,
+        min_interval: msg.min_interval,
+        min_dca_amounts: validate_min_dca_amounts(deps.api, msg.min_dca_amounts)?,
+        max_orders_per_user: msg.max_orders_per_user,
     };
 
+    // validate that the config is within the protocol defined bounds
+    validate_config(&config)?;
+
     CONFIG.save(deps.storage, &config)?;
 
+    for token in whitelisted_tokens {
+        WHITELISTED_TOKENS.save(deps.storage, token.as_bytes(), &token)?;
+    }
+
     Ok(Response::new())
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Used for contract migration. Migrates the contract storage from the stored contract version to
+/// the current version.
+///
+/// Returns a [`ContractError`] if the stored contract is a different contract or a newer version,
+/// or if a migration fails, otherwise returns a [`Response`] with the specified attributes.
 /// ## Arguments
-/// * `_deps` - A [`DepsMut`] that contains the dependencies.
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
 ///
-/// * `_env` - The [`Env`] of the blockchain.
+/// * `env` - The [`Env`] of the blockchain.
 ///
-/// * `_msg` - The [`MigrateMsg`] to migrate the contract.
+/// * `msg` - The [`MigrateMsg`] to migrate the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    // validate that we are migrating from this contract
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidMigrationContract {
+            contract: stored.contract,
+        });
+    }
+
+    // validate that we are not migrating to an older version
+    let stored_version = parse_version(&stored.version)?;
+    let new_version = parse_version(CONTRACT_VERSION)?;
+    if stored_version > new_version {
+        return Err(ContractError::MigrationDowngrade {
+            current: stored.version,
+            new: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // run every migration newer than the stored version, in order
+    if stored_version < Version::new(1, 1, 0) {
+        migrations::v1_1_0::migrate(deps.branch(), env, &msg)?;
+    }
+
+    if stored_version < Version::new(1, 2, 0) {
+        migrations::v1_2_0::migrate(deps.branch(), &msg)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("from_version", stored.version),
+        attr("to_version", CONTRACT_VERSION),
+    ]))
+}
+
+/// ## Description
+/// Parses a semver contract version, returning a [`ContractError`] if it is invalid.
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidContractVersion {
+        version: version.to_string(),
+    })
 }
 
 /// ## Description
@@ -92,15 +182,44 @@ pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Respons
 /// ## Execution Messages
 /// * **ExecuteMsg::AddBotTip { }** Adds a bot tip to fund DCA purchases.
 ///
-/// * **ExecuteMsg::CancelDcaOrder { initial_asset }** Cancels an existing DCA order.
+/// * **ExecuteMsg::AddVaultTip { vault_id }** Adds a bot tip to the position of the sender in a
+/// vault.
+///
+/// * **ExecuteMsg::AddWhitelistedTokens { tokens }** Adds tokens to the hop route whitelist.
+///
+/// * **ExecuteMsg::ApproveOperator {
+///         operator,
+///         expires,
+///         permissions
+///     }** Approves an operator to manage the DCA orders of the sender.
+///
+/// * **ExecuteMsg::CancelDcaOrder { initial_asset, owner }** Cancels an existing DCA order.
+///
+/// * **ExecuteMsg::ClaimReferralRewards {}** Claims the referral rewards credited to the sender.
+///
+/// * **ExecuteMsg::ClaimVaultProceeds { vault_id }** Claims the purchases of a vault credited to
+/// the sender.
 ///
 /// * **ExecuteMsg::CreateDcaOrder {
 ///         initial_asset,
 ///         target_asset,
 ///         interval,
-///         dca_amount
+///         dca_amount,
+///         referrer,
+///         max_hops,
+///         max_spread,
+///         route_preference,
+///         owner
 ///     }** Creates a new DCA order where `initial_asset` will purchase `target_asset`.
 ///
+/// * **ExecuteMsg::DepositVault {
+///         initial_asset,
+///         target_asset,
+///         interval,
+///         dca_amount,
+///         max_spread
+///     }** Deposits into the vault where `initial_asset` will purchase `target_asset`.
+///
 /// * **ExecuteMsg::ModifyDcaOrder {
 ///         old_initial_asset,
 ///         new_initial_asset,
@@ -108,24 +227,82 @@ pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Respons
 ///         new_interval,
 ///         new_dca_amount,
 ///         should_reset_purchase_time,
+///         new_max_hops,
+///         new_max_spread,
+///         new_route_preference,
+///         owner,
 ///     }** Modifies an existing DCA order, allowing the user to change certain parameters.
 ///
+/// * **ExecuteMsg::PauseDcaOrder { id }** Pauses a DCA order until it is resumed.
+///
+/// * **ExecuteMsg::ResumeDcaOrder { id, catch_up }** Resumes a paused DCA order, optionally
+/// keeping its schedule so that a single purchase is due immediately if any was missed while it
+/// was paused.
+///
+/// * **ExecuteMsg::SkipNextPurchase { id }** Skips the next purchase of a DCA order.
+///
+/// * **ExecuteMsg::RegisterBot {}** Registers the sender as a bot, adding any uusd sent to its
+/// bond.
+///
+/// * **ExecuteMsg::TransferNft { recipient, token_id }** Transfers a DCA order to a new owner.
+///
+/// * **ExecuteMsg::UnregisterBot {}** Unregisters the sender as a bot, starting the unbonding of
+/// its bond.
+///
+/// * **ExecuteMsg::ClaimBotBond {}** Claims the bond of the sender once it has unbonded.
+///
+/// * **ExecuteMsg::SlashBot { bot, amount }** Slashes the bond of a registered bot.
+///
+/// * **ExecuteMsg::WhitelistBot { bot, whitelisted }** Adds a bot to the bot whitelist, or
+/// removes it.
+///
 /// * **ExecuteMsg::PerformDcaPurchase { user, hops }** Performs a DCA purchase on behalf of a
 /// specified user given a hop route.
 ///
+/// * **ExecuteMsg::PerformBatchSettlement { order_ids, hops }** Settles opposing DCA orders
+/// against each other, swapping only the net imbalance given a hop route.
+///
+/// * **ExecuteMsg::PerformVaultPurchase { vault_id, hops }** Performs the aggregated purchase of a
+/// vault given a hop route.
+///
+/// * **ExecuteMsg::RevokeOperator { operator }** Revokes the approval of an operator.
+///
+/// * **ExecuteMsg::RemoveWhitelistedTokens { tokens }** Removes tokens from the hop route
+/// whitelist.
+///
 /// * **ExecuteMsg::UpdateConfig {
 ///         max_hops,
 ///         per_hop_fee,
+///         base_tip,
+///         tip_volume_rate,
+///         max_tip,
 ///         whitelisted_tokens,
-///         max_spread
+///         max_spread,
+///         fee_collector,
+///         protocol_fee_bps,
+///         target_asset_fees,
+///         fee_exempt_addresses,
+///         referral_share,
+///         referral_source,
+///         bot_priority_window,
+///         min_bot_bond,
+///         bot_unbonding_period,
+///         bot_whitelist_enabled,
+///         min_interval,
+///         min_dca_amounts,
+///         max_orders_per_user
 ///     }** Updates the contract configuration with the specified input parameters.
 ///
 /// * **ExecuteMsg::UpdateUserConfig {
 ///         max_hops,
 ///         max_spread,
+///         max_tip_per_purchase,
+///         tip_mode,
 ///     }** Updates a users configuration with the new input parameters.
 ///
-/// * **ExecuteMsg::Withdraw { tip }** Withdraws a bot tip from the contract.
+/// * **ExecuteMsg::Withdraw { tip, owner }** Withdraws a bot tip from the contract.
+///
+/// * **ExecuteMsg::WithdrawVault { vault_id }** Withdraws the position of the sender in a vault.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -137,40 +314,147 @@ pub fn execute(
         ExecuteMsg::UpdateConfig {
             max_hops,
             per_hop_fee,
+            base_tip,
+            tip_volume_rate,
+            max_tip,
             whitelisted_tokens,
             max_spread,
+            fee_collector,
+            protocol_fee_bps,
+            target_asset_fees,
+            fee_exempt_addresses,
+            referral_share,
+            referral_source,
+            bot_priority_window,
+            min_bot_bond,
+            bot_unbonding_period,
+            bot_whitelist_enabled,
+            min_interval,
+            min_dca_amounts,
+            max_orders_per_user,
         } => update_config(
             deps,
             info,
-            max_hops,
-            per_hop_fee,
-            whitelisted_tokens,
-            max_spread,
+            UpdateConfigParameters {
+                max_hops,
+                per_hop_fee,
+                base_tip,
+                tip_volume_rate,
+                max_tip,
+                whitelisted_tokens,
+                max_spread,
+                fee_collector,
+                protocol_fee_bps,
+                target_asset_fees,
+                fee_exempt_addresses,
+                referral_share,
+                referral_source,
+                bot_priority_window,
+                min_bot_bond,
+                bot_unbonding_period,
+                bot_whitelist_enabled,
+                min_interval,
+                min_dca_amounts,
+                max_orders_per_user,
+            },
         ),
         ExecuteMsg::UpdateUserConfig {
             max_hops,
             max_spread,
-        } => update_user_config(deps, info, max_hops, max_spread),
+            max_tip_per_purchase,
+            tip_mode,
+        } => update_user_config(
+            deps,
+            info,
+            max_hops,
+            max_spread,
+            max_tip_per_purchase,
+            tip_mode,
+        ),
         ExecuteMsg::CreateDcaOrder {
             initial_asset,
             target_asset,
             interval,
             dca_amount,
+            referrer,
+            max_hops,
+            max_spread,
+            route_preference,
+            owner,
         } => create_dca_order(
             deps,
             env,
             info,
+            CreateDcaOrderParameters {
+                initial_asset,
+                target_asset,
+                interval,
+                dca_amount,
+                referrer,
+                max_hops,
+                max_spread,
+                route_preference,
+                owner,
+            },
+        ),
+        ExecuteMsg::AddBotTip {} => add_bot_tip(deps, info),
+        ExecuteMsg::AddVaultTip { vault_id } => add_vault_tip(deps, info, vault_id),
+        ExecuteMsg::DepositVault {
             initial_asset,
             target_asset,
             interval,
             dca_amount,
+            max_spread,
+        } => deposit_vault(
+            deps,
+            env,
+            info,
+            DepositVaultParameters {
+                initial_asset,
+                target_asset,
+                interval,
+                dca_amount,
+                max_spread,
+            },
         ),
-        ExecuteMsg::AddBotTip {} => add_bot_tip(deps, info),
-        ExecuteMsg::Withdraw { tip: amount } => withdraw(deps, info, amount),
+        ExecuteMsg::PerformBatchSettlement { order_ids, hops } => {
+            perform_batch_settlement(deps, env, info, order_ids, hops)
+        }
+        ExecuteMsg::PerformVaultPurchase { vault_id, hops } => {
+            perform_vault_purchase(deps, env, info, vault_id, hops)
+        }
+        ExecuteMsg::ClaimVaultProceeds { vault_id } => claim_vault_proceeds(deps, info, vault_id),
+        ExecuteMsg::WithdrawVault { vault_id } => withdraw_vault(deps, info, vault_id),
+        ExecuteMsg::Withdraw { tip: amount, owner } => withdraw(deps, env, info, amount, owner),
+        ExecuteMsg::ApproveOperator {
+            operator,
+            expires,
+            permissions,
+        } => approve_operator(deps, env, info, operator, expires, permissions),
+        ExecuteMsg::RevokeOperator { operator } => revoke_operator(deps, info, operator),
+        ExecuteMsg::RegisterBot {} => register_bot(deps, env, info),
+        ExecuteMsg::UnregisterBot {} => unregister_bot(deps, env, info),
+        ExecuteMsg::ClaimBotBond {} => claim_bot_bond(deps, env, info),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => transfer_nft(deps, info, recipient, token_id),
+        ExecuteMsg::SlashBot { bot, amount } => slash_bot(deps, info, bot, amount),
+        ExecuteMsg::WhitelistBot { bot, whitelisted } => {
+            whitelist_bot(deps, info, bot, whitelisted)
+        }
         ExecuteMsg::PerformDcaPurchase { user, hops } => {
             perform_dca_purchase(deps, env, info, user, hops)
         }
-        ExecuteMsg::CancelDcaOrder { initial_asset } => cancel_dca_order(deps, info, initial_asset),
+        ExecuteMsg::CancelDcaOrder {
+            initial_asset,
+            owner,
+        } => cancel_dca_order(deps, env, info, initial_asset, owner),
+        ExecuteMsg::ClaimReferralRewards {} => claim_referral_rewards(deps, info),
+        ExecuteMsg::AddWhitelistedTokens { tokens } => add_whitelisted_tokens(deps, info, tokens),
+        ExecuteMsg::RemoveWhitelistedTokens { tokens } => {
+            remove_whitelisted_tokens(deps, info, tokens)
+        }
         ExecuteMsg::ModifyDcaOrder {
             old_initial_asset,
             new_initial_asset,
@@ -178,6 +462,10 @@ pub fn execute(
             new_interval,
             new_dca_amount,
             should_reset_purchase_time,
+            new_max_hops,
+            new_max_spread,
+            new_route_preference,
+            owner,
         } => modify_dca_order(
             deps,
             env,
@@ -189,8 +477,17 @@ pub fn execute(
                 new_interval,
                 new_dca_amount,
                 should_reset_purchase_time,
+                new_max_hops,
+                new_max_spread,
+                new_route_preference,
+                owner,
             },
         ),
+        ExecuteMsg::PauseDcaOrder { id } => pause_dca_order(deps, env, info, id),
+        ExecuteMsg::ResumeDcaOrder { id, catch_up } => {
+            resume_dca_order(deps, env, info, id, catch_up)
+        }
+        ExecuteMsg::SkipNextPurchase { id } => skip_next_purchase(deps, env, info, id),
     }
 }
 
@@ -207,16 +504,146 @@ pub fn execute(
 /// * **QueryMsg::Config {}** Returns information about the configuration of the contract in a
 /// [`Config`] object.
 ///
+/// * **QueryMsg::ReferrerStats { referrer }** Returns the referral statistics and claimable
+/// rewards of a referrer in a [`ReferrerStats`] object.
+///
+/// * **QueryMsg::Bot { bot }** Returns information about a registered bot in a [`BotInfo`]
+/// object.
+///
+/// * **QueryMsg::Bots { start_after, limit }** Returns information about the registered bots in a
+/// [`Vec<BotInfo>`] object.
+///
 /// * **QueryMsg::UserConfig {}** Returns information about a specified users configuration set for
 /// DCA purchases in a [`UserConfig`] object.
 ///
 /// * **QueryMsg::UserDcaOrders {}** Returns information about a specified users current DCA orders
-/// set in a [`Vec<DcaInfo>`] object.
+/// set in a [`Vec<DcaQueryInfo>`] object, leaving out paused orders unless `include_paused` is set.
+///
+/// * **QueryMsg::OwnerOf { token_id }** Returns the owner of a DCA order in an
+/// [`OwnerOfResponse`] object.
+///
+/// * **QueryMsg::NftInfo { token_id }** Returns a DCA order in an [`NftInfoResponse`] object.
+///
+/// * **QueryMsg::Tokens { owner, start_after, limit }** Returns the ids of the DCA orders owned by
+/// a user in a [`TokensResponse`] object.
+///
+/// * **QueryMsg::Operators { owner, start_after, limit }** Returns the operators approved by a
+/// user in a [`Vec<OperatorApproval>`] object.
+///
+/// * **QueryMsg::OrderLimits { initial_asset }** Returns the limits that a new or modified DCA
+/// order spending `initial_asset` must satisfy in an [`OrderLimitsResponse`] object.
+///
+/// * **QueryMsg::PurchaseHistory { token_id, start_after, limit }** Returns the fills of a DCA
+/// order in a [`Vec<PurchaseRecord>`] object.
+///
+/// * **QueryMsg::WhitelistedTokens { start_after, limit }** Returns the tokens that can be used in
+/// a hop route in a [`Vec<AssetInfo>`] object.
+///
+/// * **QueryMsg::Vault { vault_id }** Returns information about a vault in a [`VaultInfo`]
+/// object.
+///
+/// * **QueryMsg::Vaults { start_after, limit }** Returns information about the vaults in a
+/// [`Vec<VaultInfo>`] object.
+///
+/// * **QueryMsg::VaultPosition { vault_id, user }** Returns the position of a user in a vault in a
+/// [`VaultPositionResponse`] object.
+///
+/// * **QueryMsg::VaultPurchaseHistory { vault_id, start_after, limit }** Returns the aggregated
+/// purchases of a vault in a [`Vec<VaultPurchaseRecord>`] object.
+///
+/// * **QueryMsg::GlobalStats { start_after, limit }** Returns the statistics of all DCA orders,
+/// broken down per asset pair, in a [`StatsResponse`] object.
+///
+/// * **QueryMsg::UserStats { user }** Returns the statistics of the DCA orders of a user, broken
+/// down per asset pair, in a [`StatsResponse`] object.
+///
+/// * **QueryMsg::OrderPerformance { token_id }** Returns the average cost and current value of the
+/// asset purchased by a DCA order in an [`OrderPerformanceResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&get_config(deps)?),
         QueryMsg::UserConfig { user } => to_binary(&get_user_config(deps, user)?),
-        QueryMsg::UserDcaOrders { user } => to_binary(&get_user_dca_orders(deps, env, user)?),
+        QueryMsg::ReferrerStats { referrer } => to_binary(&get_referrer_stats(deps, referrer)?),
+        QueryMsg::Bot { bot } => to_binary(&get_bot(deps, bot)?),
+        QueryMsg::Bots { start_after, limit } => to_binary(&get_bots(deps, start_after, limit)?),
+        QueryMsg::UserDcaOrders {
+            user,
+            include_paused,
+        } => to_binary(&get_user_dca_orders(deps, env, user, include_paused)?),
+        QueryMsg::OwnerOf { token_id } => to_binary(&get_owner_of(deps, token_id)?),
+        QueryMsg::NftInfo { token_id } => to_binary(&get_nft_info(deps, token_id)?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&get_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::Operators {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&get_operators(deps, owner, start_after, limit)?),
+        QueryMsg::OrderLimits { initial_asset } => {
+            to_binary(&get_order_limits(deps, initial_asset)?)
+        }
+        QueryMsg::PurchaseHistory {
+            token_id,
+            start_after,
+            limit,
+        } => to_binary(&get_purchase_history(deps, token_id, start_after, limit)?),
+        QueryMsg::WhitelistedTokens { start_after, limit } => {
+            to_binary(&get_whitelisted_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::Vault { vault_id } => to_binary(&get_vault(deps, vault_id)?),
+        QueryMsg::Vaults { start_after, limit } => {
+            to_binary(&get_vaults(deps, start_after, limit)?)
+        }
+        QueryMsg::VaultPosition { vault_id, user } => {
+            to_binary(&get_vault_position(deps, vault_id, user)?)
+        }
+        QueryMsg::VaultPurchaseHistory {
+            vault_id,
+            start_after,
+            limit,
+        } => to_binary(&get_vault_purchase_history(
+            deps,
+            vault_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GlobalStats { start_after, limit } => {
+            to_binary(&get_global_stats(deps, start_after, limit)?)
+        }
+        QueryMsg::UserStats { user } => to_binary(&get_user_stats(deps, user)?),
+        QueryMsg::OrderPerformance { token_id } => {
+            to_binary(&get_order_performance(deps, token_id)?)
+        }
+    }
+}
+
+/// ## Description
+/// Handles the replies to the submessages sent by the contract.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `msg` - The [`Reply`] to the submessage.
+///
+/// ## Replies
+/// * **VAULT_PURCHASE_REPLY_ID** Credits the proceeds of a vault purchase to its shares.
+///
+/// * **BATCH_SETTLEMENT_REPLY_ID** Distributes the proceeds of the net imbalance of a batch
+/// settlement.
+///
+/// * **DCA_PURCHASE_REPLY_ID** Records the proceeds of a DCA purchase in the purchase history of
+/// its order.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        VAULT_PURCHASE_REPLY_ID => vault_purchase_reply(deps, env),
+        BATCH_SETTLEMENT_REPLY_ID => batch_settlement_reply(deps, env),
+        DCA_PURCHASE_REPLY_ID => dca_purchase_reply(deps, env),
+        id => Err(StdError::generic_err(format!("Unknown reply id {}", id)).into()),
     }
 }
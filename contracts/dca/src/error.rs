@@ -1,4 +1,5 @@
-use cosmwasm_std::{OverflowError, StdError};
+use astroport_dca::evaluation::Ineligible;
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -61,4 +62,158 @@ pub enum ContractError {
 
     #[error("Initial asset deposited is not divisible by the DCA amount")]
     IndivisibleDeposit {},
+
+    #[error("Protocol fee exceeds the maximum of {max} basis points")]
+    ProtocolFeeTooHigh { max: u16 },
+
+    #[error("Target asset {asset} has more than one protocol fee override")]
+    DuplicateTargetAssetFee { asset: String },
+
+    #[error("Referral share must be between 0 and 1")]
+    InvalidReferralShare {},
+
+    #[error("Users can not refer themselves")]
+    SelfReferral {},
+
+    #[error("There are no referral rewards to claim")]
+    NoReferralRewards {},
+
+    #[error("Bot bond is less than the minimum bond of {min}")]
+    InsufficientBotBond { min: Uint128 },
+
+    #[error("The bot is not registered")]
+    BotNotRegistered {},
+
+    #[error("The bot is unbonding")]
+    BotUnbonding {},
+
+    #[error("The bot is not unbonding")]
+    BotNotUnbonding {},
+
+    #[error("The bot bond can not be claimed until {until}")]
+    BondNotUnbonded { until: u64 },
+
+    #[error("Only registered bots can perform this DCA purchase until {until}")]
+    PriorityWindowActive { until: u64 },
+
+    #[error("Tip volume rate exceeds the maximum of {max}")]
+    TipVolumeRateTooHigh { max: Decimal },
+
+    #[error("Maximum tip is less than the minimum tip of {min}")]
+    MaxTipTooLow { min: Uint128 },
+
+    #[error("Tip of {tip} exceeds the maximum tip per purchase of {max}")]
+    TipCapExceeded { tip: Uint128, max: Uint128 },
+
+    #[error("Dutch auction start tip is less than the minimum tip of {min}")]
+    StartTipTooLow { min: Uint128 },
+
+    #[error("Dutch auction tip must rise from start_tip to max_tip over a non-zero duration")]
+    InvalidDutchAuction {},
+
+    #[error("Cannot migrate from contract {contract}")]
+    InvalidMigrationContract { contract: String },
+
+    #[error("Cannot migrate from version {current} to older version {new}")]
+    MigrationDowngrade { current: String, new: String },
+
+    #[error("Missing migration parameter {name}")]
+    MissingMigrationParameter { name: String },
+
+    #[error("Max spread must be greater than zero and at most {max}")]
+    InvalidMaxSpread { max: Decimal },
+
+    #[error("Max hops must be at least 1 and at most {max}")]
+    InvalidMaxHops { max: u32 },
+
+    #[error("Whitelisted tokens can not be empty")]
+    EmptyWhitelist {},
+
+    #[error("Token {token} is whitelisted more than once")]
+    DuplicateWhitelistedToken { token: String },
+
+    #[error("Token {token} is not whitelisted")]
+    TokenNotWhitelisted { token: String },
+
+    #[error("DCA interval is less than the minimum interval of {min} seconds")]
+    IntervalTooShort { min: u64 },
+
+    #[error("DCA amount is less than the minimum amount of {min}")]
+    DcaAmountTooSmall { min: Uint128 },
+
+    #[error("Initial asset {asset} has more than one minimum DCA amount")]
+    DuplicateMinDcaAmount { asset: String },
+
+    #[error("Users can not have more than {max} DCA orders")]
+    MaxOrdersReached { max: u32 },
+
+    #[error("DCA orders spending a CW20 token can not be transferred")]
+    Cw20OrderNotTransferable {},
+
+    #[error("Max orders per user must be at least 1")]
+    InvalidMaxOrdersPerUser {},
+
+    #[error(
+        "Pinned route must start at initial_asset, end at target_asset and have connected hops"
+    )]
+    InvalidPinnedRoute {},
+
+    #[error("The spread of the pair price exceeds the max spread of order {id}")]
+    BatchSpreadAssertion { id: u64 },
+
+    #[error("Hop route does not match the pinned route of the order")]
+    PinnedRouteMismatch {},
+
+    #[error("Invalid hop route through {token} due to the allowed intermediates of the order")]
+    IntermediateNotAllowed { token: String },
+
+    #[error("Users can not approve themselves as an operator")]
+    CannotApproveSelf {},
+
+    #[error("Operator approval has already expired")]
+    ApprovalExpired {},
+
+    #[error("Hop route does not start at initial_asset")]
+    InitialAssetAssertion {},
+
+    #[error("The vault has no shares taking part in the purchase")]
+    EmptyVault {},
+
+    #[error("The user does not have a position in the vault")]
+    NonexistentVaultPosition {},
+
+    #[error("There are no vault proceeds to claim")]
+    NoVaultProceeds {},
+
+    #[error("The position was suspended from the vault and has to be withdrawn")]
+    SuspendedVaultPosition {},
+
+    #[error("A batch settlement needs orders in both directions between the same assets")]
+    NoOpposingOrders {},
+
+    #[error("Orders in a batch settlement must swap between the same two assets")]
+    BatchAssetMismatch {},
+
+    #[error("Order {id} is included in the batch settlement more than once")]
+    DuplicateBatchOrder { id: u64 },
+
+    #[error("Invalid contract version {version}")]
+    InvalidContractVersion { version: String },
+
+    #[error("DCA order {id} is paused")]
+    OrderPaused { id: u64 },
+
+    #[error("DCA order {id} is not paused")]
+    OrderNotPaused { id: u64 },
+}
+
+impl From<Ineligible> for ContractError {
+    fn from(ineligible: Ineligible) -> Self {
+        match ineligible {
+            Ineligible::TooEarly => ContractError::PurchaseTooEarly {},
+            Ineligible::PriorityWindowActive { until } => {
+                ContractError::PriorityWindowActive { until }
+            }
+        }
+    }
 }
@@ -0,0 +1,502 @@
+use astroport::{
+    asset::{Asset, AssetInfo},
+    router::SwapOperation,
+};
+use astroport_dca::dca::{
+    BotInfo, DcaInfo, OperatorApproval, UserConfig, VaultInfo, VaultPurchaseRecord,
+};
+use cosmwasm_std::{to_vec, Addr, Event, StdResult, Uint128};
+
+use crate::state::VaultPosition;
+
+/// ## Description
+/// Returns a [`String`] for an optional value, which is `none` if it is not set.
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "none".to_string(), |value| value.to_string())
+}
+
+/// ## Description
+/// Returns the attributes describing the full state of `order`, shared by the events that create
+/// or replace an order.
+/// ## Arguments
+/// * `event` - The [`Event`] to add the attributes to.
+///
+/// * `order` - The [`DcaInfo`] of the order after the state transition.
+fn with_order(event: Event, order: &DcaInfo) -> StdResult<Event> {
+    let route_preference = match &order.route_preference {
+        Some(route_preference) => String::from_utf8(to_vec(route_preference)?)?,
+        None => "none".to_string(),
+    };
+
+    Ok(event
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("owner", &order.owner)
+        .add_attribute("initial_asset", order.initial_asset.info.to_string())
+        .add_attribute("target_asset", order.target_asset.to_string())
+        .add_attribute("balance", order.initial_asset.amount)
+        .add_attribute("dca_amount", order.dca_amount)
+        .add_attribute("interval", order.interval.to_string())
+        .add_attribute("last_purchase", order.last_purchase.to_string())
+        .add_attribute("start_time", order.start_time.to_string())
+        .add_attribute("referrer", optional(&order.referrer))
+        .add_attribute("max_hops", optional(&order.max_hops))
+        .add_attribute("max_spread", optional(&order.max_spread))
+        .add_attribute("route_preference", route_preference)
+        .add_attribute("paused_at", optional(&order.paused_at)))
+}
+
+/// ## Description
+/// Returns the route of a purchase spending `initial_asset` through `hops`, as the assets it
+/// passes through separated by `>`.
+/// ## Arguments
+/// * `initial_asset` - The [`AssetInfo`] spent by the purchase.
+///
+/// * `hops` - The [`SwapOperation`]s of the purchase.
+pub fn route(initial_asset: &AssetInfo, hops: &[SwapOperation]) -> String {
+    std::iter::once(initial_asset.to_string())
+        .chain(
+            hops.iter()
+                .map(|hop| hop.get_target_asset_info().to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join(">")
+}
+
+/// ## Description
+/// Returns the `dca_create_order` event describing a new order.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the new order.
+pub fn create_order_event(order: &DcaInfo) -> StdResult<Event> {
+    with_order(Event::new("dca_create_order"), order)
+}
+
+/// ## Description
+/// Returns the `dca_modify_order` event describing the state of an order after it was modified.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the modified order.
+pub fn modify_order_event(order: &DcaInfo) -> StdResult<Event> {
+    with_order(Event::new("dca_modify_order"), order)
+}
+
+/// ## Description
+/// Returns the `dca_cancel_order` event describing an order that was removed, with the `balance`
+/// it had left.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the cancelled order.
+pub fn cancel_order_event(order: &DcaInfo) -> Event {
+    Event::new("dca_cancel_order")
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("owner", &order.owner)
+        .add_attribute("initial_asset", order.initial_asset.info.to_string())
+        .add_attribute("target_asset", order.target_asset.to_string())
+        .add_attribute("balance", order.initial_asset.amount)
+}
+
+/// ## Description
+/// Returns the `dca_transfer_order` event describing an order moving to a new owner.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the order, already owned by the recipient.
+///
+/// * `sender` - The previous owner of the order.
+pub fn transfer_order_event(order: &DcaInfo, sender: &Addr) -> Event {
+    Event::new("dca_transfer_order")
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("sender", sender)
+        .add_attribute("owner", &order.owner)
+}
+
+/// ## Description
+/// Returns the `dca_pause_order` event describing an order that was paused.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the paused order.
+pub fn pause_order_event(order: &DcaInfo) -> Event {
+    Event::new("dca_pause_order")
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("owner", &order.owner)
+        .add_attribute("paused_at", optional(&order.paused_at))
+}
+
+/// ## Description
+/// Returns the `dca_resume_order` event describing an order that was resumed, with the schedule
+/// it resumes with.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the resumed order.
+///
+/// * `catch_up` - Whether the order kept its schedule while it was paused.
+pub fn resume_order_event(order: &DcaInfo, catch_up: bool) -> Event {
+    Event::new("dca_resume_order")
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("owner", &order.owner)
+        .add_attribute("catch_up", catch_up.to_string())
+        .add_attribute("last_purchase", order.last_purchase.to_string())
+        .add_attribute("start_time", order.start_time.to_string())
+        .add_attribute("catch_up_purchases", order.catch_up_purchases.to_string())
+}
+
+/// ## Description
+/// Returns the `dca_skip_purchase` event describing an order whose next purchase was skipped.
+/// ## Arguments
+/// * `order` - The [`DcaInfo`] of the order after skipping the purchase.
+pub fn skip_purchase_event(order: &DcaInfo) -> Event {
+    Event::new("dca_skip_purchase")
+        .add_attribute("order_id", order.id.to_string())
+        .add_attribute("owner", &order.owner)
+        .add_attribute("last_purchase", order.last_purchase.to_string())
+}
+
+/// Describes a DCA purchase of an order for its `dca_purchase` event
+pub struct PurchaseEvent<'a> {
+    /// The order after the purchase
+    pub order: &'a DcaInfo,
+    /// The protocol fee taken from the `dca_amount`
+    pub protocol_fee: Uint128,
+    /// The amount of `initial_asset` swapped, or matched against opposing orders
+    pub swap_amount: Uint128,
+    /// The uusd tip paid by the owner
+    pub tip: Uint128,
+    /// The uusd tip balance of the owner after the purchase
+    pub tip_balance: Uint128,
+    /// The bot that performed the purchase
+    pub bot: &'a Addr,
+}
+
+impl PurchaseEvent<'_> {
+    /// ## Description
+    /// Returns the `dca_purchase` event, spending the `dca_amount` of the order through `route`.
+    /// ## Arguments
+    /// * `route` - The route of the purchase, as returned by [`route`], or `batch` if the order
+    /// was settled in a batch.
+    pub fn into_event(self, route: &str) -> Event {
+        Event::new("dca_purchase")
+            .add_attribute("order_id", self.order.id.to_string())
+            .add_attribute("owner", &self.order.owner)
+            .add_attribute("initial_asset", self.order.initial_asset.info.to_string())
+            .add_attribute("target_asset", self.order.target_asset.to_string())
+            .add_attribute("spent", self.order.dca_amount)
+            .add_attribute("protocol_fee", self.protocol_fee)
+            .add_attribute("swap_amount", self.swap_amount)
+            .add_attribute("route", route)
+            .add_attribute("tip", self.tip)
+            .add_attribute("bot", self.bot)
+            .add_attribute("balance", self.order.initial_asset.amount)
+            .add_attribute("tip_balance", self.tip_balance)
+            .add_attribute("last_purchase", self.order.last_purchase.to_string())
+    }
+}
+
+/// ## Description
+/// Returns the `dca_purchase_settled` event describing the `target_asset` received for a purchase
+/// of an order, once it has been measured.
+/// ## Arguments
+/// * `order_id` - The id of the order.
+///
+/// * `owner` - The owner of the order, who received the proceeds.
+///
+/// * `offer_asset` - The [`Asset`] swapped, or matched against opposing orders.
+///
+/// * `received` - The [`Asset`] received for `offer_asset`.
+///
+/// * `internal_match` - Whether `offer_asset` was matched against opposing orders.
+pub fn purchase_settled_event(
+    order_id: u64,
+    owner: &Addr,
+    offer_asset: &Asset,
+    received: &Asset,
+    internal_match: bool,
+) -> Event {
+    Event::new("dca_purchase_settled")
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("offer_asset", offer_asset.to_string())
+        .add_attribute("received", received.to_string())
+        .add_attribute("internal_match", internal_match.to_string())
+}
+
+/// ## Description
+/// Returns the `dca_vault_purchase` event describing the aggregated purchase of a vault.
+/// ## Arguments
+/// * `vault` - The [`VaultInfo`] of the vault after the purchase.
+///
+/// * `protocol_fee` - The protocol fee taken from the shares of the vault.
+///
+/// * `swap_amount` - The amount of `initial_asset` swapped.
+///
+/// * `route` - The route of the purchase, as returned by [`route`].
+///
+/// * `tip` - The uusd tip paid out of the vault tip balance.
+///
+/// * `bot` - The bot that performed the purchase.
+pub fn vault_purchase_event(
+    vault: &VaultInfo,
+    protocol_fee: Uint128,
+    swap_amount: Uint128,
+    route: &str,
+    tip: Uint128,
+    bot: &Addr,
+) -> Event {
+    Event::new("dca_vault_purchase")
+        .add_attribute("vault_id", vault.id.to_string())
+        .add_attribute("epoch", vault.epoch.to_string())
+        .add_attribute("initial_asset", vault.initial_asset.to_string())
+        .add_attribute("target_asset", vault.target_asset.to_string())
+        .add_attribute("spent", vault.total_shares)
+        .add_attribute("protocol_fee", protocol_fee)
+        .add_attribute("swap_amount", swap_amount)
+        .add_attribute("route", route)
+        .add_attribute("tip", tip)
+        .add_attribute("bot", bot)
+        .add_attribute("tip_balance", vault.tip_balance)
+}
+
+/// ## Description
+/// Returns the `dca_vault_purchase_settled` event describing the `target_asset` received by the
+/// aggregated purchase of a vault.
+/// ## Arguments
+/// * `vault` - The [`VaultInfo`] of the vault.
+///
+/// * `record` - The [`VaultPurchaseRecord`] of the purchase.
+pub fn vault_purchase_settled_event(vault: &VaultInfo, record: &VaultPurchaseRecord) -> Event {
+    Event::new("dca_vault_purchase_settled")
+        .add_attribute("vault_id", vault.id.to_string())
+        .add_attribute("epoch", record.epoch.to_string())
+        .add_attribute("received", record.received.to_string())
+}
+
+/// ## Description
+/// Returns the `dca_deposit_vault` event describing a new position in a vault.
+/// ## Arguments
+/// * `vault` - The [`VaultInfo`] of the vault after the deposit.
+///
+/// * `owner` - The owner of the position.
+///
+/// * `deposit` - The [`Asset`] deposited.
+///
+/// * `position` - The new [`VaultPosition`].
+pub fn deposit_vault_event(
+    vault: &VaultInfo,
+    owner: &Addr,
+    deposit: &Asset,
+    position: &VaultPosition,
+) -> Event {
+    Event::new("dca_deposit_vault")
+        .add_attribute("vault_id", vault.id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("initial_asset", vault.initial_asset.to_string())
+        .add_attribute("target_asset", vault.target_asset.to_string())
+        .add_attribute("interval", vault.interval.to_string())
+        .add_attribute("max_spread", optional(&vault.max_spread))
+        .add_attribute("deposit", deposit.amount)
+        .add_attribute("shares", position.shares)
+        .add_attribute("start_epoch", position.start_epoch.to_string())
+        .add_attribute("end_epoch", position.end_epoch.to_string())
+        .add_attribute("total_shares", vault.total_shares)
+}
+
+/// ## Description
+/// Returns the `dca_withdraw_vault` event describing a position withdrawn from a vault.
+/// ## Arguments
+/// * `vault` - The [`VaultInfo`] of the vault after the withdrawal.
+///
+/// * `owner` - The owner of the position.
+///
+/// * `remaining` - The [`Asset`] of `initial_asset` returned.
+///
+/// * `proceeds` - The [`Asset`] of `target_asset` returned.
+///
+/// * `tip_refund` - The uusd tip deposit returned.
+pub fn withdraw_vault_event(
+    vault: &VaultInfo,
+    owner: &Addr,
+    remaining: &Asset,
+    proceeds: &Asset,
+    tip_refund: Uint128,
+) -> Event {
+    Event::new("dca_withdraw_vault")
+        .add_attribute("vault_id", vault.id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("remaining", remaining.amount)
+        .add_attribute("proceeds", proceeds.amount)
+        .add_attribute("tip_refund", tip_refund)
+        .add_attribute("total_shares", vault.total_shares)
+        .add_attribute("tip_balance", vault.tip_balance)
+}
+
+/// ## Description
+/// Returns the `dca_claim_vault_proceeds` event describing the proceeds claimed by a position.
+/// ## Arguments
+/// * `vault_id` - The id of the vault.
+///
+/// * `owner` - The owner of the position.
+///
+/// * `proceeds` - The [`Asset`] of `target_asset` claimed.
+///
+/// * `position` - The [`VaultPosition`] after the claim.
+pub fn claim_vault_proceeds_event(
+    vault_id: u64,
+    owner: &Addr,
+    proceeds: &Asset,
+    position: &VaultPosition,
+) -> Event {
+    Event::new("dca_claim_vault_proceeds")
+        .add_attribute("vault_id", vault_id.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("proceeds", proceeds.amount)
+        .add_attribute("claimed_epoch", position.claimed_epoch.to_string())
+}
+
+/// ## Description
+/// Returns the `dca_claim_referral_rewards` event describing the rewards claimed by a referrer.
+/// ## Arguments
+/// * `referrer` - The referrer who claimed the rewards.
+///
+/// * `rewards` - The [`Asset`]s claimed.
+pub fn claim_referral_rewards_event(referrer: &Addr, rewards: &[Asset]) -> Event {
+    Event::new("dca_claim_referral_rewards")
+        .add_attribute("referrer", referrer)
+        .add_attribute(
+            "rewards",
+            rewards
+                .iter()
+                .map(|reward| reward.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
+/// ## Description
+/// Returns the event of type `ty` describing the state of a bot after a change.
+/// ## Arguments
+/// * `ty` - The type of the event.
+///
+/// * `bot` - The [`BotInfo`] of the bot after the change.
+fn bot_event(ty: &str, bot: &BotInfo) -> Event {
+    Event::new(ty)
+        .add_attribute("bot", &bot.address)
+        .add_attribute("bond", bot.bond)
+        .add_attribute("unbonding_until", optional(&bot.unbonding_until))
+        .add_attribute("whitelisted", bot.whitelisted.to_string())
+}
+
+/// ## Description
+/// Returns the `dca_register_bot` event describing a bot after it registered or topped up its
+/// bond.
+pub fn register_bot_event(bot: &BotInfo) -> Event {
+    bot_event("dca_register_bot", bot)
+}
+
+/// ## Description
+/// Returns the `dca_unregister_bot` event describing a bot that started unbonding.
+pub fn unregister_bot_event(bot: &BotInfo) -> Event {
+    bot_event("dca_unregister_bot", bot)
+}
+
+/// ## Description
+/// Returns the `dca_slash_bot` event describing a bot after `slashed` was taken from its bond.
+pub fn slash_bot_event(bot: &BotInfo, slashed: Uint128) -> Event {
+    bot_event("dca_slash_bot", bot).add_attribute("slashed", slashed)
+}
+
+/// ## Description
+/// Returns the `dca_whitelist_bot` event describing a bot that was added to or removed from the
+/// bot whitelist.
+pub fn whitelist_bot_event(bot: &BotInfo) -> Event {
+    bot_event("dca_whitelist_bot", bot)
+}
+
+/// ## Description
+/// Returns the `dca_claim_bot_bond` event describing a bot that was removed, returning its `bond`.
+pub fn claim_bot_bond_event(bot: &BotInfo) -> Event {
+    Event::new("dca_claim_bot_bond")
+        .add_attribute("bot", &bot.address)
+        .add_attribute("bond_returned", bot.bond)
+}
+
+/// ## Description
+/// Returns the `dca_add_whitelisted_tokens` event describing the tokens added to the whitelist.
+pub fn add_whitelisted_tokens_event(added: &[String]) -> Event {
+    Event::new("dca_add_whitelisted_tokens").add_attribute("added", added.join(","))
+}
+
+/// ## Description
+/// Returns the `dca_remove_whitelisted_tokens` event describing the tokens removed from the
+/// whitelist.
+pub fn remove_whitelisted_tokens_event(removed: &[String]) -> Event {
+    Event::new("dca_remove_whitelisted_tokens").add_attribute("removed", removed.join(","))
+}
+
+/// ## Description
+/// Returns the `dca_update_user_config` event describing the configuration of a user after it
+/// was updated.
+/// ## Arguments
+/// * `owner` - The user who updated their configuration.
+///
+/// * `config` - The [`UserConfig`] of the user after the update.
+pub fn update_user_config_event(owner: &Addr, config: &UserConfig) -> StdResult<Event> {
+    Ok(Event::new("dca_update_user_config")
+        .add_attribute("owner", owner)
+        .add_attribute("max_hops", optional(&config.max_hops))
+        .add_attribute("max_spread", optional(&config.max_spread))
+        .add_attribute(
+            "max_tip_per_purchase",
+            optional(&config.max_tip_per_purchase),
+        )
+        .add_attribute("tip_mode", String::from_utf8(to_vec(&config.tip_mode)?)?)
+        .add_attribute("tip_balance", config.tip_balance))
+}
+
+/// ## Description
+/// Returns the `dca_approve_operator` event describing an operator approved by `owner`.
+/// ## Arguments
+/// * `owner` - The user who approved the operator.
+///
+/// * `approval` - The [`OperatorApproval`] of the operator.
+pub fn approve_operator_event(owner: &Addr, approval: &OperatorApproval) -> StdResult<Event> {
+    Ok(Event::new("dca_approve_operator")
+        .add_attribute("owner", owner)
+        .add_attribute("operator", &approval.operator)
+        .add_attribute(
+            "permissions",
+            String::from_utf8(to_vec(&approval.permissions)?)?,
+        )
+        .add_attribute("expires", approval.expires.to_string()))
+}
+
+/// ## Description
+/// Returns the `dca_revoke_operator` event describing an operator revoked by `owner`.
+pub fn revoke_operator_event(owner: &Addr, operator: &Addr) -> Event {
+    Event::new("dca_revoke_operator")
+        .add_attribute("owner", owner)
+        .add_attribute("operator", operator)
+}
+
+/// ## Description
+/// Returns the event describing a change of the tip balance of `owner`, which is
+/// `dca_deposit_tip` or `dca_withdraw_tip`.
+/// ## Arguments
+/// * `ty` - The type of the event.
+///
+/// * `owner` - The user whose tip balance changed.
+///
+/// * `amount` - The uusd amount deposited or withdrawn.
+///
+/// * `tip_balance` - The uusd tip balance of the user afterwards.
+fn tip_event(ty: &str, owner: &Addr, amount: Uint128, tip_balance: Uint128) -> Event {
+    Event::new(ty)
+        .add_attribute("owner", owner)
+        .add_attribute("amount", amount)
+        .add_attribute("tip_balance", tip_balance)
+}
+
+/// ## Description
+/// Returns the `dca_deposit_tip` event describing a tip deposit.
+pub fn deposit_tip_event(owner: &Addr, amount: Uint128, tip_balance: Uint128) -> Event {
+    tip_event("dca_deposit_tip", owner, amount, tip_balance)
+}
+
+/// ## Description
+/// Returns the `dca_withdraw_tip` event describing a tip withdrawal.
+pub fn withdraw_tip_event(owner: &Addr, amount: Uint128, tip_balance: Uint128) -> Event {
+    tip_event("dca_withdraw_tip", owner, amount, tip_balance)
+}
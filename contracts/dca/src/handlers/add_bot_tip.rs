@@ -1,10 +1,8 @@
 use astroport::asset::UUSD_DENOM;
+use astroport_dca::dca::UserConfig;
 use cosmwasm_std::{attr, DepsMut, MessageInfo, Response, StdResult};
 
-use crate::{
-    error::ContractError,
-    state::{UserConfig, USER_CONFIG},
-};
+use crate::{error::ContractError, events::deposit_tip_event, state::USER_CONFIG};
 
 /// ## Description
 /// Adds a tip to the contract for a users DCA purchases.
@@ -24,7 +22,7 @@ pub fn add_bot_tip(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
         .amount;
 
     // update user tip in contract
-    USER_CONFIG.update(
+    let config = USER_CONFIG.update(
         deps.storage,
         &info.sender,
         |config| -> StdResult<UserConfig> {
@@ -36,26 +34,24 @@ pub fn add_bot_tip(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
         },
     )?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "add_bot_tip"),
-        attr("tip_amount", amount),
-    ]))
+    Ok(Response::new()
+        .add_event(deposit_tip_event(&info.sender, amount, config.tip_balance))
+        .add_attributes(vec![
+            attr("action", "add_bot_tip"),
+            attr("tip_amount", amount),
+        ]))
 }
 
 #[cfg(test)]
 mod tests {
-    use astroport_dca::dca::ExecuteMsg;
+    use astroport_dca::dca::{ExecuteMsg, UserConfig};
     use cosmwasm_std::{
         attr, coin,
         testing::{mock_dependencies, mock_env, mock_info},
-        Addr, Response,
+        Addr, Event, Response,
     };
 
-    use crate::{
-        contract::execute,
-        error::ContractError,
-        state::{UserConfig, USER_CONFIG},
-    };
+    use crate::{contract::execute, error::ContractError, state::USER_CONFIG};
 
     #[test]
     fn does_add_bot_tip() {
@@ -70,10 +66,17 @@ mod tests {
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(
             res,
-            Response::new().add_attributes(vec![
-                attr("action", "add_bot_tip"),
-                attr("tip_amount", tip_sent.amount)
-            ])
+            Response::new()
+                .add_event(
+                    Event::new("dca_deposit_tip")
+                        .add_attribute("owner", "creator")
+                        .add_attribute("amount", "10000")
+                        .add_attribute("tip_balance", "10000")
+                )
+                .add_attributes(vec![
+                    attr("action", "add_bot_tip"),
+                    attr("tip_amount", tip_sent.amount)
+                ])
         );
 
         // check that user tip balance was added
@@ -0,0 +1,61 @@
+use astroport::asset::UUSD_DENOM;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    state::{VAULTS, VAULT_POSITIONS},
+};
+
+/// ## Description
+/// Adds a tip to the position of the sender in a vault, which pays for its share of the tips of
+/// the purchases of the vault.
+///
+/// A position that was suspended because its tip deposit ran out can not be topped up, and has to
+/// be withdrawn instead.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from a participant of the vault, which contains a uusd tip to add
+/// to their position.
+///
+/// * `vault_id` - The id of the vault to add the tip to.
+pub fn add_vault_tip(
+    deps: DepsMut,
+    info: MessageInfo,
+    vault_id: u64,
+) -> Result<Response, ContractError> {
+    let amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == UUSD_DENOM)
+        .ok_or(ContractError::InvalidZeroAmount {})?
+        .amount;
+
+    let mut position = VAULT_POSITIONS
+        .may_load(deps.storage, (U64Key::new(vault_id), &info.sender))?
+        .ok_or(ContractError::NonexistentVaultPosition {})?;
+    if position.suspended {
+        return Err(ContractError::SuspendedVaultPosition {});
+    }
+    position.tip_deposit = position.tip_deposit.checked_add(amount)?;
+    VAULT_POSITIONS.save(
+        deps.storage,
+        (U64Key::new(vault_id), &info.sender),
+        &position,
+    )?;
+
+    // the vault tip balance is the sum of the tip deposits of its positions
+    let mut vault = VAULTS.load(deps.storage, U64Key::new(vault_id))?;
+    vault.tip_balance = vault.tip_balance.checked_add(amount)?;
+    VAULTS.save(deps.storage, U64Key::new(vault_id), &vault)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_vault_tip"),
+        attr("vault_id", vault_id.to_string()),
+        attr("tip_amount", amount),
+    ]))
+}
@@ -0,0 +1,126 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    assert_owner::assert_owner, error::ContractError, events::add_whitelisted_tokens_event,
+    state::WHITELISTED_TOKENS, validation::validate_asset_info,
+};
+
+/// ## Description
+/// Adds tokens to the whitelist that can be used in a hop route for DCA purchases.
+///
+/// Tokens that are already whitelisted are ignored, so that the same message can safely be
+/// executed more than once.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the factory contract owner.
+///
+/// * `tokens` - The [`Vec<AssetInfo>`] of tokens to whitelist.
+pub fn add_whitelisted_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let mut added = Vec::new();
+    for token in tokens {
+        let token = validate_asset_info(deps.api, token)?;
+
+        if !WHITELISTED_TOKENS.has(deps.storage, token.as_bytes()) {
+            WHITELISTED_TOKENS.save(deps.storage, token.as_bytes(), &token)?;
+            added.push(token.to_string());
+        }
+    }
+
+    Ok(Response::new()
+        .add_event(add_whitelisted_tokens_event(&added))
+        .add_attributes(vec![
+            attr("action", "add_whitelisted_tokens"),
+            attr("added", added.join(",")),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::AssetInfo;
+    use astroport_dca::dca::{ExecuteMsg, QueryMsg};
+    use cosmwasm_std::{
+        attr, from_binary,
+        testing::{mock_env, mock_info},
+        Addr,
+    };
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+    };
+
+    #[test]
+    fn does_add_tokens_idempotently() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+        let msg = ExecuteMsg::AddWhitelistedTokens {
+            tokens: vec![token.clone()],
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("added", "token")));
+
+        // does not add the token twice
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("added", "")));
+
+        let tokens: Vec<AssetInfo> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::WhitelistedTokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                token,
+                AssetInfo::NativeToken {
+                    denom: "uusd".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn does_require_owner() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &[]),
+            ExecuteMsg::AddWhitelistedTokens { tokens: vec![] },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+}
@@ -0,0 +1,240 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::{OperatorApproval, OperatorPermission};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw20::Expiration;
+
+use crate::{error::ContractError, events::approve_operator_event, state::OPERATORS};
+
+/// ## Description
+/// Approves an operator to manage the DCA orders of the sender, replacing any previous approval
+/// of the operator.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the user who wants to approve the operator.
+///
+/// * `operator` - The address of the operator as a [`String`].
+///
+/// * `expires` - The [`Expiration`] of the approval, which never expires if not specified.
+///
+/// * `permissions` - The [`Vec<OperatorPermission>`] of actions the operator can perform.
+pub fn approve_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+    permissions: Vec<OperatorPermission>,
+) -> Result<Response, ContractError> {
+    let operator = addr_validate_to_lower(deps.api, &operator)?;
+    if operator == info.sender {
+        return Err(ContractError::CannotApproveSelf {});
+    }
+
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    let approval = OperatorApproval {
+        operator: operator.clone(),
+        permissions,
+        expires,
+    };
+    OPERATORS.save(deps.storage, (&info.sender, &operator), &approval)?;
+
+    Ok(Response::new()
+        .add_event(approve_operator_event(&info.sender, &approval)?)
+        .add_attributes(vec![
+            attr("action", "approve_operator"),
+            attr("owner", info.sender),
+            attr("operator", operator),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, OperatorApproval, OperatorPermission, QueryMsg};
+    use cosmwasm_std::{
+        coin, from_binary,
+        testing::{mock_env, mock_info},
+        Addr, BankMsg, CosmosMsg, DepsMut, Uint128,
+    };
+    use cw20::Expiration;
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract, uusd},
+        state::user_orders,
+    };
+
+    fn approve(deps: DepsMut, expires: Option<Expiration>, permissions: Vec<OperatorPermission>) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("user", &[]),
+            ExecuteMsg::ApproveOperator {
+                operator: "manager".to_string(),
+                expires,
+                permissions,
+            },
+        )
+        .unwrap();
+    }
+
+    fn create_order_as_manager(deps: DepsMut) -> Result<(), ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("manager", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: Some("user".to_string()),
+            },
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn does_manage_orders_for_owner() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        approve(
+            deps.as_mut(),
+            None,
+            vec![
+                OperatorPermission::CreateOrder,
+                OperatorPermission::CancelOrder,
+            ],
+        );
+
+        create_order_as_manager(deps.as_mut()).unwrap();
+        assert_eq!(
+            user_orders(&deps.storage, &Addr::unchecked("user"))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // does refund the owner rather than the operator
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            ExecuteMsg::CancelDcaOrder {
+                initial_asset: uusd(),
+                owner: Some("user".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![coin(10_000, "uusd")],
+            })
+        );
+
+        let operators: Vec<OperatorApproval> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Operators {
+                    owner: "user".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(operators[0].operator, Addr::unchecked("manager"));
+    }
+
+    #[test]
+    fn does_require_permission() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        // does not allow operators that were never approved
+        assert_eq!(
+            create_order_as_manager(deps.as_mut()).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        approve(deps.as_mut(), None, vec![OperatorPermission::WithdrawTip]);
+        assert_eq!(
+            create_order_as_manager(deps.as_mut()).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        // does not allow expired approvals
+        let expires = Expiration::AtHeight(mock_env().block.height + 1);
+        approve(
+            deps.as_mut(),
+            Some(expires),
+            vec![OperatorPermission::WithdrawTip],
+        );
+
+        let withdraw = |deps: DepsMut, height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            execute(
+                deps,
+                env,
+                mock_info("manager", &[]),
+                ExecuteMsg::Withdraw {
+                    tip: Uint128::zero(),
+                    owner: Some("user".to_string()),
+                },
+            )
+        };
+
+        assert!(withdraw(deps.as_mut(), mock_env().block.height).is_ok());
+        assert_eq!(
+            withdraw(deps.as_mut(), mock_env().block.height + 1).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+    }
+
+    #[test]
+    fn does_revoke_operator() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        approve(deps.as_mut(), None, vec![OperatorPermission::CreateOrder]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &[]),
+            ExecuteMsg::RevokeOperator {
+                operator: "manager".to_string(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            create_order_as_manager(deps.as_mut()).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+    }
+}
@@ -1,59 +1,83 @@
 use astroport::asset::AssetInfo;
-use astroport_dca::dca::DcaInfo;
-use cosmwasm_std::{attr, BankMsg, Coin, DepsMut, MessageInfo, Response};
+use astroport_dca::dca::OperatorPermission;
+use cosmwasm_std::{attr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
+use cw_storage_plus::U64Key;
 
-use crate::{error::ContractError, state::USER_DCA};
+use crate::{
+    error::ContractError,
+    events::cancel_order_event,
+    resolve_owner::resolve_owner,
+    state::{dca_orders, update_stats, user_orders},
+};
 
 /// ## Description
 /// Cancels a users DCA purchase so that it will no longer be fulfilled.
 ///
-/// Returns the `initial_asset` back to the user if it was a native token.
+/// Returns the `initial_asset` back to the owner of the order if it was a native token, even if the
+/// order is cancelled by an operator.
 ///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
 /// ## Arguments
 /// * `deps` - A [`DepsMut`] that contains the dependencies.
 ///
-/// * `info` - A [`MessageInfo`] from the sender who wants to cancel their order.
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the sender who wants to cancel their order, or an operator
+/// approved to cancel orders for the owner.
 ///
 /// * `initial_asset` The [`AssetInfo`] which the user wants to cancel the DCA order for.
+///
+/// * `owner` - The address of the user who owns the order as a [`String`], if the sender is an
+/// approved operator.
 pub fn cancel_dca_order(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     initial_asset: AssetInfo,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
+    let owner = resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        owner,
+        OperatorPermission::CancelOrder,
+    )?;
+
     let mut funds = Vec::new();
 
     // remove order from user dca's, and add any native token funds for `initial_asset` into the `funds`.
-    USER_DCA.update(
+    let removed_order = user_orders(deps.storage, &owner)?
+        .into_iter()
+        .find(|order| order.initial_asset.info == initial_asset)
+        .ok_or(ContractError::NonexistentDca {})?;
+
+    if let AssetInfo::NativeToken { denom } = &removed_order.initial_asset.info {
+        funds.push(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![Coin {
+                amount: removed_order.initial_asset.amount,
+                denom: denom.clone(),
+            }],
+        })
+    }
+
+    dca_orders().remove(deps.storage, U64Key::new(removed_order.id))?;
+
+    update_stats(
         deps.storage,
-        &info.sender,
-        |orders| -> Result<Vec<DcaInfo>, ContractError> {
-            let mut orders = orders.ok_or(ContractError::NonexistentDca {})?;
-
-            let order_position = orders
-                .iter()
-                .position(|order| order.initial_asset.info == initial_asset)
-                .ok_or(ContractError::NonexistentDca {})?;
-
-            let removed_order = &orders[order_position];
-            if let AssetInfo::NativeToken { denom } = &removed_order.initial_asset.info {
-                funds.push(BankMsg::Send {
-                    to_address: info.sender.to_string(),
-                    amount: vec![Coin {
-                        amount: removed_order.initial_asset.amount,
-                        denom: denom.clone(),
-                    }],
-                })
-            }
-
-            orders.remove(order_position);
-
-            Ok(orders)
-        },
+        &owner,
+        &removed_order.initial_asset.info,
+        &removed_order.target_asset,
+        |stats| stats.remove_active_order(),
     )?;
 
     Ok(Response::new()
         .add_messages(funds)
-        .add_attributes(vec![attr("action", "cancel_dca_order")]))
+        .add_event(cancel_order_event(&removed_order))
+        .add_attributes(vec![
+            attr("action", "cancel_dca_order"),
+            attr("token_id", removed_order.id.to_string()),
+        ]))
 }
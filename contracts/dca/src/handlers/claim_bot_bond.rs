@@ -0,0 +1,54 @@
+use astroport::asset::UUSD_DENOM;
+use cosmwasm_std::{attr, coins, BankMsg, DepsMut, Env, MessageInfo, Response};
+
+use crate::{error::ContractError, events::claim_bot_bond_event, state::BOTS};
+
+/// ## Description
+/// Claims the remaining bond of an unregistering bot once its unbonding period has passed,
+/// removing the bot from the registry.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot claiming its bond.
+pub fn claim_bot_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let bot = BOTS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::BotNotRegistered {})?;
+
+    let unbonding_until = bot
+        .unbonding_until
+        .ok_or(ContractError::BotNotUnbonding {})?;
+    if env.block.time.seconds() < unbonding_until {
+        return Err(ContractError::BondNotUnbonded {
+            until: unbonding_until,
+        });
+    }
+
+    BOTS.remove(deps.storage, &info.sender);
+
+    let mut response = Response::new()
+        .add_event(claim_bot_bond_event(&bot))
+        .add_attributes(vec![
+            attr("action", "claim_bot_bond"),
+            attr("bot", info.sender.clone()),
+            attr("bond_returned", bot.bond),
+        ]);
+
+    if !bot.bond.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(bot.bond.u128(), UUSD_DENOM),
+        });
+    }
+
+    Ok(response)
+}
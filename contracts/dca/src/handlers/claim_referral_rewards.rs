@@ -0,0 +1,147 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, MessageInfo, Response, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{error::ContractError, events::claim_referral_rewards_event, state::REFERRER_STATS};
+
+/// ## Description
+/// Claims all the referral rewards that have been credited to the sender.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the referrer who wants to claim their rewards.
+pub fn claim_referral_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut stats = REFERRER_STATS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    let rewards: Vec<_> = stats
+        .claimable
+        .drain(..)
+        .filter(|reward| !reward.amount.is_zero())
+        .collect();
+
+    if rewards.is_empty() {
+        return Err(ContractError::NoReferralRewards {});
+    }
+
+    REFERRER_STATS.save(deps.storage, &info.sender, &stats)?;
+
+    let messages = rewards
+        .iter()
+        .map(|reward| {
+            Ok(match &reward.info {
+                AssetInfo::NativeToken { denom } => BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: vec![Coin {
+                        amount: reward.amount,
+                        denom: denom.clone(),
+                    }],
+                }
+                .into(),
+                AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: info.sender.to_string(),
+                        amount: reward.amount,
+                    })?,
+                }
+                .into(),
+            })
+        })
+        .collect::<Result<Vec<CosmosMsg>, ContractError>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(claim_referral_rewards_event(&info.sender, &rewards))
+        .add_attributes(vec![
+            attr("action", "claim_referral_rewards"),
+            attr(
+                "rewards",
+                rewards
+                    .iter()
+                    .map(|reward| reward.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, ReferrerStats};
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr, BankMsg, CosmosMsg, Uint128,
+    };
+
+    use crate::{contract::execute, error::ContractError, state::REFERRER_STATS};
+
+    #[test]
+    fn does_claim_rewards() {
+        let mut deps = mock_dependencies(&[]);
+
+        let reward = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(500u128),
+        };
+        REFERRER_STATS
+            .save(
+                deps.as_mut().storage,
+                &Addr::unchecked("frontend"),
+                &ReferrerStats {
+                    orders_referred: 1,
+                    purchases_referred: 1,
+                    claimable: vec![reward.clone()],
+                    total_earned: vec![reward.clone()],
+                },
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("frontend", &[]),
+            ExecuteMsg::ClaimReferralRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "frontend".to_string(),
+                amount: vec![coin(500, "uusd")],
+            })
+        );
+
+        // does clear the claimable rewards but keep the total earned
+        let stats = REFERRER_STATS
+            .load(&deps.storage, &Addr::unchecked("frontend"))
+            .unwrap();
+        assert!(stats.claimable.is_empty());
+        assert_eq!(stats.total_earned, vec![reward]);
+    }
+
+    #[test]
+    fn does_require_rewards() {
+        let mut deps = mock_dependencies(&[]);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("frontend", &[]),
+            ExecuteMsg::ClaimReferralRewards {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoReferralRewards {});
+    }
+}
@@ -0,0 +1,61 @@
+use astroport::asset::Asset;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::claim_vault_proceeds_event,
+    send_asset::send_asset,
+    state::{claimable_vault_proceeds, VAULTS, VAULT_POSITIONS},
+};
+
+/// ## Description
+/// Claims the `target_asset` purchased for the position of the sender in a vault.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the participant who wants to claim their proceeds.
+///
+/// * `vault_id` - The id of the vault to claim the proceeds of.
+pub fn claim_vault_proceeds(
+    deps: DepsMut,
+    info: MessageInfo,
+    vault_id: u64,
+) -> Result<Response, ContractError> {
+    let vault = VAULTS.load(deps.storage, U64Key::new(vault_id))?;
+    let mut position = VAULT_POSITIONS
+        .may_load(deps.storage, (U64Key::new(vault_id), &info.sender))?
+        .ok_or(ContractError::NonexistentVaultPosition {})?;
+
+    let proceeds = Asset {
+        info: vault.target_asset.clone(),
+        amount: claimable_vault_proceeds(deps.storage, &vault, &position)?,
+    };
+    if proceeds.amount.is_zero() {
+        return Err(ContractError::NoVaultProceeds {});
+    }
+
+    position.claimed_epoch = position.last_epoch(&vault);
+    VAULT_POSITIONS.save(
+        deps.storage,
+        (U64Key::new(vault_id), &info.sender),
+        &position,
+    )?;
+
+    Ok(Response::new()
+        .add_message(send_asset(&proceeds, &info.sender)?)
+        .add_event(claim_vault_proceeds_event(
+            vault_id,
+            &info.sender,
+            &proceeds,
+            &position,
+        ))
+        .add_attributes(vec![
+            attr("action", "claim_vault_proceeds"),
+            attr("vault_id", vault_id.to_string()),
+            attr("proceeds", proceeds.to_string()),
+        ]))
+}
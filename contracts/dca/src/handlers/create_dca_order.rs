@@ -1,8 +1,42 @@
-use astroport::asset::{Asset, AssetInfo};
-use astroport_dca::dca::DcaInfo;
-use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdError, Uint128};
+use astroport::asset::{addr_validate_to_lower, Asset, AssetInfo};
+use astroport_dca::dca::{DcaInfo, OperatorPermission, ReferrerStats, RoutePreference};
+use cosmwasm_std::{
+    attr, Decimal, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
 
-use crate::{error::ContractError, get_token_allowance::get_token_allowance, state::USER_DCA};
+use crate::{
+    error::ContractError,
+    events::create_order_event,
+    get_token_allowance::get_token_allowance,
+    resolve_owner::resolve_owner,
+    state::{next_order_id, save_order, update_stats, user_orders, CONFIG, REFERRER_STATS},
+    validation::{
+        validate_max_hops, validate_max_spread, validate_order_limits, validate_route_preference,
+    },
+};
+
+/// Stores the parameters of a new dca order
+pub struct CreateDcaOrderParameters {
+    /// The [`Asset`] that is being spent to purchase DCA orders.
+    pub initial_asset: Asset,
+    /// The [`AssetInfo`] that is being purchased with `initial_asset`.
+    pub target_asset: AssetInfo,
+    /// The time in seconds between DCA purchases.
+    pub interval: u64,
+    /// A [`Uint128`] amount of `initial_asset` to spend each DCA purchase.
+    pub dca_amount: Uint128,
+    /// The address of the referrer of the order as a [`String`], if any.
+    pub referrer: Option<String>,
+    /// An override for the maximum amount of hops of each DCA purchase, if any.
+    pub max_hops: Option<u32>,
+    /// A [`Decimal`] override for the maximum spread of each DCA purchase, if any.
+    pub max_spread: Option<Decimal>,
+    /// The [`RoutePreference`] restricting the hop routes of each DCA purchase, if any.
+    pub route_preference: Option<RoutePreference>,
+    /// The address of the user to create the order for as a [`String`], if the sender is an
+    /// approved operator.
+    pub owner: Option<String>,
+}
 
 /// ## Description
 /// Creates a new DCA order for a user where the `target_asset` will be purchased with `dca_amount`
@@ -15,32 +49,49 @@ use crate::{error::ContractError, get_token_allowance::get_token_allowance, stat
 ///
 /// * `env` - The [`Env`] of the blockchain.
 ///
-/// * `info` - A [`MessageInfo`] from the sender who wants to create their order, containing the
-/// [`AssetInfo::NativeToken`] if the `initial_asset` is a native token.
-///
-/// * `initial_asset` - The [`Asset`] that is being spent to purchase DCA orders. If the asset is a
-/// Token (non-native), the contact will need to have the allowance for the DCA contract set to the
-/// `initial_asset.amount`.
-///
-/// * `target_asset` - The [`AssetInfo`] that is being purchased with `initial_asset`.
-///
-/// * `interval` - The time in seconds between DCA purchases.
+/// * `info` - A [`MessageInfo`] from the sender who wants to create their order, or an operator
+/// approved to create orders for the owner, containing the [`AssetInfo::NativeToken`] if the
+/// `initial_asset` is a native token.
 ///
-/// * `dca_amount` - A [`Uint128`] representing the amount of `initial_asset` to spend each DCA
-/// purchase.
+/// * `order_details` - The [`CreateDcaOrderParameters`] of the new order. If the `initial_asset`
+/// is a Token (non-native), the contact will need to have the allowance for the DCA contract set to
+/// the `initial_asset.amount`.
 pub fn create_dca_order(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    initial_asset: Asset,
-    target_asset: AssetInfo,
-    interval: u64,
-    dca_amount: Uint128,
+    order_details: CreateDcaOrderParameters,
 ) -> Result<Response, ContractError> {
+    let CreateDcaOrderParameters {
+        initial_asset,
+        target_asset,
+        interval,
+        dca_amount,
+        referrer,
+        max_hops,
+        max_spread,
+        route_preference,
+        owner,
+    } = order_details;
+
+    let owner = resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        owner,
+        OperatorPermission::CreateOrder,
+    )?;
+    let config = CONFIG.load(deps.storage)?;
+
     // check that user has not previously created dca strategy with this initial_asset
-    let mut orders = USER_DCA
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default();
+    let orders = user_orders(deps.storage, &owner)?;
+
+    // check that the user has not reached the maximum amount of orders
+    if orders.len() >= config.max_orders_per_user as usize {
+        return Err(ContractError::MaxOrdersReached {
+            max: config.max_orders_per_user,
+        });
+    }
 
     if orders
         .iter()
@@ -54,6 +105,20 @@ pub fn create_dca_order(
         return Err(ContractError::DuplicateAsset {});
     }
 
+    // check that the interval and dca_amount are within the governance set limits
+    validate_order_limits(&config, &initial_asset.info, interval, dca_amount)?;
+
+    // check that the order overrides are within the protocol defined bounds
+    if let Some(max_hops) = max_hops {
+        validate_max_hops(max_hops)?;
+    }
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+    }
+    if let Some(route_preference) = &route_preference {
+        validate_route_preference(route_preference, &initial_asset.info, &target_asset)?;
+    }
+
     // check that dca_amount is less than initial_asset.amount
     if dca_amount > initial_asset.amount {
         return Err(ContractError::DepositTooSmall {});
@@ -75,29 +140,192 @@ pub fn create_dca_order(
     match &initial_asset.info {
         AssetInfo::NativeToken { .. } => initial_asset.assert_sent_native_token_balance(&info)?,
         AssetInfo::Token { contract_addr } => {
-            let allowance = get_token_allowance(&deps.as_ref(), &env, &info.sender, contract_addr)?;
+            let allowance = get_token_allowance(&deps.as_ref(), &env, &owner, contract_addr)?;
             if allowance != initial_asset.amount {
                 return Err(ContractError::InvalidTokenDeposit {});
             }
         }
     }
 
+    // validate the referrer and record the referral
+    let referrer = referrer
+        .map(|referrer| addr_validate_to_lower(deps.api, &referrer))
+        .transpose()?;
+
+    if let Some(referrer) = &referrer {
+        if referrer == &owner {
+            return Err(ContractError::SelfReferral {});
+        }
+
+        REFERRER_STATS.update(deps.storage, referrer, |stats| -> StdResult<_> {
+            let mut stats: ReferrerStats = stats.unwrap_or_default();
+            stats.orders_referred += 1;
+            Ok(stats)
+        })?;
+    }
+
     // store dca order
-    orders.push(DcaInfo {
+    let id = next_order_id(deps.storage)?;
+    let order = DcaInfo {
+        id,
+        owner: owner.clone(),
         initial_asset: initial_asset.clone(),
         target_asset: target_asset.clone(),
         interval,
         last_purchase: 0,
+        start_time: env.block.time.seconds(),
         dca_amount,
-    });
+        referrer: referrer.clone(),
+        max_hops,
+        max_spread,
+        route_preference,
+        paused_at: None,
+        catch_up_purchases: 0,
+    };
+    save_order(deps.storage, &order)?;
+
+    update_stats(
+        deps.storage,
+        &owner,
+        &initial_asset.info,
+        &target_asset,
+        |stats| {
+            stats.orders_created += 1;
+            stats.active_orders += 1;
+            Ok(())
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(create_order_event(&order)?)
+        .add_attributes(vec![
+            attr("action", "create_dca_order"),
+            attr("order_id", id.to_string()),
+            attr("token_id", id.to_string()),
+            attr("owner", owner),
+            attr("initial_asset", initial_asset.to_string()),
+            attr("target_asset", target_asset.to_string()),
+            attr("interval", interval.to_string()),
+            attr("dca_amount", dca_amount),
+            attr(
+                "referrer",
+                referrer.map_or_else(|| "none".to_string(), |referrer| referrer.to_string()),
+            ),
+        ]))
+}
 
-    USER_DCA.save(deps.storage, &info.sender, &orders)?;
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, MinDcaAmount, OrderLimitsResponse, QueryMsg};
+    use cosmwasm_std::{
+        coin, from_binary,
+        testing::{mock_env, mock_info},
+        DepsMut, Response, Uint128,
+    };
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract, uusd},
+        state::CONFIG,
+    };
+
+    fn set_order_limits(deps: DepsMut) {
+        CONFIG
+            .update(deps.storage, |mut config| -> Result<_, ContractError> {
+                config.min_interval = 60;
+                config.min_dca_amounts = vec![MinDcaAmount {
+                    initial_asset: uusd(),
+                    amount: Uint128::from(1_000u128),
+                }];
+                config.max_orders_per_user = 1;
+                Ok(config)
+            })
+            .unwrap();
+    }
+
+    fn create_order(
+        deps: DepsMut,
+        denom: &str,
+        interval: u64,
+        dca_amount: u128,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("user", &[coin(10_000, denom)]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: denom.to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval,
+                dca_amount: Uint128::from(dca_amount),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+    }
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "create_dca_order"),
-        attr("initial_asset", initial_asset.to_string()),
-        attr("target_asset", target_asset.to_string()),
-        attr("interval", interval.to_string()),
-        attr("dca_amount", dca_amount),
-    ]))
+    #[test]
+    fn does_enforce_order_limits() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        set_order_limits(deps.as_mut());
+
+        assert_eq!(
+            create_order(deps.as_mut(), "uusd", 59, 1_000).unwrap_err(),
+            ContractError::IntervalTooShort { min: 60 }
+        );
+        assert_eq!(
+            create_order(deps.as_mut(), "uusd", 60, 500).unwrap_err(),
+            ContractError::DcaAmountTooSmall {
+                min: Uint128::from(1_000u128)
+            }
+        );
+
+        create_order(deps.as_mut(), "uusd", 60, 1_000).unwrap();
+
+        // does limit the amount of orders of a user
+        assert_eq!(
+            create_order(deps.as_mut(), "ukrw", 60, 1_000).unwrap_err(),
+            ContractError::MaxOrdersReached { max: 1 }
+        );
+    }
+
+    #[test]
+    fn does_query_order_limits() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        set_order_limits(deps.as_mut());
+
+        let limits: OrderLimitsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OrderLimits {
+                    initial_asset: uusd(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            limits,
+            OrderLimitsResponse {
+                min_interval: 60,
+                min_dca_amount: Uint128::from(1_000u128),
+                max_orders_per_user: 1,
+            }
+        );
+    }
 }
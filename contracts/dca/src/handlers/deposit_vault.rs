@@ -0,0 +1,175 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{
+    attr, to_binary, Decimal, DepsMut, Env, MessageInfo, Response, StdError, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::deposit_vault_event,
+    state::{
+        load_or_create_vault, VaultPosition, CONFIG, USER_CONFIG, VAULTS, VAULT_EXPIRING_SHARES,
+        VAULT_POSITIONS,
+    },
+    validation::{validate_max_spread, validate_order_limits},
+};
+
+/// Stores the parameters of a deposit into a vault
+pub struct DepositVaultParameters {
+    /// The [`Asset`] being deposited.
+    pub initial_asset: Asset,
+    /// The [`AssetInfo`] being purchased by the vault.
+    pub target_asset: AssetInfo,
+    /// The time in seconds between the purchases of the vault.
+    pub interval: u64,
+    /// The [`Uint128`] amount of `initial_asset` spent in each purchase.
+    pub dca_amount: Uint128,
+    /// A [`Decimal`] maximum spread of the purchases of the vault, if any.
+    pub max_spread: Option<Decimal>,
+}
+
+/// ## Description
+/// Deposits `initial_asset` into the vault purchasing `target_asset` every `interval`, creating the
+/// vault if it does not exist yet.
+///
+/// The position of the sender receives `dca_amount` shares, which take part in the purchases of
+/// the vault until `initial_asset` is used up. Vaults with a different `max_spread` are kept
+/// apart, so that every participant of a vault has agreed to its maximum spread, which defaults
+/// to the `max_spread` of the user configuration of the sender.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the sender who wants to deposit into the vault, containing the
+/// [`AssetInfo::NativeToken`] if the `initial_asset` is a native token.
+///
+/// * `deposit` - The [`DepositVaultParameters`] of the deposit. If the `initial_asset` is a Token
+/// (non-native), the sender will need to have set the allowance for the DCA contract to the
+/// `initial_asset.amount`.
+pub fn deposit_vault(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    deposit: DepositVaultParameters,
+) -> Result<Response, ContractError> {
+    let DepositVaultParameters {
+        initial_asset,
+        target_asset,
+        interval,
+        dca_amount,
+        max_spread,
+    } = deposit;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that assets are not duplicate
+    if initial_asset.info == target_asset {
+        return Err(ContractError::DuplicateAsset {});
+    }
+
+    // check that the interval and dca_amount are within the governance set limits
+    validate_order_limits(&config, &initial_asset.info, interval, dca_amount)?;
+
+    // check that dca_amount is less than initial_asset.amount
+    if dca_amount > initial_asset.amount {
+        return Err(ContractError::DepositTooSmall {});
+    }
+
+    // check that initial_asset.amount is divisible by dca_amount
+    let purchases = initial_asset
+        .amount
+        .checked_div(dca_amount)
+        .map_err(|e| StdError::DivideByZero { source: e })?;
+    if purchases * dca_amount != initial_asset.amount {
+        return Err(ContractError::IndivisibleDeposit {});
+    }
+
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+    }
+
+    // the vault purchases with the max_spread of the deposit, then of the user, then of the contract
+    let max_spread = match max_spread {
+        Some(max_spread) => Some(max_spread),
+        None => USER_CONFIG
+            .may_load(deps.storage, &info.sender)?
+            .and_then(|user_config| user_config.max_spread),
+    };
+
+    let mut vault = load_or_create_vault(
+        deps.storage,
+        &initial_asset.info,
+        &target_asset,
+        interval,
+        max_spread,
+    )?;
+
+    // check that the user does not already have a position in the vault
+    if VAULT_POSITIONS.has(deps.storage, (U64Key::new(vault.id), &info.sender)) {
+        return Err(ContractError::AlreadyDeposited {});
+    }
+
+    // the vault holds the deposits of its participants, so cw20 tokens are transferred in now
+    let mut response = Response::new();
+    match &initial_asset.info {
+        AssetInfo::NativeToken { .. } => initial_asset.assert_sent_native_token_balance(&info)?,
+        AssetInfo::Token { contract_addr } => {
+            response = response.add_message(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: initial_asset.amount,
+                })?,
+            })
+        }
+    }
+
+    // the position takes part in the next `purchases` purchases of the vault
+    let position = VaultPosition {
+        shares: dca_amount,
+        start_epoch: vault.epoch,
+        end_epoch: vault.epoch + purchases.u128() as u64,
+        claimed_epoch: vault.epoch,
+        tip_deposit: Uint128::zero(),
+        refund: Uint128::zero(),
+        suspended: false,
+    };
+
+    vault.total_shares = vault.total_shares.checked_add(dca_amount)?;
+    VAULT_EXPIRING_SHARES.update(
+        deps.storage,
+        (U64Key::new(vault.id), U64Key::new(position.end_epoch + 1)),
+        |shares| -> Result<_, ContractError> {
+            Ok(shares.unwrap_or_default().checked_add(dca_amount)?)
+        },
+    )?;
+
+    VAULTS.save(deps.storage, U64Key::new(vault.id), &vault)?;
+    VAULT_POSITIONS.save(
+        deps.storage,
+        (U64Key::new(vault.id), &info.sender),
+        &position,
+    )?;
+
+    Ok(response
+        .add_event(deposit_vault_event(
+            &vault,
+            &info.sender,
+            &initial_asset,
+            &position,
+        ))
+        .add_attributes(vec![
+            attr("action", "deposit_vault"),
+            attr("vault_id", vault.id.to_string()),
+            attr("owner", info.sender),
+            attr("initial_asset", initial_asset.to_string()),
+            attr("shares", dca_amount),
+        ]))
+}
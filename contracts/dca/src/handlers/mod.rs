@@ -1,17 +1,57 @@
 mod add_bot_tip;
+mod add_vault_tip;
+mod add_whitelisted_tokens;
+mod approve_operator;
 mod cancel_dca_order;
+mod claim_bot_bond;
+mod claim_referral_rewards;
+mod claim_vault_proceeds;
 mod create_dca_order;
+mod deposit_vault;
 mod modify_dca_order;
+mod pause_dca_order;
+mod perform_batch_settlement;
 mod perform_dca_purchase;
+mod perform_vault_purchase;
+mod register_bot;
+mod remove_whitelisted_tokens;
+mod resume_dca_order;
+mod revoke_operator;
+mod skip_next_purchase;
+mod slash_bot;
+mod transfer_nft;
+mod unregister_bot;
 mod update_config;
 mod update_user_config;
+mod whitelist_bot;
 mod withdraw;
+mod withdraw_vault;
 
 pub use add_bot_tip::add_bot_tip;
+pub use add_vault_tip::add_vault_tip;
+pub use add_whitelisted_tokens::add_whitelisted_tokens;
+pub use approve_operator::approve_operator;
 pub use cancel_dca_order::cancel_dca_order;
-pub use create_dca_order::create_dca_order;
+pub use claim_bot_bond::claim_bot_bond;
+pub use claim_referral_rewards::claim_referral_rewards;
+pub use claim_vault_proceeds::claim_vault_proceeds;
+pub use create_dca_order::{create_dca_order, CreateDcaOrderParameters};
+pub use deposit_vault::{deposit_vault, DepositVaultParameters};
 pub use modify_dca_order::{modify_dca_order, ModifyDcaOrderParameters};
-pub use perform_dca_purchase::perform_dca_purchase;
-pub use update_config::update_config;
+pub use pause_dca_order::pause_dca_order;
+pub use perform_batch_settlement::{batch_settlement_reply, perform_batch_settlement};
+pub use perform_dca_purchase::{dca_purchase_reply, perform_dca_purchase};
+pub use perform_vault_purchase::{perform_vault_purchase, vault_purchase_reply};
+pub use register_bot::register_bot;
+pub use remove_whitelisted_tokens::remove_whitelisted_tokens;
+pub use resume_dca_order::resume_dca_order;
+pub use revoke_operator::revoke_operator;
+pub use skip_next_purchase::skip_next_purchase;
+pub use slash_bot::slash_bot;
+pub use transfer_nft::transfer_nft;
+pub use unregister_bot::unregister_bot;
+pub use update_config::{update_config, UpdateConfigParameters};
 pub use update_user_config::update_user_config;
+pub use whitelist_bot::whitelist_bot;
 pub use withdraw::withdraw;
+pub use withdraw_vault::withdraw_vault;
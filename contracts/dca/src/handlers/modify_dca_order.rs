@@ -1,7 +1,17 @@
 use astroport::asset::{Asset, AssetInfo};
-use cosmwasm_std::{attr, coins, BankMsg, DepsMut, Env, MessageInfo, Response, Uint128};
+use astroport_dca::dca::{OperatorPermission, RoutePreference};
+use cosmwasm_std::{attr, coins, BankMsg, Decimal, DepsMut, Env, MessageInfo, Response, Uint128};
 
-use crate::{error::ContractError, get_token_allowance::get_token_allowance, state::USER_DCA};
+use crate::{
+    error::ContractError,
+    events::modify_order_event,
+    get_token_allowance::get_token_allowance,
+    resolve_owner::resolve_owner,
+    state::{save_order, update_stats, user_orders, CONFIG},
+    validation::{
+        validate_max_hops, validate_max_spread, validate_order_limits, validate_route_preference,
+    },
+};
 
 /// Stores a modified dca order new parameters
 pub struct ModifyDcaOrderParameters {
@@ -17,6 +27,15 @@ pub struct ModifyDcaOrderParameters {
     pub new_dca_amount: Uint128,
     /// A bool flag that determines if the order's last purchase time should be reset.
     pub should_reset_purchase_time: bool,
+    /// The new override for the maximum amount of hops of each DCA purchase, if any.
+    pub new_max_hops: Option<u32>,
+    /// The new [`Decimal`] override for the maximum spread of each DCA purchase, if any.
+    pub new_max_spread: Option<Decimal>,
+    /// The new [`RoutePreference`] restricting the hop routes of each DCA purchase, if any.
+    pub new_route_preference: Option<RoutePreference>,
+    /// The address of the user who owns the order as a [`String`], if the sender is an approved
+    /// operator.
+    pub owner: Option<String>,
 }
 
 /// ## Description
@@ -26,7 +45,8 @@ pub struct ModifyDcaOrderParameters {
 /// If the user increases the size of their order, they must allocate the correct amount of new
 /// assets to the contract.
 ///
-/// If the user decreases the size of their order, they will be refunded with the difference.
+/// If the user decreases the size of their order, they will be refunded with the difference. Refunds
+/// are always sent to the owner of the order, even if it is modified by an operator.
 ///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
@@ -35,8 +55,9 @@ pub struct ModifyDcaOrderParameters {
 ///
 /// * `env` - The [`Env`] of the blockchain.
 ///
-/// * `info` - A [`MessageInfo`] from the sender who wants to modify their order, containing the
-/// [`AssetInfo::NativeToken`] if the DCA order is being increased in size.
+/// * `info` - A [`MessageInfo`] from the sender who wants to modify their order, or an operator
+/// approved to modify orders for the owner, containing the [`AssetInfo::NativeToken`] if the DCA
+/// order is being increased in size.
 ///
 /// * `order_details` - The [`ModifyDcaOrderParameters`] details about the old and new DCA order
 /// parameters.
@@ -53,18 +74,50 @@ pub fn modify_dca_order(
         new_interval,
         new_dca_amount,
         should_reset_purchase_time,
+        new_max_hops,
+        new_max_spread,
+        new_route_preference,
+        owner,
     } = order_details;
 
-    let mut orders = USER_DCA
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default();
+    let owner = resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        owner,
+        OperatorPermission::ModifyOrder,
+    )?;
 
     // check that old_initial_asset.info exists
-    let order = orders
-        .iter_mut()
+    let mut order = user_orders(deps.storage, &owner)?
+        .into_iter()
         .find(|order| order.initial_asset.info == old_initial_asset)
         .ok_or(ContractError::NonexistentDca {})?;
 
+    // check that the new interval and dca_amount are within the governance set limits
+    let config = CONFIG.load(deps.storage)?;
+    validate_order_limits(
+        &config,
+        &new_initial_asset.info,
+        new_interval,
+        new_dca_amount,
+    )?;
+
+    // check that the new order overrides are within the protocol defined bounds
+    if let Some(new_max_hops) = new_max_hops {
+        validate_max_hops(new_max_hops)?;
+    }
+    if let Some(new_max_spread) = new_max_spread {
+        validate_max_spread(new_max_spread)?;
+    }
+    if let Some(new_route_preference) = &new_route_preference {
+        validate_route_preference(
+            new_route_preference,
+            &new_initial_asset.info,
+            &new_target_asset,
+        )?;
+    }
+
     let should_refund = order.initial_asset.amount > new_initial_asset.amount;
     let asset_difference = Asset {
         info: new_initial_asset.info.clone(),
@@ -94,7 +147,7 @@ pub fn modify_dca_order(
                 }
                 AssetInfo::Token { contract_addr } => {
                     let allowance =
-                        get_token_allowance(&deps.as_ref(), &env, &info.sender, contract_addr)?;
+                        get_token_allowance(&deps.as_ref(), &env, &owner, contract_addr)?;
                     if allowance != new_initial_asset.amount {
                         return Err(ContractError::InvalidTokenDeposit {});
                     }
@@ -104,16 +157,16 @@ pub fn modify_dca_order(
             // we need to refund the user with the difference if it is a native token
             if let AssetInfo::NativeToken { denom } = &new_initial_asset.info {
                 messages.push(BankMsg::Send {
-                    to_address: info.sender.to_string(),
+                    to_address: owner.to_string(),
                     amount: coins(asset_difference.amount.u128(), denom),
                 })
             }
         }
     } else {
         // they are different assets, so we will return the old_initial_asset if it is a native token
-        if let AssetInfo::NativeToken { denom } = &new_initial_asset.info {
+        if let AssetInfo::NativeToken { denom } = &old_initial_asset {
             messages.push(BankMsg::Send {
-                to_address: info.sender.to_string(),
+                to_address: owner.to_string(),
                 amount: coins(order.initial_asset.amount.u128(), denom),
             })
         }
@@ -124,8 +177,7 @@ pub fn modify_dca_order(
                 new_initial_asset.assert_sent_native_token_balance(&info)?
             }
             AssetInfo::Token { contract_addr } => {
-                let allowance =
-                    get_token_allowance(&deps.as_ref(), &env, &info.sender, contract_addr)?;
+                let allowance = get_token_allowance(&deps.as_ref(), &env, &owner, contract_addr)?;
                 if allowance != new_initial_asset.amount {
                     return Err(ContractError::InvalidTokenDeposit {});
                 }
@@ -133,24 +185,148 @@ pub fn modify_dca_order(
         }
     }
 
+    // move the order to the statistics of its new pair
+    if old_initial_asset != new_initial_asset.info || order.target_asset != new_target_asset {
+        update_stats(
+            deps.storage,
+            &owner,
+            &old_initial_asset,
+            &order.target_asset,
+            |stats| stats.remove_active_order(),
+        )?;
+        update_stats(
+            deps.storage,
+            &owner,
+            &new_initial_asset.info,
+            &new_target_asset,
+            |stats| {
+                stats.active_orders += 1;
+                Ok(())
+            },
+        )?;
+    }
+
     // update order
     order.initial_asset = new_initial_asset.clone();
     order.target_asset = new_target_asset.clone();
     order.interval = new_interval;
     order.dca_amount = new_dca_amount;
+    order.max_hops = new_max_hops;
+    order.max_spread = new_max_spread;
+    order.route_preference = new_route_preference;
 
     if should_reset_purchase_time {
         order.last_purchase = 0;
+        order.start_time = env.block.time.seconds();
+        order.catch_up_purchases = 0;
     }
 
-    USER_DCA.save(deps.storage, &info.sender, &orders)?;
+    save_order(deps.storage, &order)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(modify_order_event(&order)?)
+        .add_attributes(vec![
+            attr("action", "modify_dca_order"),
+            attr("old_initial_asset", old_initial_asset.to_string()),
+            attr("new_initial_asset", new_initial_asset.to_string()),
+            attr("new_target_asset", new_target_asset.to_string()),
+            attr("new_interval", new_interval.to_string()),
+            attr("new_dca_amount", new_dca_amount),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::Asset;
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{
+        coin, coins,
+        testing::{mock_env, mock_info},
+        BankMsg, CosmosMsg, Uint128,
+    };
+
+    use crate::{
+        contract::execute,
+        mock_querier::{mock_dependencies_with_owner, native, setup_contract},
+        state::GLOBAL_STATS,
+    };
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "modify_dca_order"),
-        attr("old_initial_asset", old_initial_asset.to_string()),
-        attr("new_initial_asset", new_initial_asset.to_string()),
-        attr("new_target_asset", new_target_asset.to_string()),
-        attr("new_interval", new_interval.to_string()),
-        attr("new_dca_amount", new_dca_amount),
-    ]))
+    #[test]
+    fn does_refund_old_asset() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: native("uusd"),
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: native("uluna"),
+                interval: 100,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        // switching the order to uluna returns the deposited uusd
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &[coin(5_000, "uluna")]),
+            ExecuteMsg::ModifyDcaOrder {
+                old_initial_asset: native("uusd"),
+                new_initial_asset: Asset {
+                    info: native("uluna"),
+                    amount: Uint128::from(5_000u128),
+                },
+                new_target_asset: native("uusd"),
+                new_interval: 100,
+                new_dca_amount: Uint128::from(1_000u128),
+                should_reset_purchase_time: false,
+                new_max_hops: None,
+                new_max_spread: None,
+                new_route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(10_000, "uusd"),
+            })]
+        );
+
+        // the order is active for its new pair only
+        let stats = |initial_asset: &str, target_asset: &str| {
+            GLOBAL_STATS
+                .load(
+                    &deps.storage,
+                    (
+                        native(initial_asset).as_bytes(),
+                        native(target_asset).as_bytes(),
+                    ),
+                )
+                .unwrap()
+        };
+        assert_eq!(stats("uusd", "uluna").orders_created, 1);
+        assert_eq!(stats("uusd", "uluna").active_orders, 0);
+        assert_eq!(stats("uluna", "uusd").orders_created, 0);
+        assert_eq!(stats("uluna", "uusd").active_orders, 1);
+    }
 }
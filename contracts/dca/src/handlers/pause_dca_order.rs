@@ -0,0 +1,158 @@
+use astroport_dca::dca::OperatorPermission;
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::pause_order_event,
+    resolve_owner::resolve_owner,
+    state::{dca_orders, save_order},
+};
+
+/// ## Description
+/// Pauses a DCA order, so that it is not purchased until it is resumed. The deposit of the order
+/// stays in the contract while it is paused.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the owner of the order, or an operator approved to modify
+/// orders for the owner.
+///
+/// * `id` - The id of the order.
+pub fn pause_dca_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut order = dca_orders().load(deps.storage, U64Key::new(id))?;
+    resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        Some(order.owner.to_string()),
+        OperatorPermission::ModifyOrder,
+    )?;
+
+    if order.paused_at.is_some() {
+        return Err(ContractError::OrderPaused { id });
+    }
+
+    order.paused_at = Some(env.block.time.seconds());
+    save_order(deps.storage, &order)?;
+
+    Ok(Response::new()
+        .add_event(pause_order_event(&order))
+        .add_attributes(vec![
+            attr("action", "pause_dca_order"),
+            attr("order_id", id.to_string()),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::SwapOperation,
+    };
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_env, mock_info},
+        Uint128,
+    };
+    use cw_storage_plus::U64Key;
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::dca_orders,
+    };
+
+    #[test]
+    fn does_pause_order() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        let pause = ExecuteMsg::PauseDcaOrder { id: 1 };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob", &[]),
+                pause.clone()
+            )
+            .unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            pause.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            dca_orders()
+                .load(&deps.storage, U64Key::new(1))
+                .unwrap()
+                .paused_at,
+            Some(mock_env().block.time.seconds())
+        );
+
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), pause).unwrap_err(),
+            ContractError::OrderPaused { id: 1 }
+        );
+
+        // paused orders cannot be purchased
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bot", &[]),
+                ExecuteMsg::PerformDcaPurchase {
+                    user: "alice".to_string(),
+                    hops: vec![SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "uluna".to_string(),
+                    }],
+                },
+            )
+            .unwrap_err(),
+            ContractError::OrderPaused { id: 1 }
+        );
+    }
+}
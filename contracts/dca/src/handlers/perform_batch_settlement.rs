@@ -0,0 +1,1331 @@
+use astroport::{
+    asset::{Asset, AssetInfo, UUSD_DENOM},
+    pair::SimulationResponse,
+    querier::{query_pair_info, simulate},
+    router::SwapOperation,
+};
+use astroport_dca::{
+    dca::{Config, DcaInfo, ReferralSource, ReferrerStats},
+    evaluation::{check_order_eligibility, purchase_amounts},
+};
+use cosmwasm_std::{
+    attr, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Event, MessageInfo,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::{purchase_settled_event, PurchaseEvent},
+    router_swap::router_swap_message,
+    send_asset::send_asset,
+    state::{
+        add_to_cost_basis, dca_orders, record_purchase, save_order, update_stats,
+        PendingBatchSettlement, PendingFill, BATCH_SETTLEMENT_REPLY_ID, BOTS, CONFIG,
+        PENDING_BATCH_SETTLEMENT, REFERRER_STATS, USER_CONFIG,
+    },
+    tip::calculate_tip,
+    validation::{validate_route_assets, validate_route_for_order},
+};
+
+/// Stores the orders of a batch settlement spending the same asset
+struct Side {
+    /// The asset spent by the orders
+    asset: AssetInfo,
+    /// The fills of the orders, with the amount spent after the protocol fee
+    fills: Vec<PendingFill>,
+    /// The messages pulling the cw20 funds of the orders into the contract
+    pull_messages: Vec<CosmosMsg>,
+}
+
+/// Stores a DCA order taking part in a batch settlement
+struct BatchOrder {
+    /// The order before the purchase, which prices the tip
+    order: DcaInfo,
+    /// The order after the purchase
+    purchased: DcaInfo,
+    /// Whether the order spends the asset of side a
+    is_side_a: bool,
+    /// The protocol fee taken from the `dca_amount`
+    protocol_fee: Uint128,
+    /// The amount of `initial_asset` settled after the protocol fee
+    swap_amount: Uint128,
+    /// The maximum amount of hops of the purchase of the order
+    max_hops: u32,
+    /// The maximum spread of the purchase of the order
+    max_spread: Decimal,
+}
+
+/// Stores the tip charged for a purchase in a batch settlement
+struct TipCharge {
+    /// The tip paid by the owner of the order
+    tip: Uint128,
+    /// The part of the tip paid to the bot
+    bot_tip: Uint128,
+    /// The tip balance of the owner after the purchase
+    tip_balance: Uint128,
+}
+
+impl Side {
+    fn new(asset: AssetInfo) -> Self {
+        Side {
+            asset,
+            fills: vec![],
+            pull_messages: vec![],
+        }
+    }
+
+    fn total(&self) -> StdResult<Uint128> {
+        total_offer(&self.fills)
+    }
+}
+
+/// ## Description
+/// Settles a batch of due DCA orders between the same two assets in opposite directions.
+///
+/// The opposing orders are matched against each other at the current simulated price of the
+/// Astroport pair of the assets, as long as swapping each side through the pair and the amount
+/// each order receives from the match would stay within the `max_spread` of the order. Only the
+/// net imbalance is swapped through the router using the `hops` specified, which are validated
+/// for each order routed through it as for a DCA purchase, with the strictest `max_spread` among
+/// them. The rounding dust of the pro rata distributions is sent to the fee collector.
+///
+/// Each order pays its protocol fee as usual, and the tip of a purchase through `hops` if it is
+/// routed through the router, or of a single hop purchase if it is matched in full. Every fill is
+/// recorded in the purchase history of the order.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot who is settling the batch, who will be rewarded with
+/// the uusd tips of the orders.
+///
+/// * `order_ids` - The ids of the orders to settle.
+///
+/// * `hops` - A [`Vec<SwapOperation>`] of the hop operations to complete in the swap of the net
+/// imbalance, which is ignored if the orders match exactly.
+pub fn perform_batch_settlement(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_ids: Vec<u64>,
+    hops: Vec<SwapOperation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    // registered bots with a sufficient bond that is not unbonding have priority when an order
+    // becomes due
+    let is_priority_bot = matches!(
+        BOTS.may_load(deps.storage, &info.sender)?,
+        Some(bot) if config.has_bot_priority(&bot)
+    );
+
+    let mut orders: Vec<DcaInfo> = Vec::with_capacity(order_ids.len());
+    for (i, id) in order_ids.iter().enumerate() {
+        if order_ids[..i].contains(id) {
+            return Err(ContractError::DuplicateBatchOrder { id: *id });
+        }
+
+        orders.push(dca_orders().load(deps.storage, U64Key::new(*id))?);
+    }
+
+    // every order must swap between the assets of the first order, in either direction
+    let (mut side_a, mut side_b) = match orders.first() {
+        Some(order) => (
+            Side::new(order.initial_asset.info.clone()),
+            Side::new(order.target_asset.clone()),
+        ),
+        None => return Err(ContractError::NoOpposingOrders {}),
+    };
+
+    let mut is_side_a: Vec<bool> = Vec::with_capacity(orders.len());
+    for order in &orders {
+        if order.initial_asset.info == side_a.asset && order.target_asset == side_b.asset {
+            is_side_a.push(true);
+        } else if order.initial_asset.info == side_b.asset && order.target_asset == side_a.asset {
+            is_side_a.push(false);
+        } else {
+            return Err(ContractError::BatchAssetMismatch {});
+        }
+
+        if order.paused_at.is_some() {
+            return Err(ContractError::OrderPaused { id: order.id });
+        }
+
+        // check that the order is due, and that the sender has priority if it only recently
+        // became due
+        check_order_eligibility(order, config.bot_priority_window, is_priority_bot, now)?;
+    }
+
+    if is_side_a.iter().all(|is_a| *is_a) || !is_side_a.contains(&true) {
+        return Err(ContractError::NoOpposingOrders {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut batch_orders: Vec<BatchOrder> = Vec::with_capacity(orders.len());
+
+    for (order, is_a) in orders.into_iter().zip(is_side_a) {
+        let side = if is_a { &mut side_a } else { &mut side_b };
+
+        // the purchase of each order is subject to the limits of the order, user or contract
+        let user_config = USER_CONFIG
+            .may_load(deps.storage, &order.owner)?
+            .unwrap_or_default();
+        let max_hops = config.max_hops_for(&user_config, &order);
+        let max_spread = config.max_spread_for(&user_config, &order);
+
+        // subtract dca_amount from order, update last_purchase time and count off a purchase made
+        // up for, keeping the order as it was before the purchase to price its tip
+        let mut purchased = order.clone();
+        purchased.initial_asset.amount = purchased
+            .initial_asset
+            .amount
+            .checked_sub(purchased.dca_amount)
+            .map_err(|_| ContractError::InsufficientBalance {})?;
+        purchased.last_purchase = now;
+        purchased.catch_up_purchases = purchased.catch_up_purchases.saturating_sub(1);
+        save_order(deps.storage, &purchased)?;
+
+        update_stats(
+            deps.storage,
+            &order.owner,
+            &order.initial_asset.info,
+            &order.target_asset,
+            |stats| {
+                stats.purchases += 1;
+                stats.volume = stats.volume.checked_add(order.dca_amount)?;
+                Ok(())
+            },
+        )?;
+
+        // cw20 orders are funded from the allowance of the owner, while native orders are
+        // already held by the contract
+        if let AssetInfo::Token { contract_addr } = &order.initial_asset.info {
+            side.pull_messages.push(
+                WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: order.owner.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: order.dca_amount,
+                    })?,
+                }
+                .into(),
+            );
+        }
+
+        // take the protocol fee out of the dca_amount, settling only the remainder
+        let fee_bps = config.protocol_fee_bps_for(&order.owner, &order.target_asset);
+        let amounts = purchase_amounts(
+            order.dca_amount,
+            fee_bps,
+            match (&order.referrer, &config.referral_source) {
+                (Some(_), ReferralSource::ProtocolFee) => Some(config.referral_share),
+                _ => None,
+            },
+        )?;
+        if let Some(referrer) = &order.referrer {
+            credit_referrer(deps.storage, referrer, &side.asset, amounts.referral_fee)?;
+        }
+
+        // the protocol fee counts towards the cost of the target_asset purchased by the order
+        add_to_cost_basis(
+            deps.storage,
+            order.id,
+            &Asset {
+                info: side.asset.clone(),
+                amount: amounts.protocol_fee,
+            },
+            &Asset {
+                info: order.target_asset.clone(),
+                amount: Uint128::zero(),
+            },
+        )?;
+
+        let collector_fee = amounts.collector_fee;
+        if !collector_fee.is_zero() {
+            messages.push(send_asset(
+                &Asset {
+                    info: side.asset.clone(),
+                    amount: collector_fee,
+                },
+                &config.fee_collector,
+            )?);
+        }
+
+        side.fills.push(PendingFill {
+            order_id: order.id,
+            owner: order.owner.clone(),
+            offer_amount: amounts.swap_amount,
+        });
+
+        batch_orders.push(BatchOrder {
+            order,
+            purchased,
+            is_side_a: is_a,
+            protocol_fee: amounts.protocol_fee,
+            swap_amount: amounts.swap_amount,
+            max_hops,
+            max_spread,
+        });
+    }
+
+    let total_a = side_a.total()?;
+    let total_b = side_b.total()?;
+
+    // value side a at the simulated price of the pair, before spread and commission
+    let pair = query_pair_info(
+        &deps.querier,
+        config.factory_addr.clone(),
+        &[side_a.asset.clone(), side_b.asset.clone()],
+    )?;
+    let simulation_a = simulate(
+        &deps.querier,
+        pair.contract_addr.clone(),
+        &Asset {
+            info: side_a.asset.clone(),
+            amount: total_a,
+        },
+    )?;
+    let simulation_b = simulate(
+        &deps.querier,
+        pair.contract_addr,
+        &Asset {
+            info: side_b.asset.clone(),
+            amount: total_b,
+        },
+    )?;
+    let value_a = simulation_a
+        .return_amount
+        .checked_add(simulation_a.spread_amount)?
+        .checked_add(simulation_a.commission_amount)?;
+    if value_a.is_zero() {
+        return Err(StdError::generic_err("Pair simulation returned no value").into());
+    }
+
+    // the orders are only matched at the pair price if swapping their side through the pair
+    // would stay within their max_spread, as the router checks for a swap
+    let spread_a = simulation_spread(&simulation_a)?;
+    let spread_b = simulation_spread(&simulation_b)?;
+    for batch_order in &batch_orders {
+        let spread = if batch_order.is_side_a {
+            spread_a
+        } else {
+            spread_b
+        };
+        if spread > batch_order.max_spread {
+            return Err(ContractError::BatchSpreadAssertion {
+                id: batch_order.order.id,
+            });
+        }
+    }
+
+    // the side worth more is matched against the whole other side, leaving a net imbalance, with
+    // the price of the excess side in the asset of the other side
+    let (excess, matched, excess_matched, price) = if value_a >= total_b {
+        (
+            side_a,
+            side_b,
+            total_b.multiply_ratio(total_a, value_a),
+            (value_a, total_a),
+        )
+    } else {
+        (side_b, side_a, value_a, (total_a, value_a))
+    };
+    let total_excess = excess.total()?;
+    let total_matched = matched.total()?;
+    let max_spread_of = |order_id: u64| {
+        batch_orders
+            .iter()
+            .find(|batch_order| batch_order.order.id == order_id)
+            .map_or(config.max_spread, |batch_order| batch_order.max_spread)
+    };
+
+    // match each order of the excess side pro rata, routing the remainder through the router
+    let mut net_fills: Vec<PendingFill> = vec![];
+    let mut settled_events: Vec<Event> = vec![];
+    let mut matched_amount = Uint128::zero();
+    let mut excess_received = Uint128::zero();
+    for fill in &excess.fills {
+        let fill_matched = excess_matched.multiply_ratio(fill.offer_amount, total_excess);
+        let received = total_matched.multiply_ratio(fill.offer_amount, total_excess);
+        matched_amount = matched_amount.checked_add(fill_matched)?;
+        excess_received = excess_received.checked_add(received)?;
+
+        assert_match_price(
+            fill.order_id,
+            max_spread_of(fill.order_id),
+            fill_matched,
+            received,
+            price,
+        )?;
+        record_internal_fill(
+            deps.storage,
+            fill,
+            now,
+            Asset {
+                info: excess.asset.clone(),
+                amount: fill_matched,
+            },
+            Asset {
+                info: matched.asset.clone(),
+                amount: received,
+            },
+            &mut messages,
+            &mut settled_events,
+        )?;
+
+        let remainder = fill.offer_amount.checked_sub(fill_matched)?;
+        if !remainder.is_zero() {
+            net_fills.push(PendingFill {
+                offer_amount: remainder,
+                ..fill.clone()
+            });
+        }
+    }
+
+    // the orders of the other side receive the part of the excess side that was matched
+    let mut matched_received = Uint128::zero();
+    for fill in &matched.fills {
+        let received = matched_amount.multiply_ratio(fill.offer_amount, total_matched);
+        matched_received = matched_received.checked_add(received)?;
+
+        assert_match_price(
+            fill.order_id,
+            max_spread_of(fill.order_id),
+            fill.offer_amount,
+            received,
+            (price.1, price.0),
+        )?;
+        record_internal_fill(
+            deps.storage,
+            fill,
+            now,
+            Asset {
+                info: matched.asset.clone(),
+                amount: fill.offer_amount,
+            },
+            Asset {
+                info: excess.asset.clone(),
+                amount: received,
+            },
+            &mut messages,
+            &mut settled_events,
+        )?;
+    }
+
+    // the rounding dust of the pro rata distribution goes to the fee collector
+    for dust in [
+        Asset {
+            info: matched.asset.clone(),
+            amount: total_matched.checked_sub(excess_received)?,
+        },
+        Asset {
+            info: excess.asset.clone(),
+            amount: matched_amount.checked_sub(matched_received)?,
+        },
+    ] {
+        if !dust.amount.is_zero() {
+            messages.push(send_asset(&dust, &config.fee_collector)?);
+        }
+    }
+
+    let mut response = Response::new().add_messages(excess.pull_messages);
+
+    // the orders routed through the router pay the tip of a purchase through the hop route, while
+    // the orders matched in full pay the tip of a single hop purchase
+    let routed_ids: Vec<u64> = net_fills.iter().map(|fill| fill.order_id).collect();
+
+    // swap the net imbalance, measuring the proceeds when the router returns them to the contract
+    let net_amount = total_offer(&net_fills)?;
+    if !net_amount.is_zero() {
+        validate_route_assets(&excess.asset, &matched.asset, &hops)?;
+
+        // the swap is subject to the limits and route preferences of every order routed through
+        // it, as for a DCA purchase, with the strictest max_spread among them
+        let mut max_spread = config.max_spread;
+        for batch_order in batch_orders
+            .iter()
+            .filter(|batch_order| routed_ids.contains(&batch_order.order.id))
+        {
+            validate_route_for_order(
+                deps.storage,
+                batch_order.order.route_preference.as_ref(),
+                batch_order.max_hops,
+                &hops,
+            )?;
+
+            max_spread = max_spread.min(batch_order.max_spread);
+        }
+
+        PENDING_BATCH_SETTLEMENT.save(
+            deps.storage,
+            &PendingBatchSettlement {
+                offer_asset: excess.asset.clone(),
+                ask_asset: matched.asset.clone(),
+                fills: net_fills,
+                balance_before: matched
+                    .asset
+                    .query_pool(&deps.querier, env.contract.address.clone())?,
+            },
+        )?;
+
+        response = response.add_submessage(SubMsg::reply_on_success(
+            router_swap_message(
+                &config.router_addr,
+                &Asset {
+                    info: excess.asset.clone(),
+                    amount: net_amount,
+                },
+                hops.clone(),
+                &env.contract.address,
+                max_spread,
+            )?,
+            BATCH_SETTLEMENT_REPLY_ID,
+        ));
+    }
+
+    let mut events: Vec<Event> = vec![];
+    let mut bot_tip = Uint128::zero();
+    for batch_order in &batch_orders {
+        let hops_len = if routed_ids.contains(&batch_order.order.id) {
+            hops.len() as u32
+        } else {
+            1
+        };
+        let charge = charge_tip(deps.branch(), &config, &batch_order.order, hops_len, now)?;
+        bot_tip = bot_tip.checked_add(charge.bot_tip)?;
+
+        update_stats(
+            deps.storage,
+            &batch_order.order.owner,
+            &batch_order.order.initial_asset.info,
+            &batch_order.order.target_asset,
+            |stats| {
+                stats.tips_paid = stats.tips_paid.checked_add(charge.tip)?;
+                Ok(())
+            },
+        )?;
+
+        events.push(
+            PurchaseEvent {
+                order: &batch_order.purchased,
+                protocol_fee: batch_order.protocol_fee,
+                swap_amount: batch_order.swap_amount,
+                tip: charge.tip,
+                tip_balance: charge.tip_balance,
+                bot: &info.sender,
+            }
+            .into_event("batch"),
+        );
+    }
+
+    // the other side is only pulled in after the swap, so that it is not measured as proceeds
+    response = response
+        .add_messages(matched.pull_messages)
+        .add_messages(messages);
+    if !bot_tip.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                amount: bot_tip,
+                denom: UUSD_DENOM.to_string(),
+            }],
+        });
+    }
+
+    Ok(response
+        .add_events(events)
+        .add_events(settled_events)
+        .add_attributes(vec![
+            attr("action", "perform_batch_settlement"),
+            attr("orders", order_ids.len().to_string()),
+            attr("excess_asset", excess.asset.to_string()),
+            attr("matched_amount", matched_amount),
+            attr("net_amount", net_amount),
+            attr("tip_cost", bot_tip),
+        ]))
+}
+
+/// ## Description
+/// Distributes the proceeds of the net imbalance of a batch settlement to the orders that were
+/// routed through the router, pro rata to the amount swapped for each order.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+pub fn batch_settlement_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_BATCH_SETTLEMENT.load(deps.storage)?;
+    PENDING_BATCH_SETTLEMENT.remove(deps.storage);
+
+    let proceeds = pending
+        .ask_asset
+        .query_pool(&deps.querier, env.contract.address)?
+        .checked_sub(pending.balance_before)?;
+    let net_amount = total_offer(&pending.fills)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut events: Vec<Event> = vec![];
+    let mut distributed = Uint128::zero();
+    for fill in &pending.fills {
+        let offer_asset = Asset {
+            info: pending.offer_asset.clone(),
+            amount: fill.offer_amount,
+        };
+        let received = Asset {
+            info: pending.ask_asset.clone(),
+            amount: proceeds.multiply_ratio(fill.offer_amount, net_amount),
+        };
+        distributed = distributed.checked_add(received.amount)?;
+
+        events.push(purchase_settled_event(
+            fill.order_id,
+            &fill.owner,
+            &offer_asset,
+            &received,
+            false,
+        ));
+        update_stats(
+            deps.storage,
+            &fill.owner,
+            &offer_asset.info,
+            &received.info,
+            |stats| {
+                stats.bought = stats.bought.checked_add(received.amount)?;
+                Ok(())
+            },
+        )?;
+        record_purchase(
+            deps.storage,
+            fill.order_id,
+            env.block.time.seconds(),
+            offer_asset,
+            Some(received.clone()),
+            false,
+        )?;
+
+        if !received.amount.is_zero() {
+            messages.push(send_asset(&received, &fill.owner)?);
+        }
+    }
+
+    // the rounding dust of the pro rata distribution goes to the fee collector
+    let dust = Asset {
+        info: pending.ask_asset.clone(),
+        amount: proceeds.checked_sub(distributed)?,
+    };
+    if !dust.amount.is_zero() {
+        let config = CONFIG.load(deps.storage)?;
+        messages.push(send_asset(&dust, &config.fee_collector)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_events(events)
+        .add_attributes(vec![
+            attr("action", "batch_settlement_reply"),
+            attr("proceeds", proceeds),
+        ]))
+}
+
+/// ## Description
+/// Returns the total amount offered by `fills`.
+fn total_offer(fills: &[PendingFill]) -> StdResult<Uint128> {
+    fills.iter().try_fold(Uint128::zero(), |total, fill| {
+        Ok(total.checked_add(fill.offer_amount)?)
+    })
+}
+
+/// ## Description
+/// Records the part of `fill` that was matched internally in the purchase history of its order,
+/// adding the message sending the `received` asset to the owner of the order to `messages` and
+/// its `dca_purchase_settled` event to `events`.
+fn record_internal_fill(
+    storage: &mut dyn Storage,
+    fill: &PendingFill,
+    now: u64,
+    offer_asset: Asset,
+    received: Asset,
+    messages: &mut Vec<CosmosMsg>,
+    events: &mut Vec<Event>,
+) -> StdResult<()> {
+    if offer_asset.amount.is_zero() {
+        return Ok(());
+    }
+
+    if !received.amount.is_zero() {
+        messages.push(send_asset(&received, &fill.owner)?);
+    }
+    events.push(purchase_settled_event(
+        fill.order_id,
+        &fill.owner,
+        &offer_asset,
+        &received,
+        true,
+    ));
+    update_stats(
+        storage,
+        &fill.owner,
+        &offer_asset.info,
+        &received.info,
+        |stats| {
+            stats.bought = stats.bought.checked_add(received.amount)?;
+            Ok(())
+        },
+    )?;
+    record_purchase(
+        storage,
+        fill.order_id,
+        now,
+        offer_asset,
+        Some(received),
+        true,
+    )?;
+
+    Ok(())
+}
+
+/// ## Description
+/// Returns an error if the amount `received` by the order `order_id` for the amount `offered` in
+/// an internal match falls short of the amount due at the pair price by more than the
+/// `max_spread` of the order, as it would for a swap.
+///
+/// The `price` is the amount of the received asset due for the numerator amount of the offered
+/// asset, as a (numerator, denominator) pair.
+fn assert_match_price(
+    order_id: u64,
+    max_spread: Decimal,
+    offered: Uint128,
+    received: Uint128,
+    price: (Uint128, Uint128),
+) -> Result<(), ContractError> {
+    // compare received / offered with price.0 / price.1 without rounding the amount due
+    let received_value = received.checked_mul(price.1)?;
+    let due_value = offered.checked_mul(price.0)?;
+    if received_value < due_value.checked_sub(max_spread * due_value)? {
+        return Err(ContractError::BatchSpreadAssertion { id: order_id });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Returns the share of the value of a pair simulation lost to the spread, as checked against the
+/// `max_spread` of a swap. A simulation without any value, such as that of an empty side, loses
+/// nothing to the spread.
+fn simulation_spread(simulation: &SimulationResponse) -> Result<Decimal, ContractError> {
+    let total = simulation
+        .return_amount
+        .checked_add(simulation.spread_amount)?;
+    if total.is_zero() {
+        return Ok(Decimal::zero());
+    }
+
+    Ok(Decimal::from_ratio(simulation.spread_amount, total))
+}
+
+/// ## Description
+/// Removes the tip of a purchase of `order` with `hops` hops from the tip balance of its owner,
+/// crediting the referrer of the order if it is paid out of the bot tip, and returns the
+/// [`TipCharge`] of the purchase.
+fn charge_tip(
+    deps: DepsMut,
+    config: &Config,
+    order: &DcaInfo,
+    hops: u32,
+    now: u64,
+) -> Result<TipCharge, ContractError> {
+    let mut user_config = USER_CONFIG
+        .may_load(deps.storage, &order.owner)?
+        .unwrap_or_default();
+
+    let tip_cost = calculate_tip(
+        &deps.querier,
+        config,
+        &user_config.tip_mode,
+        order,
+        hops,
+        now,
+    )?;
+    if let Some(max_tip) = user_config.max_tip_per_purchase {
+        if tip_cost > max_tip {
+            return Err(ContractError::TipCapExceeded {
+                tip: tip_cost,
+                max: max_tip,
+            });
+        }
+    }
+
+    user_config.tip_balance = user_config
+        .tip_balance
+        .checked_sub(tip_cost)
+        .map_err(|_| ContractError::InsufficientTipBalance {})?;
+    USER_CONFIG.save(deps.storage, &order.owner, &user_config)?;
+
+    let referral_tip = match (&order.referrer, &config.referral_source) {
+        (Some(referrer), ReferralSource::BotTip) => {
+            let referral_tip = config.referral_share * tip_cost;
+            let uusd = AssetInfo::NativeToken {
+                denom: UUSD_DENOM.to_string(),
+            };
+            credit_referrer(deps.storage, referrer, &uusd, referral_tip)?;
+            referral_tip
+        }
+        _ => Uint128::zero(),
+    };
+
+    Ok(TipCharge {
+        tip: tip_cost,
+        bot_tip: tip_cost.checked_sub(referral_tip)?,
+        tip_balance: user_config.tip_balance,
+    })
+}
+
+/// ## Description
+/// Credits `referrer` with a referral reward of `amount` of `asset`, which is kept by the contract
+/// until claimed.
+fn credit_referrer(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    REFERRER_STATS.update(storage, referrer, |stats| -> StdResult<_> {
+        let mut stats: ReferrerStats = stats.unwrap_or_default();
+        stats.credit(&Asset {
+            info: asset.clone(),
+            amount,
+        })?;
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    };
+    use astroport_dca::dca::{
+        ExecuteMsg, PurchaseRecord, QueryMsg, RoutePreference, StatsResponse,
+    };
+    use cosmwasm_std::{
+        coin, from_binary,
+        testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR},
+        to_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal, Event, OwnedDeps, Reply,
+        Response, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+    };
+
+    use crate::{
+        contract::{execute, query, reply},
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, native, setup_contract, WasmMockQuerier},
+        state::BATCH_SETTLEMENT_REPLY_ID,
+    };
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, WasmMockQuerier>;
+
+    fn create_order(deps: &mut MockDeps, user: &str, initial: &str, target: &str, amount: u128) {
+        create_limited_order(deps, user, initial, target, amount, None, None);
+    }
+
+    fn create_limited_order(
+        deps: &mut MockDeps,
+        user: &str,
+        initial: &str,
+        target: &str,
+        amount: u128,
+        max_spread: Option<Decimal>,
+        route_preference: Option<RoutePreference>,
+    ) {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[coin(1_000, "uusd")]),
+            ExecuteMsg::AddBotTip {},
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[coin(amount * 10, initial)]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: native(initial),
+                    amount: Uint128::from(amount * 10),
+                },
+                target_asset: native(target),
+                interval: 60,
+                dca_amount: Uint128::from(amount),
+                referrer: None,
+                max_hops: None,
+                max_spread,
+                route_preference,
+                owner: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn setup_orders() -> MockDeps {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        // 1 uusd is worth 0.01 uluna
+        deps.querier.update_simulation_rate(Decimal::percent(1));
+
+        create_order(&mut deps, "alice", "uusd", "uluna", 1_000);
+        create_order(&mut deps, "bob", "uluna", "uusd", 5);
+
+        deps
+    }
+
+    fn uusd_to_uluna() -> Vec<SwapOperation> {
+        vec![SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        }]
+    }
+
+    fn uusd_to_ukrw_to_uluna() -> Vec<SwapOperation> {
+        vec![
+            SwapOperation::NativeSwap {
+                offer_denom: "uusd".to_string(),
+                ask_denom: "ukrw".to_string(),
+            },
+            SwapOperation::NativeSwap {
+                offer_denom: "ukrw".to_string(),
+                ask_denom: "uluna".to_string(),
+            },
+        ]
+    }
+
+    fn settle(deps: &mut MockDeps, order_ids: Vec<u64>) -> Result<Response, ContractError> {
+        settle_with_hops(deps, order_ids, uusd_to_uluna())
+    }
+
+    fn settle_with_hops(
+        deps: &mut MockDeps,
+        order_ids: Vec<u64>,
+        hops: Vec<SwapOperation>,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[]),
+            ExecuteMsg::PerformBatchSettlement { order_ids, hops },
+        )
+    }
+
+    fn history(deps: &MockDeps, token_id: &str) -> Vec<PurchaseRecord> {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PurchaseHistory {
+                    token_id: token_id.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn send(to: &str, amount: u128, denom: &str) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![coin(amount, denom)],
+        })
+    }
+
+    #[test]
+    fn does_net_opposing_orders() {
+        let mut deps = setup_orders();
+
+        let res = settle(&mut deps, vec![1, 2]).unwrap();
+
+        // alice is worth 10 uluna, so half of her 1_000 uusd is matched with the 5 uluna of bob
+        // and only the other half is swapped
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_on_success(
+                    CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: "router".to_string(),
+                        funds: vec![coin(500, "uusd")],
+                        msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                            operations: uusd_to_uluna(),
+                            minimum_receive: None,
+                            to: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                            max_spread: Some(Decimal::percent(5)),
+                        })
+                        .unwrap(),
+                    }),
+                    BATCH_SETTLEMENT_REPLY_ID,
+                ),
+                SubMsg::new(send("alice", 5, "uluna")),
+                SubMsg::new(send("bob", 500, "uusd")),
+                SubMsg::new(send("bot", 200, "uusd")),
+            ]
+        );
+
+        // each order emits its own purchase event
+        assert_eq!(
+            res.events
+                .iter()
+                .map(|event| {
+                    let attribute = |key: &str| {
+                        event
+                            .attributes
+                            .iter()
+                            .find(|attribute| attribute.key == key)
+                            .map(|attribute| attribute.value.as_str())
+                    };
+                    (
+                        event.ty.as_str(),
+                        attribute("order_id"),
+                        attribute("route"),
+                        attribute("tip"),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                ("dca_purchase", Some("1"), Some("batch"), Some("100")),
+                ("dca_purchase", Some("2"), Some("batch"), Some("100")),
+                ("dca_purchase_settled", Some("1"), None, None),
+                ("dca_purchase_settled", Some("2"), None, None),
+            ]
+        );
+
+        // the router returns 6 uluna for the net imbalance, which is sent to alice
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, vec![coin(6, "uluna")]);
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: BATCH_SETTLEMENT_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(send("alice", 6, "uluna"))]);
+        assert_eq!(
+            res.events,
+            vec![Event::new("dca_purchase_settled")
+                .add_attribute("order_id", "1")
+                .add_attribute("owner", "alice")
+                .add_attribute("offer_asset", "500uusd")
+                .add_attribute("received", "6uluna")
+                .add_attribute("internal_match", "false")]
+        );
+
+        // both fills of alice are recorded, with the internal match flagged
+        let now = mock_env().block.time.seconds();
+        assert_eq!(
+            history(&deps, "1"),
+            vec![
+                PurchaseRecord {
+                    id: 1,
+                    order_id: 1,
+                    time: now,
+                    offer_asset: Asset {
+                        info: native("uusd"),
+                        amount: Uint128::from(500u128),
+                    },
+                    received: Some(Asset {
+                        info: native("uluna"),
+                        amount: Uint128::from(5u128),
+                    }),
+                    internal_match: true,
+                },
+                PurchaseRecord {
+                    id: 3,
+                    order_id: 1,
+                    time: now,
+                    offer_asset: Asset {
+                        info: native("uusd"),
+                        amount: Uint128::from(500u128),
+                    },
+                    received: Some(Asset {
+                        info: native("uluna"),
+                        amount: Uint128::from(6u128),
+                    }),
+                    internal_match: false,
+                },
+            ]
+        );
+        assert_eq!(
+            history(&deps, "2"),
+            vec![PurchaseRecord {
+                id: 2,
+                order_id: 2,
+                time: now,
+                offer_asset: Asset {
+                    info: native("uluna"),
+                    amount: Uint128::from(5u128),
+                },
+                received: Some(Asset {
+                    info: native("uusd"),
+                    amount: Uint128::from(500u128),
+                }),
+                internal_match: true,
+            }]
+        );
+
+        // both fills of alice count towards the uluna bought, returned one pair at a time
+        let stats = |start_after: Option<(AssetInfo, AssetInfo)>| -> StatsResponse {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GlobalStats {
+                        start_after,
+                        limit: Some(1),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        let first = stats(None);
+        assert_eq!(first.pairs.len(), 1);
+        assert_eq!(first.pairs[0].target_asset, native("uluna"));
+        assert_eq!(first.pairs[0].bought, Uint128::from(11u128));
+
+        let second = stats(Some((native("uusd"), native("uluna"))));
+        assert_eq!(second.pairs[0].target_asset, native("uusd"));
+        assert_eq!(second.pairs[0].bought, Uint128::from(500u128));
+        assert_eq!(second.purchases, 1);
+
+        // the orders are not due again until their interval has passed
+        assert_eq!(
+            settle(&mut deps, vec![1, 2]).unwrap_err(),
+            ContractError::PurchaseTooEarly {}
+        );
+    }
+
+    #[test]
+    fn does_validate_batch() {
+        let mut deps = setup_orders();
+        create_order(&mut deps, "carol", "uusd", "ukrw", 1_000);
+        create_order(&mut deps, "dave", "uusd", "uluna", 1_000);
+
+        assert_eq!(
+            settle(&mut deps, vec![1, 4]).unwrap_err(),
+            ContractError::NoOpposingOrders {}
+        );
+        assert_eq!(
+            settle(&mut deps, vec![1, 2, 3]).unwrap_err(),
+            ContractError::BatchAssetMismatch {}
+        );
+        assert_eq!(
+            settle(&mut deps, vec![1, 2, 1]).unwrap_err(),
+            ContractError::DuplicateBatchOrder { id: 1 }
+        );
+    }
+
+    #[test]
+    fn does_check_spread_of_batch_orders() {
+        let mut deps = setup_orders();
+
+        // swapping the uusd of alice through the pair loses 10% to the spread, more than the 5%
+        // max_spread of the contract
+        deps.querier.update_simulation_spread(Decimal::percent(10));
+        assert_eq!(
+            settle(&mut deps, vec![1, 2]).unwrap_err(),
+            ContractError::BatchSpreadAssertion { id: 1 }
+        );
+
+        let mut deps = setup_orders();
+        deps.querier.update_simulation_spread(Decimal::percent(4));
+        settle(&mut deps, vec![1, 2]).unwrap();
+    }
+
+    fn setup_limited_orders() -> MockDeps {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        deps.querier.update_simulation_rate(Decimal::percent(1));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::AddWhitelistedTokens {
+                tokens: vec![native("ukrw")],
+            },
+        )
+        .unwrap();
+
+        create_limited_order(
+            &mut deps,
+            "alice",
+            "uusd",
+            "uluna",
+            1_000,
+            Some(Decimal::percent(2)),
+            Some(RoutePreference::PinnedRoute {
+                hops: uusd_to_ukrw_to_uluna(),
+            }),
+        );
+        create_order(&mut deps, "bob", "uluna", "uusd", 5);
+
+        deps
+    }
+
+    #[test]
+    fn does_apply_order_limits_to_net_swap() {
+        // the net swap of alice must follow her pinned route
+        let mut deps = setup_limited_orders();
+        assert_eq!(
+            settle(&mut deps, vec![1, 2]).unwrap_err(),
+            ContractError::PinnedRouteMismatch {}
+        );
+
+        let mut deps = setup_limited_orders();
+        let res = settle_with_hops(&mut deps, vec![1, 2], uusd_to_ukrw_to_uluna()).unwrap();
+
+        // the net swap uses the max_spread of alice, who pays the tip of a two hop purchase while
+        // bob is matched in full and pays the tip of a single hop purchase
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_on_success(
+                    CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: "router".to_string(),
+                        funds: vec![coin(500, "uusd")],
+                        msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                            operations: uusd_to_ukrw_to_uluna(),
+                            minimum_receive: None,
+                            to: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                            max_spread: Some(Decimal::percent(2)),
+                        })
+                        .unwrap(),
+                    }),
+                    BATCH_SETTLEMENT_REPLY_ID,
+                ),
+                SubMsg::new(send("alice", 5, "uluna")),
+                SubMsg::new(send("bob", 500, "uusd")),
+                SubMsg::new(send("bot", 300, "uusd")),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_settle_pinned_route_through_unlisted_token() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        deps.querier.update_simulation_rate(Decimal::percent(1));
+
+        // a pinned route is not subject to the whitelist, as for a DCA purchase
+        create_limited_order(
+            &mut deps,
+            "alice",
+            "uusd",
+            "uluna",
+            1_000,
+            None,
+            Some(RoutePreference::PinnedRoute {
+                hops: uusd_to_ukrw_to_uluna(),
+            }),
+        );
+        create_order(&mut deps, "bob", "uluna", "uusd", 5);
+
+        let res = settle_with_hops(&mut deps, vec![1, 2], uusd_to_ukrw_to_uluna()).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "net_amount" && attr.value == "500"));
+    }
+
+    #[test]
+    fn does_send_rounding_dust_to_fee_collector() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        deps.querier.update_simulation_rate(Decimal::percent(1));
+
+        create_order(&mut deps, "alice", "uusd", "uluna", 10_000);
+        create_order(&mut deps, "bob", "uluna", "uusd", 101);
+        create_order(&mut deps, "carol", "uusd", "uluna", 10_000);
+
+        // alice and carol are each due 50.5 uluna of the 101 uluna of bob, and the uluna left
+        // over by the rounding is sent to the fee collector
+        let res = settle(&mut deps, vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            res.messages[1..]
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                send("alice", 50, "uluna"),
+                send("carol", 50, "uluna"),
+                send("bob", 10_100, "uusd"),
+                send("treasury", 1, "uluna"),
+                send("bot", 300, "uusd"),
+            ]
+        );
+
+        // so is the rounding of the proceeds of the net swap
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, vec![coin(99, "uluna")]);
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: BATCH_SETTLEMENT_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(send("alice", 49, "uluna")),
+                SubMsg::new(send("carol", 49, "uluna")),
+                SubMsg::new(send("treasury", 1, "uluna")),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_check_spread_of_internal_match() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        deps.querier.update_simulation_rate(Decimal::percent(1));
+
+        create_order(&mut deps, "alice", "uusd", "uluna", 1_000);
+        create_order(&mut deps, "bob", "uluna", "uusd", 1);
+        create_order(&mut deps, "carol", "uusd", "uluna", 1_000);
+
+        // alice would get nothing for the 50 uusd matched with half of the uluna of bob
+        assert_eq!(
+            settle(&mut deps, vec![1, 2, 3]).unwrap_err(),
+            ContractError::BatchSpreadAssertion { id: 1 }
+        );
+    }
+}
@@ -1,17 +1,27 @@
 use astroport::{
-    asset::{addr_validate_to_lower, AssetInfo, UUSD_DENOM},
+    asset::{addr_validate_to_lower, Asset, AssetInfo, UUSD_DENOM},
     router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
 };
-use astroport_dca::dca::DcaInfo;
+use astroport_dca::{
+    dca::{ReferralSource, ReferrerStats, UserConfig},
+    evaluation::{check_order_eligibility, purchase_amounts, PurchaseAmounts},
+};
 use cosmwasm_std::{
-    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128,
-    WasmMsg,
+    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, SubMsg,
+    Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
 use crate::{
     error::ContractError,
-    state::{UserConfig, CONFIG, USER_CONFIG, USER_DCA},
+    events::{purchase_settled_event, route, PurchaseEvent},
+    state::{
+        add_to_cost_basis, record_purchase, save_order, update_stats, user_orders,
+        PendingDcaPurchase, BOTS, CONFIG, DCA_PURCHASE_REPLY_ID, PENDING_DCA_PURCHASE,
+        REFERRER_STATS, USER_CONFIG,
+    },
+    tip::calculate_tip,
+    validation::validate_route_for_order,
 };
 
 /// ## Description
@@ -47,151 +57,256 @@ pub fn perform_dca_purchase(
         .unwrap_or_default();
     let contract_config = CONFIG.load(deps.storage)?;
 
+    // registered bots with a sufficient bond that is not unbonding have priority when an order
+    // becomes due
+    let is_priority_bot = matches!(
+        BOTS.may_load(deps.storage, &info.sender)?,
+        Some(bot) if contract_config.has_bot_priority(&bot)
+    );
+
     // validate hops is at least one
     if hops.is_empty() {
         return Err(ContractError::EmptyHopRoute {});
     }
 
-    // validate hops does not exceed max_hops
+    // store messages to send in response
+    let mut messages: Vec<CosmosMsg> = Vec::new();
+
+    // load the user dca order being purchased, which is the first order spending the offered
+    // asset that is not paused
+    let (paused, active): (Vec<_>, Vec<_>) = user_orders(deps.storage, &user_address)?
+        .into_iter()
+        .filter(|order| match &hops[0] {
+            SwapOperation::NativeSwap { offer_denom, .. } => match &order.initial_asset.info {
+                AssetInfo::NativeToken { denom } => offer_denom == denom,
+                _ => false,
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            } => offer_asset_info == &order.initial_asset.info,
+        })
+        .partition(|order| order.paused_at.is_some());
+    let mut order = match (active.into_iter().next(), paused.first()) {
+        (Some(order), _) => order,
+        (None, Some(order)) => return Err(ContractError::OrderPaused { id: order.id }),
+        (None, None) => return Err(ContractError::NonexistentDca {}),
+    };
+
+    // validate hops does not exceed max_hops of the order, user or contract, in that order, and
+    // follows the route preference of the order
     let hops_len = hops.len() as u32;
-    if hops_len > user_config.max_hops.unwrap_or(contract_config.max_hops) {
-        return Err(ContractError::MaxHopsAssertion { hops: hops_len });
-    }
-
-    // validate that all middle hops (last hop excluded) are whitelisted tokens for the ask_denom or ask_asset
-    let middle_hops = &hops[..hops.len() - 1];
-    for swap in middle_hops {
-        match swap {
-            SwapOperation::NativeSwap { ask_denom, .. } => {
-                if !contract_config
-                    .whitelisted_tokens
-                    .iter()
-                    .any(|token| match token {
-                        AssetInfo::NativeToken { denom } => ask_denom == denom,
-                        AssetInfo::Token { .. } => false,
-                    })
-                {
-                    // not a whitelisted native token
-                    return Err(ContractError::InvalidHopRoute {
-                        token: ask_denom.to_string(),
-                    });
-                }
-            }
-            SwapOperation::AstroSwap { ask_asset_info, .. } => {
-                if !contract_config.is_whitelisted_asset(ask_asset_info) {
-                    return Err(ContractError::InvalidHopRoute {
-                        token: ask_asset_info.to_string(),
-                    });
-                }
-            }
-        }
+    validate_route_for_order(
+        deps.storage,
+        order.route_preference.as_ref(),
+        contract_config.max_hops_for(&user_config, &order),
+        &hops,
+    )?;
+
+    // retrieve max_spread from the order, user or contract, in that order
+    let max_spread = contract_config.max_spread_for(&user_config, &order);
+
+    // check that it has been long enough between dca purchases, and that the sender has priority
+    // if the order only recently became due
+    check_order_eligibility(
+        &order,
+        contract_config.bot_priority_window,
+        is_priority_bot,
+        env.block.time.seconds(),
+    )?;
+
+    // check that last hop is target asset
+    let last_hop = &hops
+        .last()
+        .ok_or(ContractError::EmptyHopRoute {})?
+        .get_target_asset_info();
+    if last_hop != &order.target_asset {
+        return Err(ContractError::TargetAssetAssertion {});
     }
 
-    // validate purchaser has enough funds to pay the sender
-    let tip_cost = contract_config
-        .per_hop_fee
-        .checked_mul(Uint128::from(hops_len))?;
+    // validate purchaser has enough funds to pay the sender, and is willing to pay it
+    let tip_cost = calculate_tip(
+        &deps.querier,
+        &contract_config,
+        &user_config.tip_mode,
+        &order,
+        hops_len,
+        env.block.time.seconds(),
+    )?;
+    if let Some(max_tip) = user_config.max_tip_per_purchase {
+        if tip_cost > max_tip {
+            return Err(ContractError::TipCapExceeded {
+                tip: tip_cost,
+                max: max_tip,
+            });
+        }
+    }
     if tip_cost > user_config.tip_balance {
         return Err(ContractError::InsufficientTipBalance {});
     }
 
-    // retrieve max_spread from user config, or default to contract set max_spread
-    let max_spread = user_config.max_spread.unwrap_or(contract_config.max_spread);
+    // subtract dca_amount from order, update last_purchase time and count off a purchase made up
+    // for
+    order.initial_asset.amount = order
+        .initial_asset
+        .amount
+        .checked_sub(order.dca_amount)
+        .map_err(|_| ContractError::InsufficientBalance {})?;
+    order.last_purchase = env.block.time.seconds();
+    order.catch_up_purchases = order.catch_up_purchases.saturating_sub(1);
 
-    // store messages to send in response
-    let mut messages: Vec<CosmosMsg> = Vec::new();
-
-    // load user dca orders and update the relevant one
-    USER_DCA.update(
-        deps.storage,
-        &user_address,
-        |orders| -> Result<Vec<DcaInfo>, ContractError> {
-            let mut orders = orders.ok_or(ContractError::NonexistentDca {})?;
-
-            let order = orders
-                .iter_mut()
-                .find(|order| match &hops[0] {
-                    SwapOperation::NativeSwap { ask_denom, .. } => {
-                        match &order.initial_asset.info {
-                            AssetInfo::NativeToken { denom } => ask_denom == denom,
-                            _ => false,
-                        }
-                    }
-                    SwapOperation::AstroSwap {
-                        offer_asset_info, ..
-                    } => offer_asset_info == &order.initial_asset.info,
-                })
-                .ok_or(ContractError::NonexistentDca {})?;
-
-            // check that it has been long enough between dca purchases
-            if order.last_purchase + order.interval > env.block.time.seconds() {
-                return Err(ContractError::PurchaseTooEarly {});
-            }
+    // take the protocol fee out of the dca_amount, swapping only the remainder, and credit the
+    // referrer with their share of the protocol fee, which is kept by the contract until claimed
+    let referrer = order.referrer.clone();
+    let fee_bps = contract_config.protocol_fee_bps_for(&user_address, &order.target_asset);
+    let PurchaseAmounts {
+        protocol_fee,
+        referral_fee,
+        collector_fee,
+        swap_amount,
+    } = purchase_amounts(
+        order.dca_amount,
+        fee_bps,
+        match (&referrer, &contract_config.referral_source) {
+            (Some(_), ReferralSource::ProtocolFee) => Some(contract_config.referral_share),
+            _ => None,
+        },
+    )?;
+    let mut referral_reward = if referral_fee.is_zero() {
+        None
+    } else {
+        Some(Asset {
+            info: order.initial_asset.info.clone(),
+            amount: referral_fee,
+        })
+    };
 
-            // check that last hop is target asset
-            let last_hop = &hops
-                .last()
-                .ok_or(ContractError::EmptyHopRoute {})?
-                .get_target_asset_info();
-            if last_hop != &order.target_asset {
-                return Err(ContractError::TargetAssetAssertion {});
+    // add funds and router message to response
+    if let AssetInfo::Token { contract_addr } = &order.initial_asset.info {
+        // send a TransferFrom request to the token to the router
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: user_address.to_string(),
+                    recipient: contract_config.router_addr.to_string(),
+                    amount: swap_amount,
+                })?,
             }
+            .into(),
+        );
 
-            // subtract dca_amount from order and update last_purchase time
-            order.initial_asset.amount = order
-                .initial_asset
-                .amount
-                .checked_sub(order.dca_amount)
-                .map_err(|_| ContractError::InsufficientBalance {})?;
-            order.last_purchase = env.block.time.seconds();
-
-            // add funds and router message to response
-            if let AssetInfo::Token { contract_addr } = &order.initial_asset.info {
-                // send a TransferFrom request to the token to the router
+        // send the protocol fee to the fee collector and the referral fee to the contract
+        for (recipient, amount) in [
+            (&contract_config.fee_collector, collector_fee),
+            (&env.contract.address, referral_fee),
+        ] {
+            if !amount.is_zero() {
                 messages.push(
                     WasmMsg::Execute {
                         contract_addr: contract_addr.to_string(),
                         funds: vec![],
                         msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
                             owner: user_address.to_string(),
-                            recipient: contract_config.router_addr.to_string(),
-                            amount: order.dca_amount,
+                            recipient: recipient.to_string(),
+                            amount,
                         })?,
                     }
                     .into(),
                 );
             }
+        }
+    }
 
-            // if it is a native token, we need to send the funds
-            let funds = match &order.initial_asset.info {
-                AssetInfo::NativeToken { denom } => vec![Coin {
-                    amount: order.dca_amount,
-                    denom: denom.clone(),
-                }],
-                AssetInfo::Token { .. } => vec![],
-            };
-
-            // tell the router to perform swap operations
-            messages.push(
-                WasmMsg::Execute {
-                    contract_addr: contract_config.router_addr.to_string(),
-                    funds,
-                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
-                        operations: hops,
-                        minimum_receive: None,
-                        to: Some(user_address.clone()),
-                        max_spread: Some(max_spread),
-                    })?,
-                }
-                .into(),
-            );
-
-            Ok(orders)
+    // if it is a native token, we need to send the funds
+    let funds = match &order.initial_asset.info {
+        AssetInfo::NativeToken { denom } => {
+            // send the protocol fee to the fee collector
+            if !collector_fee.is_zero() {
+                messages.push(
+                    BankMsg::Send {
+                        to_address: contract_config.fee_collector.to_string(),
+                        amount: vec![Coin {
+                            amount: collector_fee,
+                            denom: denom.clone(),
+                        }],
+                    }
+                    .into(),
+                );
+            }
+
+            vec![Coin {
+                amount: swap_amount,
+                denom: denom.clone(),
+            }]
+        }
+        AssetInfo::Token { .. } => vec![],
+    };
+
+    // tell the router to perform swap operations, measuring the proceeds sent to the user in the
+    // reply to record the purchase
+    let route = route(&order.initial_asset.info, &hops);
+    let swap = SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: contract_config.router_addr.to_string(),
+            funds,
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: hops,
+                minimum_receive: None,
+                to: Some(user_address.clone()),
+                max_spread: Some(max_spread),
+            })?,
+        },
+        DCA_PURCHASE_REPLY_ID,
+    );
+
+    PENDING_DCA_PURCHASE.save(
+        deps.storage,
+        &PendingDcaPurchase {
+            order_id: order.id,
+            owner: user_address.clone(),
+            offer_asset: Asset {
+                info: order.initial_asset.info.clone(),
+                amount: swap_amount,
+            },
+            ask_asset: order.target_asset.clone(),
+            balance_before: order
+                .target_asset
+                .query_pool(&deps.querier, user_address.clone())?,
+        },
+    )?;
+
+    // the protocol fee counts towards the cost of the target_asset purchased by the order
+    add_to_cost_basis(
+        deps.storage,
+        order.id,
+        &Asset {
+            info: order.initial_asset.info.clone(),
+            amount: protocol_fee,
+        },
+        &Asset {
+            info: order.target_asset.clone(),
+            amount: Uint128::zero(),
+        },
+    )?;
+
+    save_order(deps.storage, &order)?;
+
+    update_stats(
+        deps.storage,
+        &order.owner,
+        &order.initial_asset.info,
+        &order.target_asset,
+        |stats| {
+            stats.purchases += 1;
+            stats.volume = stats.volume.checked_add(order.dca_amount)?;
+            stats.tips_paid = stats.tips_paid.checked_add(tip_cost)?;
+            Ok(())
         },
     )?;
 
     // remove tip from purchaser
-    USER_CONFIG.update(
+    let user_config = USER_CONFIG.update(
         deps.storage,
         &user_address,
         |user_config| -> Result<UserConfig, ContractError> {
@@ -206,20 +321,969 @@ pub fn perform_dca_purchase(
         },
     )?;
 
-    // add tip payment to messages
-    messages.push(
-        BankMsg::Send {
+    // credit the referrer with their share of the tip, which is kept by the contract until claimed
+    let referral_tip = match (&referrer, &contract_config.referral_source) {
+        (Some(_), ReferralSource::BotTip) => contract_config.referral_share * tip_cost,
+        _ => Uint128::zero(),
+    };
+    if !referral_tip.is_zero() {
+        referral_reward = Some(Asset {
+            info: AssetInfo::NativeToken {
+                denom: UUSD_DENOM.to_string(),
+            },
+            amount: referral_tip,
+        });
+    }
+
+    if let (Some(referrer), Some(reward)) = (&referrer, &referral_reward) {
+        REFERRER_STATS.update(
+            deps.storage,
+            referrer,
+            |stats| -> Result<ReferrerStats, ContractError> {
+                let mut stats = stats.unwrap_or_default();
+                stats.credit(reward)?;
+                Ok(stats)
+            },
+        )?;
+    }
+
+    let bot_tip = tip_cost.checked_sub(referral_tip)?;
+
+    let event = PurchaseEvent {
+        order: &order,
+        protocol_fee,
+        swap_amount,
+        tip: tip_cost,
+        tip_balance: user_config.tip_balance,
+        bot: &info.sender,
+    }
+    .into_event(&route);
+
+    let mut response = Response::new().add_messages(messages).add_submessage(swap);
+
+    // pay the tip once the swap has been performed, unless all of it is kept for the referrer
+    if !bot_tip.is_zero() {
+        response = response.add_message(BankMsg::Send {
             to_address: info.sender.to_string(),
             amount: vec![Coin {
-                amount: tip_cost,
+                amount: bot_tip,
                 denom: UUSD_DENOM.to_string(),
             }],
-        }
-        .into(),
-    );
+        });
+    }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
+    Ok(response.add_event(event).add_attributes(vec![
         attr("action", "perform_dca_purchase"),
         attr("tip_cost", tip_cost),
+        attr("protocol_fee", protocol_fee),
+        attr(
+            "referral_reward",
+            referral_reward.map_or_else(|| "none".to_string(), |reward| reward.to_string()),
+        ),
+    ]))
+}
+
+/// ## Description
+/// Records a DCA purchase in the purchase history of its order, along with the amount of
+/// `target_asset` the router sent to the owner of the order.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+pub fn dca_purchase_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_DCA_PURCHASE.load(deps.storage)?;
+    PENDING_DCA_PURCHASE.remove(deps.storage);
+
+    let received = Asset {
+        amount: pending
+            .ask_asset
+            .query_pool(&deps.querier, pending.owner.clone())?
+            .checked_sub(pending.balance_before)?,
+        info: pending.ask_asset,
+    };
+
+    let event = purchase_settled_event(
+        pending.order_id,
+        &pending.owner,
+        &pending.offer_asset,
+        &received,
+        false,
+    );
+
+    update_stats(
+        deps.storage,
+        &pending.owner,
+        &pending.offer_asset.info,
+        &received.info,
+        |stats| {
+            stats.bought = stats.bought.checked_add(received.amount)?;
+            Ok(())
+        },
+    )?;
+
+    record_purchase(
+        deps.storage,
+        pending.order_id,
+        env.block.time.seconds(),
+        pending.offer_asset,
+        Some(received.clone()),
+        false,
+    )?;
+
+    Ok(Response::new().add_event(event).add_attributes(vec![
+        attr("action", "dca_purchase_reply"),
+        attr("order_id", pending.order_id.to_string()),
+        attr("received", received.amount),
     ]))
 }
+
+#[cfg(test)]
+mod tests {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    };
+    use astroport_dca::dca::{
+        DcaQueryInfo, ExecuteMsg, InstantiateMsg, OrderPerformanceResponse, PairStats,
+        PurchaseRecord, QueryMsg, ReferralSource, RoutePreference, StatsResponse, TargetAssetFee,
+        TipMode,
+    };
+    use cosmwasm_std::{
+        attr, coin, from_binary,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr, BankMsg, ContractResult, CosmosMsg, Decimal, DepsMut, Env, Event, Reply, Response,
+        SubMsgExecutionResponse, Uint128, WasmMsg,
+    };
+
+    use crate::{
+        contract::{execute, query, reply},
+        error::ContractError,
+        mock_querier::{instantiate_msg, mock_dependencies_with_owner, setup_contract_with},
+        state::{save_order, user_orders, BOTS, CONFIG, DCA_PURCHASE_REPLY_ID, REFERRER_STATS},
+    };
+
+    fn setup(deps: DepsMut) {
+        setup_with_referrer(deps, None, ReferralSource::ProtocolFee);
+    }
+
+    fn setup_with_referrer(
+        mut deps: DepsMut,
+        referrer: Option<&str>,
+        referral_source: ReferralSource,
+    ) {
+        setup_contract_with(
+            deps.branch(),
+            InstantiateMsg {
+                protocol_fee_bps: 100,
+                referral_share: Decimal::percent(50),
+                referral_source,
+                bot_priority_window: 0,
+                min_bot_bond: Uint128::zero(),
+                ..instantiate_msg()
+            },
+        );
+
+        // create a uusd -> uluna order which purchases 1_000 uusd worth each interval
+        execute(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: referrer.map(|referrer| referrer.to_string()),
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info("creator", &[coin(1_000, "uusd")]),
+            ExecuteMsg::AddBotTip {},
+        )
+        .unwrap();
+    }
+
+    fn perform_purchase(deps: DepsMut) -> Response {
+        try_perform_purchase(deps, mock_env(), "bot").unwrap()
+    }
+
+    fn try_perform_purchase(deps: DepsMut, env: Env, bot: &str) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env,
+            mock_info(bot, &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: "creator".to_string(),
+                hops: vec![SwapOperation::NativeSwap {
+                    offer_denom: "uusd".to_string(),
+                    ask_denom: "uluna".to_string(),
+                }],
+            },
+        )
+    }
+
+    fn fee_sent(res: &Response, amount: u128) -> bool {
+        res.messages.iter().any(|msg| {
+            msg.msg
+                == CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: vec![coin(amount, "uusd")],
+                })
+        })
+    }
+
+    #[test]
+    fn does_take_protocol_fee() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        let res = perform_purchase(deps.as_mut());
+
+        // 1% of the 1_000 dca_amount goes to the fee collector
+        assert!(fee_sent(&res, 10));
+        assert!(res.attributes.contains(&attr("protocol_fee", "10")));
+    }
+
+    #[test]
+    fn does_emit_purchase_event() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        let res = perform_purchase(deps.as_mut());
+
+        assert_eq!(
+            res.events,
+            vec![Event::new("dca_purchase")
+                .add_attribute("order_id", "1")
+                .add_attribute("owner", "creator")
+                .add_attribute("initial_asset", "uusd")
+                .add_attribute("target_asset", "uluna")
+                .add_attribute("spent", "1000")
+                .add_attribute("protocol_fee", "10")
+                .add_attribute("swap_amount", "990")
+                .add_attribute("route", "uusd>uluna")
+                .add_attribute("tip", "100")
+                .add_attribute("bot", "bot")
+                .add_attribute("balance", "9000")
+                .add_attribute("tip_balance", "900")
+                .add_attribute("last_purchase", mock_env().block.time.seconds().to_string())]
+        );
+    }
+
+    #[test]
+    fn does_record_proceeds_in_reply() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup(deps.as_mut());
+
+        let res = perform_purchase(deps.as_mut());
+        assert!(res
+            .messages
+            .iter()
+            .any(|msg| msg.id == DCA_PURCHASE_REPLY_ID));
+
+        // the router sends 495 uluna to the owner for the 990 uusd swapped
+        deps.querier
+            .update_balance("creator", vec![coin(495, "uluna")]);
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: DCA_PURCHASE_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("dca_purchase_settled")
+                .add_attribute("order_id", "1")
+                .add_attribute("owner", "creator")
+                .add_attribute("offer_asset", "990uusd")
+                .add_attribute("received", "495uluna")
+                .add_attribute("internal_match", "false")]
+        );
+
+        let uusd = |amount: u128| Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(amount),
+        };
+        let uluna = |amount: u128| Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::from(amount),
+        };
+
+        let history: Vec<PurchaseRecord> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PurchaseHistory {
+                    token_id: "1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(history[0].offer_asset, uusd(990));
+        assert_eq!(history[0].received, Some(uluna(495)));
+
+        // the cost of the uluna includes the 10 uusd protocol fee, and at 3 uusd per uluna they are
+        // worth 1_485 uusd
+        deps.querier
+            .update_simulation_rate(Decimal::from_ratio(3u128, 1u128));
+        let performance: OrderPerformanceResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OrderPerformance {
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            performance,
+            OrderPerformanceResponse {
+                spent: uusd(1_000),
+                received: uluna(495),
+                average_price: Some(Decimal::from_ratio(1_000u128, 495u128)),
+                current_value: Uint128::from(1_485u128),
+                unrealized_profit: Uint128::from(485u128),
+                unrealized_loss: Uint128::zero(),
+            }
+        );
+    }
+
+    #[test]
+    fn does_update_stats() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        perform_purchase(deps.as_mut());
+
+        // the uluna bought are counted once the router has sent them to the owner
+        deps.querier
+            .update_balance("creator", vec![coin(495, "uluna")]);
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: DCA_PURCHASE_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        let expected = StatsResponse {
+            orders_created: 1,
+            active_orders: 1,
+            purchases: 1,
+            tips_paid: Uint128::from(100u128),
+            pairs: vec![PairStats {
+                initial_asset: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                orders_created: 1,
+                active_orders: 1,
+                purchases: 1,
+                volume: Uint128::from(1000u128),
+                bought: Uint128::from(495u128),
+                tips_paid: Uint128::from(100u128),
+            }],
+        };
+
+        let global_stats: StatsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GlobalStats {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(global_stats, expected);
+
+        let user_stats: StatsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::UserStats {
+                    user: "creator".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(user_stats, expected);
+    }
+
+    #[test]
+    fn does_apply_target_asset_override() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.target_asset_fees = vec![TargetAssetFee {
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    fee_bps: 250,
+                }];
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = perform_purchase(deps.as_mut());
+
+        assert!(fee_sent(&res, 25));
+        assert!(res.attributes.contains(&attr("protocol_fee", "25")));
+    }
+
+    #[test]
+    fn does_not_charge_exempt_address() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.fee_exempt_addresses = vec![Addr::unchecked("creator")];
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = perform_purchase(deps.as_mut());
+
+        assert!(!res.messages.iter().any(|msg| matches!(
+            &msg.msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) if to_address == "treasury"
+        )));
+        assert!(res.attributes.contains(&attr("protocol_fee", "0")));
+    }
+
+    #[test]
+    fn does_share_protocol_fee_with_referrer() {
+        let mut deps = mock_dependencies(&[]);
+        setup_with_referrer(deps.as_mut(), Some("frontend"), ReferralSource::ProtocolFee);
+
+        let res = perform_purchase(deps.as_mut());
+
+        // half of the 10 uusd protocol fee is kept for the referrer
+        assert!(fee_sent(&res, 5));
+        assert!(res.attributes.contains(&attr("referral_reward", "5uusd")));
+
+        let stats = REFERRER_STATS
+            .load(&deps.storage, &Addr::unchecked("frontend"))
+            .unwrap();
+        assert_eq!(stats.orders_referred, 1);
+        assert_eq!(stats.purchases_referred, 1);
+        assert_eq!(stats.claimable[0].amount, Uint128::from(5u128));
+    }
+
+    #[test]
+    fn does_share_bot_tip_with_referrer() {
+        let mut deps = mock_dependencies(&[]);
+        setup_with_referrer(deps.as_mut(), Some("frontend"), ReferralSource::BotTip);
+
+        let res = perform_purchase(deps.as_mut());
+
+        // the full protocol fee is sent, while the bot only receives half of the 100 uusd tip
+        assert!(fee_sent(&res, 10));
+        assert!(res.messages.iter().any(|msg| {
+            msg.msg
+                == CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bot".to_string(),
+                    amount: vec![coin(50, "uusd")],
+                })
+        }));
+        assert!(res.attributes.contains(&attr("referral_reward", "50uusd")));
+    }
+
+    #[test]
+    fn does_not_send_empty_bot_tip() {
+        let mut deps = mock_dependencies(&[]);
+        setup_with_referrer(deps.as_mut(), Some("frontend"), ReferralSource::BotTip);
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.referral_share = Decimal::one();
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = perform_purchase(deps.as_mut());
+
+        // the whole 100 uusd tip is kept for the referrer, so nothing is sent to the bot
+        assert!(!res.messages.iter().any(|msg| matches!(
+            &msg.msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) if to_address == "bot"
+        )));
+        assert!(res.attributes.contains(&attr("referral_reward", "100uusd")));
+    }
+
+    #[test]
+    fn does_give_registered_bots_priority() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.bot_priority_window = 30;
+                Ok(config)
+            })
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("registered", &[]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap();
+
+        perform_purchase(deps.as_mut());
+
+        // the order becomes due again after the 60 second interval
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+
+        let err = try_perform_purchase(deps.as_mut(), env.clone(), "bot").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriorityWindowActive {
+                until: env.block.time.seconds() + 30
+            }
+        );
+
+        try_perform_purchase(deps.as_mut(), env.clone(), "registered").unwrap();
+    }
+
+    #[test]
+    fn does_require_whitelisted_bonded_bots_for_priority() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.bot_priority_window = 30;
+                config.bot_whitelist_enabled = true;
+                Ok(config)
+            })
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("registered", &[]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap();
+
+        perform_purchase(deps.as_mut());
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+
+        // bots that are not whitelisted have no priority
+        let err = try_perform_purchase(deps.as_mut(), env.clone(), "registered").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriorityWindowActive {
+                until: env.block.time.seconds() + 30
+            }
+        );
+
+        BOTS.update::<_, cosmwasm_std::StdError>(
+            deps.as_mut().storage,
+            &Addr::unchecked("registered"),
+            |bot| {
+                let mut bot = bot.unwrap();
+                bot.whitelisted = true;
+                Ok(bot)
+            },
+        )
+        .unwrap();
+        try_perform_purchase(deps.as_mut(), env.clone(), "registered").unwrap();
+
+        // unbonding bots lose their priority
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("registered", &[]),
+            ExecuteMsg::UnregisterBot {},
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(60);
+        let err = try_perform_purchase(deps.as_mut(), env.clone(), "registered").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriorityWindowActive {
+                until: env.block.time.seconds() + 30
+            }
+        );
+    }
+
+    #[test]
+    fn does_open_order_after_priority_window() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.bot_priority_window = 30;
+                Ok(config)
+            })
+            .unwrap();
+
+        perform_purchase(deps.as_mut());
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(90);
+
+        try_perform_purchase(deps.as_mut(), env, "bot").unwrap();
+    }
+
+    #[test]
+    fn does_price_tip_by_volume() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.base_tip = Uint128::from(50u128);
+                config.tip_volume_rate = Decimal::percent(1);
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = perform_purchase(deps.as_mut());
+
+        // 50 base tip + 100 for the single hop + 1% of the 1_000 uusd dca_amount
+        assert!(res.attributes.contains(&attr("tip_cost", "160")));
+    }
+
+    #[test]
+    fn does_cap_tip_at_max_tip() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.tip_volume_rate = Decimal::percent(5);
+                config.max_tip = Uint128::from(120u128);
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = perform_purchase(deps.as_mut());
+
+        assert!(res.attributes.contains(&attr("tip_cost", "120")));
+    }
+
+    #[test]
+    fn does_respect_user_tip_cap() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        CONFIG
+            .update::<_, cosmwasm_std::StdError>(deps.as_mut().storage, |mut config| {
+                config.tip_volume_rate = Decimal::percent(5);
+                Ok(config)
+            })
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: None,
+                max_spread: None,
+                max_tip_per_purchase: Some(Uint128::from(120u128)),
+                tip_mode: None,
+            },
+        )
+        .unwrap();
+
+        let err = try_perform_purchase(deps.as_mut(), mock_env(), "bot").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TipCapExceeded {
+                tip: Uint128::from(150u128),
+                max: Uint128::from(120u128),
+            }
+        );
+    }
+
+    #[test]
+    fn does_raise_auction_tip_over_time() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: None,
+                max_spread: None,
+                max_tip_per_purchase: None,
+                tip_mode: Some(TipMode::DutchAuction {
+                    start_tip: Uint128::from(100u128),
+                    max_tip: Uint128::from(500u128),
+                    duration: 100,
+                }),
+            },
+        )
+        .unwrap();
+
+        // the first purchase is made as soon as the order becomes due
+        let res = perform_purchase(deps.as_mut());
+        assert!(res.attributes.contains(&attr("tip_cost", "100")));
+
+        // the order becomes due after 60 seconds, and is left unexecuted for a further 25
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(85);
+        let res = try_perform_purchase(deps.as_mut(), env.clone(), "bot").unwrap();
+        assert!(res.attributes.contains(&attr("tip_cost", "200")));
+
+        // the tip stops rising once it reaches the max_tip
+        env.block.time = env.block.time.plus_seconds(1_000);
+        let res = try_perform_purchase(deps.as_mut(), env, "bot").unwrap();
+        assert!(res.attributes.contains(&attr("tip_cost", "500")));
+    }
+
+    #[test]
+    fn does_prefer_order_overrides() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: Some(3),
+                max_spread: Some(Decimal::percent(10)),
+                max_tip_per_purchase: None,
+                tip_mode: None,
+            },
+        )
+        .unwrap();
+
+        let mut order = user_orders(&deps.storage, &Addr::unchecked("creator")).unwrap()[0].clone();
+        order.max_hops = Some(1);
+        order.max_spread = Some(Decimal::percent(2));
+        save_order(deps.as_mut().storage, &order).unwrap();
+
+        // does apply the order max_spread over the user max_spread
+        let res = perform_purchase(deps.as_mut());
+        let max_spread = res
+            .messages
+            .iter()
+            .find_map(|msg| match &msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr, msg, ..
+                }) if contract_addr == "router" => match from_binary(msg).unwrap() {
+                    RouterExecuteMsg::ExecuteSwapOperations { max_spread, .. } => max_spread,
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(max_spread, Decimal::percent(2));
+
+        // does apply the order max_hops over the user max_hops
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bot", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: "creator".to_string(),
+                hops: vec![
+                    SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "uusd".to_string(),
+                    },
+                    SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "uluna".to_string(),
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MaxHopsAssertion { hops: 2 });
+    }
+
+    #[test]
+    fn does_make_up_for_missed_purchases() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        let mut order =
+            user_orders(deps.as_ref().storage, &Addr::unchecked("creator")).unwrap()[0].clone();
+        order.catch_up_purchases = 2;
+        save_order(deps.as_mut().storage, &order).unwrap();
+
+        // the purchases made up for are due straight away, regardless of the interval
+        perform_purchase(deps.as_mut());
+        perform_purchase(deps.as_mut());
+
+        let order = &user_orders(deps.as_ref().storage, &Addr::unchecked("creator")).unwrap()[0];
+        assert_eq!(order.catch_up_purchases, 0);
+        assert_eq!(order.initial_asset.amount, Uint128::from(8_000u128));
+
+        assert_eq!(
+            try_perform_purchase(deps.as_mut(), mock_env(), "bot").unwrap_err(),
+            ContractError::PurchaseTooEarly {}
+        );
+    }
+
+    #[test]
+    fn does_not_purchase_paused_order() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::PauseDcaOrder { id: 1 },
+        )
+        .unwrap();
+
+        assert_eq!(
+            try_perform_purchase(deps.as_mut(), mock_env(), "bot").unwrap_err(),
+            ContractError::OrderPaused { id: 1 }
+        );
+
+        // paused orders are only returned when asked for
+        let user_orders = |include_paused: bool| -> Vec<DcaQueryInfo> {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::UserDcaOrders {
+                        user: "creator".to_string(),
+                        include_paused,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        assert!(user_orders(false).is_empty());
+        assert_eq!(user_orders(true)[0].info.id, 1);
+    }
+
+    fn native_hop(offer_denom: &str, ask_denom: &str) -> SwapOperation {
+        SwapOperation::NativeSwap {
+            offer_denom: offer_denom.to_string(),
+            ask_denom: ask_denom.to_string(),
+        }
+    }
+
+    fn set_route_preference(deps: DepsMut, route_preference: RoutePreference) {
+        let mut order = user_orders(deps.storage, &Addr::unchecked("creator")).unwrap()[0].clone();
+        order.route_preference = Some(route_preference);
+        save_order(deps.storage, &order).unwrap();
+    }
+
+    fn try_perform_route(
+        deps: DepsMut,
+        hops: Vec<SwapOperation>,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("bot", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: "creator".to_string(),
+                hops,
+            },
+        )
+    }
+
+    #[test]
+    fn does_enforce_pinned_route() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        let pinned_hops = vec![native_hop("uusd", "ukrw"), native_hop("ukrw", "uluna")];
+        set_route_preference(
+            deps.as_mut(),
+            RoutePreference::PinnedRoute {
+                hops: pinned_hops.clone(),
+            },
+        );
+
+        let err = try_perform_route(deps.as_mut(), vec![native_hop("uusd", "uluna")]).unwrap_err();
+        assert_eq!(err, ContractError::PinnedRouteMismatch {});
+
+        // does allow the pinned route through a token that is not whitelisted
+        try_perform_route(deps.as_mut(), pinned_hops).unwrap();
+    }
+
+    #[test]
+    fn does_enforce_allowed_intermediates() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        set_route_preference(
+            deps.as_mut(),
+            RoutePreference::AllowedIntermediates { tokens: vec![] },
+        );
+
+        let err = try_perform_route(
+            deps.as_mut(),
+            vec![native_hop("uusd", "uusd"), native_hop("uusd", "uluna")],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IntermediateNotAllowed {
+                token: "uusd".to_string()
+            }
+        );
+
+        // does still allow direct routes
+        try_perform_route(deps.as_mut(), vec![native_hop("uusd", "uluna")]).unwrap();
+    }
+}
@@ -0,0 +1,867 @@
+use astroport::{
+    asset::{Asset, UUSD_DENOM},
+    router::SwapOperation,
+};
+use astroport_dca::dca::VaultPurchaseRecord;
+use astroport_dca::evaluation::{
+    check_eligibility, purchase_amounts, PurchaseAmounts, BPS_DENOMINATOR,
+};
+use cosmwasm_std::{
+    attr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, StdResult,
+    SubMsg, Uint128,
+};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::{route, vault_purchase_event, vault_purchase_settled_event},
+    router_swap::router_swap_message,
+    send_asset::send_asset,
+    state::{
+        update_global_stats, vault_positions, vault_proceeds_index, PendingVaultPurchase, BOTS,
+        CONFIG, PENDING_VAULT_PURCHASE, VAULTS, VAULT_EXPIRING_SHARES, VAULT_POSITIONS,
+        VAULT_PROCEEDS_INDEX, VAULT_PURCHASE_HISTORY, VAULT_PURCHASE_REPLY_ID,
+    },
+    tip::calculate_vault_tip,
+    validation::validate_hop_route,
+};
+
+/// ## Description
+/// Performs the aggregated purchase of a vault for all its participants using the hop route
+/// specified.
+///
+/// The tip of the purchase is charged to the positions pro rata to their shares, each out of its
+/// own tip deposit. Positions whose deposit can not cover their share are suspended from the
+/// purchases of the vault, and the bot is paid the tip charged to the others. The protocol fee is
+/// not charged on the shares of fee exempt participants, which get that part of their shares
+/// returned instead of swapped.
+///
+/// The swap is sent to the router as a submessage with the `max_spread` of the vault, and the
+/// `target_asset` it returns to the contract is credited to the shares of the vault in
+/// [`vault_purchase_reply`].
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot who is performing the purchase, who will be rewarded
+/// with a uusd tip from the tip deposits of the positions.
+///
+/// * `vault_id` - The id of the vault being purchased.
+///
+/// * `hops` - A [`Vec<SwapOperation>`] of the hop operations to complete in the swap to purchase
+/// the target asset.
+pub fn perform_vault_purchase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    vault_id: u64,
+    hops: Vec<SwapOperation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut vault = VAULTS.load(deps.storage, U64Key::new(vault_id))?;
+
+    // validate the hop route against the vault assets and the token whitelist
+    validate_hop_route(
+        deps.storage,
+        &config,
+        &vault.initial_asset,
+        &vault.target_asset,
+        &hops,
+    )?;
+
+    // check that it has been long enough between vault purchases, and that the sender has
+    // priority if the vault only recently became due
+    let is_priority_bot = matches!(
+        BOTS.may_load(deps.storage, &info.sender)?,
+        Some(bot) if config.has_bot_priority(&bot)
+    );
+    check_eligibility(
+        vault.last_purchase,
+        vault.interval,
+        config.bot_priority_window,
+        is_priority_bot,
+        env.block.time.seconds(),
+    )?;
+
+    // remove the shares of positions which have used up their deposit before this purchase
+    vault.epoch += 1;
+    let expiring_key = (U64Key::new(vault_id), U64Key::new(vault.epoch));
+    if let Some(expiring) = VAULT_EXPIRING_SHARES.may_load(deps.storage, expiring_key.clone())? {
+        vault.total_shares = vault.total_shares.checked_sub(expiring)?;
+        VAULT_EXPIRING_SHARES.remove(deps.storage, expiring_key);
+    }
+
+    if vault.total_shares.is_zero() {
+        return Err(ContractError::EmptyVault {});
+    }
+
+    // charge the tip of the purchase to the positions taking part pro rata to their shares, each
+    // out of its own tip deposit, suspending the positions whose deposit can not cover their share
+    let tip_cost = calculate_vault_tip(&deps.querier, &config, &vault, hops.len() as u32)?;
+    let fee_bps = config.target_fee_bps(&vault.target_asset);
+    let total_shares = vault.total_shares;
+    let mut tip_paid = Uint128::zero();
+    let mut exempt_fee = Uint128::zero();
+
+    for (owner, mut position) in vault_positions(deps.storage, vault_id)? {
+        if !position.is_active(&vault) {
+            continue;
+        }
+
+        let tip_share = tip_cost.multiply_ratio(position.shares, total_shares);
+        match position.tip_deposit.checked_sub(tip_share) {
+            Ok(tip_deposit) => {
+                position.tip_deposit = tip_deposit;
+                tip_paid = tip_paid.checked_add(tip_share)?;
+
+                // the part of the shares of an exempt owner that would pay the protocol fee is
+                // returned to the position
+                if config.is_fee_exempt(&owner) {
+                    let fee_share = position.shares.multiply_ratio(fee_bps, BPS_DENOMINATOR);
+                    position.refund = position.refund.checked_add(fee_share)?;
+                    exempt_fee = exempt_fee.checked_add(fee_share)?;
+                }
+            }
+            Err(_) => {
+                VAULT_EXPIRING_SHARES.update(
+                    deps.storage,
+                    (U64Key::new(vault_id), U64Key::new(position.end_epoch + 1)),
+                    |shares| -> StdResult<_> {
+                        Ok(shares.unwrap_or_default().checked_sub(position.shares)?)
+                    },
+                )?;
+                vault.total_shares = vault.total_shares.checked_sub(position.shares)?;
+                position.suspend(&vault)?;
+            }
+        }
+
+        VAULT_POSITIONS.save(deps.storage, (U64Key::new(vault_id), &owner), &position)?;
+    }
+
+    if vault.total_shares.is_zero() {
+        return Err(ContractError::EmptyVault {});
+    }
+    vault.tip_balance = vault.tip_balance.checked_sub(tip_paid)?;
+
+    // take the protocol fee out of the purchase, swapping only the remainder
+    let PurchaseAmounts {
+        protocol_fee,
+        swap_amount,
+        ..
+    } = purchase_amounts(vault.total_shares, fee_bps, None)?;
+    let protocol_fee = protocol_fee.checked_sub(exempt_fee)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !protocol_fee.is_zero() {
+        messages.push(send_asset(
+            &Asset {
+                info: vault.initial_asset.clone(),
+                amount: protocol_fee,
+            },
+            &config.fee_collector,
+        )?);
+    }
+
+    // the proceeds of the swap are sent back to the contract, to be measured in the reply
+    let route = route(&vault.initial_asset, &hops);
+    let swap_message = router_swap_message(
+        &config.router_addr,
+        &Asset {
+            info: vault.initial_asset.clone(),
+            amount: swap_amount,
+        },
+        hops,
+        &env.contract.address,
+        vault.max_spread.unwrap_or(config.max_spread),
+    )?;
+
+    PENDING_VAULT_PURCHASE.save(
+        deps.storage,
+        &PendingVaultPurchase {
+            vault_id,
+            epoch: vault.epoch,
+            shares: vault.total_shares,
+            swap_amount,
+            tip: tip_paid,
+            balance_before: vault
+                .target_asset
+                .query_pool(&deps.querier, env.contract.address.clone())?,
+        },
+    )?;
+
+    vault.last_purchase = env.block.time.seconds();
+    VAULTS.save(deps.storage, U64Key::new(vault_id), &vault)?;
+
+    update_global_stats(
+        deps.storage,
+        &vault.initial_asset,
+        &vault.target_asset,
+        |stats| {
+            stats.purchases += 1;
+            stats.volume = stats.volume.checked_add(vault.total_shares)?;
+            stats.tips_paid = stats.tips_paid.checked_add(tip_paid)?;
+            Ok(())
+        },
+    )?;
+
+    let event = vault_purchase_event(
+        &vault,
+        protocol_fee,
+        swap_amount,
+        &route,
+        tip_paid,
+        &info.sender,
+    );
+
+    let mut response =
+        Response::new()
+            .add_messages(messages)
+            .add_submessage(SubMsg::reply_on_success(
+                swap_message,
+                VAULT_PURCHASE_REPLY_ID,
+            ));
+    if !tip_paid.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                amount: tip_paid,
+                denom: UUSD_DENOM.to_string(),
+            }],
+        });
+    }
+
+    Ok(response.add_event(event).add_attributes(vec![
+        attr("action", "perform_vault_purchase"),
+        attr("vault_id", vault_id.to_string()),
+        attr("epoch", vault.epoch.to_string()),
+        attr("shares", vault.total_shares),
+        attr("tip_cost", tip_paid),
+        attr("protocol_fee", protocol_fee),
+    ]))
+}
+
+/// ## Description
+/// Credits the `target_asset` returned by the router for a vault purchase to the shares that took
+/// part in it, by adding the proceeds per share to the vault proceeds index, and records the
+/// purchase in the purchase history of the vault.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+pub fn vault_purchase_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_VAULT_PURCHASE.load(deps.storage)?;
+    PENDING_VAULT_PURCHASE.remove(deps.storage);
+
+    let vault = VAULTS.load(deps.storage, U64Key::new(pending.vault_id))?;
+
+    let proceeds = vault
+        .target_asset
+        .query_pool(&deps.querier, env.contract.address)?
+        .checked_sub(pending.balance_before)?;
+
+    let index = vault_proceeds_index(deps.storage, pending.vault_id, pending.epoch - 1)?
+        + Decimal::from_ratio(proceeds, pending.shares);
+    VAULT_PROCEEDS_INDEX.save(
+        deps.storage,
+        (U64Key::new(pending.vault_id), U64Key::new(pending.epoch)),
+        &index,
+    )?;
+
+    let record = VaultPurchaseRecord {
+        epoch: pending.epoch,
+        time: env.block.time.seconds(),
+        shares: pending.shares,
+        offer_asset: Asset {
+            info: vault.initial_asset.clone(),
+            amount: pending.swap_amount,
+        },
+        received: Asset {
+            info: vault.target_asset.clone(),
+            amount: proceeds,
+        },
+        tip: pending.tip,
+    };
+    VAULT_PURCHASE_HISTORY.save(
+        deps.storage,
+        (U64Key::new(pending.vault_id), U64Key::new(pending.epoch)),
+        &record,
+    )?;
+
+    update_global_stats(
+        deps.storage,
+        &vault.initial_asset,
+        &vault.target_asset,
+        |stats| {
+            stats.bought = stats.bought.checked_add(proceeds)?;
+            Ok(())
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(vault_purchase_settled_event(&vault, &record))
+        .add_attributes(vec![
+            attr("action", "vault_purchase_reply"),
+            attr("vault_id", pending.vault_id.to_string()),
+            attr("epoch", pending.epoch.to_string()),
+            attr("proceeds", proceeds),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{
+        asset::Asset,
+        router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    };
+    use astroport_dca::dca::{
+        ExecuteMsg, QueryMsg, StatsResponse, VaultPositionResponse, VaultPurchaseRecord,
+    };
+    use cosmwasm_std::{
+        attr, coin, from_binary,
+        testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR},
+        to_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal, Env, OwnedDeps, Reply,
+        Response, StdError, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+    };
+
+    use crate::{
+        contract::{execute, query, reply},
+        error::ContractError,
+        mock_querier::{
+            env_at, mock_dependencies_with_owner, setup_contract, uluna, uusd, WasmMockQuerier,
+        },
+        state::{CONFIG, VAULT_PURCHASE_REPLY_ID},
+    };
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, WasmMockQuerier>;
+
+    fn uusd_to_uluna() -> Vec<SwapOperation> {
+        vec![SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        }]
+    }
+
+    fn deposit(deps: &mut MockDeps, user: &str, amount: u128) {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[coin(amount, "uusd")]),
+            ExecuteMsg::DepositVault {
+                initial_asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(amount),
+                },
+                target_asset: uluna(),
+                interval: 100,
+                dca_amount: Uint128::from(1_000u128),
+                max_spread: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn add_tip(deps: &mut MockDeps, user: &str, amount: u128) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[coin(amount, "uusd")]),
+            ExecuteMsg::AddVaultTip { vault_id: 1 },
+        )
+    }
+
+    fn setup_vault() -> MockDeps {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        deposit(&mut deps, "alice", 3_000);
+        deposit(&mut deps, "bob", 2_000);
+
+        add_tip(&mut deps, "alice", 1_000).unwrap();
+        add_tip(&mut deps, "bob", 500).unwrap();
+
+        deps
+    }
+
+    fn purchase(
+        deps: &mut MockDeps,
+        env: Env,
+        hops: Vec<SwapOperation>,
+    ) -> Result<Response, ContractError> {
+        purchase_vault(deps, env, 1, hops)
+    }
+
+    fn purchase_vault(
+        deps: &mut MockDeps,
+        env: Env,
+        vault_id: u64,
+        hops: Vec<SwapOperation>,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("bot", &[]),
+            ExecuteMsg::PerformVaultPurchase { vault_id, hops },
+        )
+    }
+
+    /// Completes a purchase by setting the uluna balance of the contract to `balance` and replying
+    /// to the router swap.
+    fn purchase_with_balance(deps: &mut MockDeps, env: Env, balance: u128) -> Response {
+        let res = purchase(deps, env.clone(), uusd_to_uluna()).unwrap();
+
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, vec![coin(balance, "uluna")]);
+        reply(
+            deps.as_mut(),
+            env,
+            Reply {
+                id: VAULT_PURCHASE_REPLY_ID,
+                result: ContractResult::Ok(SubMsgExecutionResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        res
+    }
+
+    fn position(deps: &MockDeps, user: &str) -> VaultPositionResponse {
+        from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::VaultPosition {
+                    vault_id: 1,
+                    user: user.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn does_aggregate_purchases() {
+        let mut deps = setup_vault();
+
+        let res = purchase_with_balance(&mut deps, mock_env(), 4_000);
+
+        // the shares of both participants are swapped in a single router message
+        assert_eq!(
+            res.messages[0],
+            SubMsg::reply_on_success(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "router".to_string(),
+                    funds: vec![coin(2_000, "uusd")],
+                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                        operations: uusd_to_uluna(),
+                        minimum_receive: None,
+                        to: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                        max_spread: Some(Decimal::percent(5)),
+                    })
+                    .unwrap(),
+                }),
+                VAULT_PURCHASE_REPLY_ID,
+            )
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bot".to_string(),
+                amount: vec![coin(100, "uusd")],
+            })
+        );
+
+        // the proceeds are distributed pro rata
+        assert_eq!(
+            position(&deps, "alice"),
+            VaultPositionResponse {
+                shares: Uint128::from(1_000u128),
+                remaining: Uint128::from(2_000u128),
+                claimable: Uint128::from(2_000u128),
+                tip_balance: Uint128::from(950u128),
+                suspended: false,
+            }
+        );
+        assert_eq!(position(&deps, "bob").claimable, Uint128::from(2_000u128));
+
+        // the purchase is recorded in the vault history and the global statistics
+        let history: Vec<VaultPurchaseRecord> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::VaultPurchaseHistory {
+                    vault_id: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            history,
+            vec![VaultPurchaseRecord {
+                epoch: 1,
+                time: mock_env().block.time.seconds(),
+                shares: Uint128::from(2_000u128),
+                offer_asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(2_000u128),
+                },
+                received: Asset {
+                    info: uluna(),
+                    amount: Uint128::from(4_000u128),
+                },
+                tip: Uint128::from(100u128),
+            }]
+        );
+
+        let stats: StatsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GlobalStats {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats.purchases, 1);
+        assert_eq!(stats.pairs[0].volume, Uint128::from(2_000u128));
+        assert_eq!(stats.pairs[0].bought, Uint128::from(4_000u128));
+        assert_eq!(stats.tips_paid, Uint128::from(100u128));
+
+        let event = res
+            .events
+            .iter()
+            .find(|event| event.ty == "dca_vault_purchase")
+            .unwrap();
+        assert!(event.attributes.contains(&attr("route", "uusd>uluna")));
+        assert!(event.attributes.contains(&attr("tip_balance", "1400")));
+
+        // the vault can not be purchased again until the interval has passed
+        assert_eq!(
+            purchase(&mut deps, mock_env(), uusd_to_uluna()).unwrap_err(),
+            ContractError::PurchaseTooEarly {}
+        );
+    }
+
+    #[test]
+    fn does_withdraw_and_claim() {
+        let mut deps = setup_vault();
+        purchase_with_balance(&mut deps, mock_env(), 4_000);
+
+        // bob leaves with his unspent uusd and purchased uluna
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::WithdrawVault { vault_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: vec![coin(1_000, "uusd")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: vec![coin(2_000, "uluna")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: vec![coin(450, "uusd")],
+                }),
+            ]
+        );
+
+        // only the shares of alice take part in the next purchase
+        let res = purchase_with_balance(&mut deps, env_at(200), 5_000);
+        assert!(res.attributes.contains(&attr("shares", "1000")));
+        assert_eq!(position(&deps, "alice").claimable, Uint128::from(3_000u128));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ClaimVaultProceeds { vault_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: vec![coin(3_000, "uluna")],
+            })
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[]),
+                ExecuteMsg::ClaimVaultProceeds { vault_id: 1 },
+            )
+            .unwrap_err(),
+            ContractError::NoVaultProceeds {}
+        );
+
+        // alice takes part in one more purchase, after which the vault is empty
+        purchase_with_balance(&mut deps, env_at(400), 6_000);
+        assert_eq!(
+            position(&deps, "alice"),
+            VaultPositionResponse {
+                shares: Uint128::from(1_000u128),
+                remaining: Uint128::zero(),
+                claimable: Uint128::from(1_000u128),
+                tip_balance: Uint128::from(750u128),
+                suspended: false,
+            }
+        );
+        assert_eq!(
+            purchase(&mut deps, env_at(600), uusd_to_uluna()).unwrap_err(),
+            ContractError::EmptyVault {}
+        );
+    }
+
+    #[test]
+    fn does_validate_vault_route() {
+        let mut deps = setup_vault();
+
+        assert_eq!(
+            purchase(
+                &mut deps,
+                mock_env(),
+                vec![SwapOperation::NativeSwap {
+                    offer_denom: "ukrw".to_string(),
+                    ask_denom: "uluna".to_string(),
+                }],
+            )
+            .unwrap_err(),
+            ContractError::InitialAssetAssertion {}
+        );
+        assert_eq!(
+            purchase(
+                &mut deps,
+                mock_env(),
+                vec![
+                    SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "ukrw".to_string(),
+                    },
+                    SwapOperation::NativeSwap {
+                        offer_denom: "ukrw".to_string(),
+                        ask_denom: "uluna".to_string(),
+                    },
+                ],
+            )
+            .unwrap_err(),
+            ContractError::InvalidHopRoute {
+                token: "ukrw".to_string(),
+            }
+        );
+
+        // a participant can only have one position in each vault
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[coin(1_000, "uusd")]),
+                ExecuteMsg::DepositVault {
+                    initial_asset: Asset {
+                        info: uusd(),
+                        amount: Uint128::from(1_000u128),
+                    },
+                    target_asset: uluna(),
+                    interval: 100,
+                    dca_amount: Uint128::from(1_000u128),
+                    max_spread: None,
+                },
+            )
+            .unwrap_err(),
+            ContractError::AlreadyDeposited {}
+        );
+    }
+
+    #[test]
+    fn does_charge_vault_tips_pro_rata() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        deposit(&mut deps, "alice", 3_000);
+        deposit(&mut deps, "bob", 2_000);
+        add_tip(&mut deps, "alice", 1_000).unwrap();
+
+        // only participants can add a tip to the vault
+        assert_eq!(
+            add_tip(&mut deps, "carol", 1_000).unwrap_err(),
+            ContractError::NonexistentVaultPosition {}
+        );
+
+        // bob has no tip deposit to pay his share of the tip, so he is suspended and the bot is
+        // only paid the share of alice
+        let res = purchase_with_balance(&mut deps, mock_env(), 4_000);
+        assert!(res.attributes.contains(&attr("shares", "1000")));
+        assert!(res.attributes.contains(&attr("tip_cost", "50")));
+        assert_eq!(
+            position(&deps, "bob"),
+            VaultPositionResponse {
+                shares: Uint128::from(1_000u128),
+                remaining: Uint128::from(2_000u128),
+                claimable: Uint128::zero(),
+                tip_balance: Uint128::zero(),
+                suspended: true,
+            }
+        );
+        assert_eq!(
+            add_tip(&mut deps, "bob", 50).unwrap_err(),
+            ContractError::SuspendedVaultPosition {}
+        );
+
+        // bob gets back his whole deposit
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::WithdrawVault { vault_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: vec![coin(2_000, "uusd")],
+            })]
+        );
+
+        // alice gets the whole purchase and the part of her tip deposit that was not charged
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::WithdrawVault { vault_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: vec![coin(2_000, "uusd")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: vec![coin(4_000, "uluna")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: vec![coin(950, "uusd")],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_charge_fee_to_exempt_participants() {
+        let mut deps = setup_vault();
+
+        CONFIG
+            .update::<_, StdError>(deps.as_mut().storage, |mut config| {
+                config.protocol_fee_bps = 100;
+                config.fee_exempt_addresses = vec![Addr::unchecked("alice")];
+                Ok(config)
+            })
+            .unwrap();
+
+        // only the fee on the shares of bob is sent to the fee collector, and the part of the
+        // shares of alice that would pay the fee is not swapped
+        let res = purchase_with_balance(&mut deps, mock_env(), 3_960);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![coin(10, "uusd")],
+            })
+        );
+        match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => {
+                assert_eq!(funds, &vec![coin(1_980, "uusd")])
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // alice keeps the uusd she did not pay as a fee
+        let alice = position(&deps, "alice");
+        assert_eq!(alice.remaining, Uint128::from(2_010u128));
+        assert_eq!(alice.claimable, Uint128::from(1_980u128));
+
+        let bob = position(&deps, "bob");
+        assert_eq!(bob.remaining, Uint128::from(1_000u128));
+        assert_eq!(bob.claimable, Uint128::from(1_980u128));
+    }
+
+    #[test]
+    fn does_purchase_with_vault_max_spread() {
+        let mut deps = setup_vault();
+
+        // a deposit with another max_spread goes into a separate vault
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("carol", &[coin(1_000, "uusd")]),
+            ExecuteMsg::DepositVault {
+                initial_asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(1_000u128),
+                },
+                target_asset: uluna(),
+                interval: 100,
+                dca_amount: Uint128::from(1_000u128),
+                max_spread: Some(Decimal::percent(2)),
+            },
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("vault_id", "2")));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("carol", &[coin(100, "uusd")]),
+            ExecuteMsg::AddVaultTip { vault_id: 2 },
+        )
+        .unwrap();
+
+        let res = purchase_vault(&mut deps, mock_env(), 2, uusd_to_uluna()).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(msg).unwrap() {
+                RouterExecuteMsg::ExecuteSwapOperations { max_spread, .. } => {
+                    assert_eq!(max_spread, Some(Decimal::percent(2)))
+                }
+                _ => panic!("unexpected router message"),
+            },
+            _ => panic!("unexpected message"),
+        }
+    }
+}
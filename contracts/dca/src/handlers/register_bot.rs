@@ -0,0 +1,205 @@
+use astroport::asset::UUSD_DENOM;
+use astroport_dca::dca::BotInfo;
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+
+use crate::{
+    error::ContractError,
+    events::register_bot_event,
+    state::{BOTS, CONFIG},
+};
+
+/// ## Description
+/// Registers the sender as a bot, which gives them priority to perform DCA purchases once an order
+/// becomes due.
+///
+/// Any uusd sent is added to the bots bond. If the bot is already registered, the bond is topped
+/// up instead, and any unbonding of the bond is cancelled.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot who wants to register, which contains the uusd bond.
+pub fn register_bot(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == UUSD_DENOM)
+        .map(|coin| coin.amount)
+        .unwrap_or_else(Uint128::zero);
+
+    let mut bot = BOTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or(BotInfo {
+            address: info.sender.clone(),
+            bond: Uint128::zero(),
+            registered_at: env.block.time.seconds(),
+            unbonding_until: None,
+            whitelisted: false,
+        });
+
+    bot.bond = bot.bond.checked_add(amount)?;
+    bot.unbonding_until = None;
+
+    if bot.bond < config.min_bot_bond {
+        return Err(ContractError::InsufficientBotBond {
+            min: config.min_bot_bond,
+        });
+    }
+
+    BOTS.save(deps.storage, &info.sender, &bot)?;
+
+    Ok(Response::new()
+        .add_event(register_bot_event(&bot))
+        .add_attributes(vec![
+            attr("action", "register_bot"),
+            attr("bot", info.sender),
+            attr("bond", bot.bond),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr, BankMsg, CosmosMsg, DepsMut, Uint128,
+    };
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        mock_querier::{instantiate_msg, mock_dependencies_with_owner, setup_contract_with},
+        state::BOTS,
+    };
+
+    fn setup(deps: DepsMut) {
+        setup_contract_with(
+            deps,
+            InstantiateMsg {
+                bot_unbonding_period: 100,
+                ..instantiate_msg()
+            },
+        );
+    }
+
+    #[test]
+    fn does_register_and_top_up_bot() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[coin(1_000, "uusd")]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[coin(500, "uusd")]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap();
+
+        let bot = BOTS.load(&deps.storage, &Addr::unchecked("bot")).unwrap();
+        assert_eq!(bot.bond, Uint128::from(1_500u128));
+    }
+
+    #[test]
+    fn does_require_min_bond() {
+        let mut deps = mock_dependencies(&[]);
+        setup(deps.as_mut());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[coin(999, "uusd")]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientBotBond {
+                min: Uint128::from(1_000u128)
+            }
+        );
+    }
+
+    #[test]
+    fn does_return_bond_after_unbonding() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[coin(1_000, "uusd")]),
+            ExecuteMsg::RegisterBot {},
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[]),
+            ExecuteMsg::UnregisterBot {},
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+
+        let unbonding_until = mock_env().block.time.seconds() + 100;
+        let bot = BOTS.load(&deps.storage, &Addr::unchecked("bot")).unwrap();
+        assert_eq!(bot.unbonding_until, Some(unbonding_until));
+
+        // the bond stays slashable while it unbonds
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SlashBot {
+                bot: "bot".to_string(),
+                amount: Uint128::from(400u128),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bot", &[]),
+                ExecuteMsg::ClaimBotBond {},
+            )
+            .unwrap_err(),
+            ContractError::BondNotUnbonded {
+                until: unbonding_until
+            }
+        );
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bot", &[]),
+            ExecuteMsg::ClaimBotBond {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bot".to_string(),
+                amount: vec![coin(600, "uusd")],
+            })
+        );
+        assert!(!BOTS.has(&deps.storage, &Addr::unchecked("bot")));
+    }
+}
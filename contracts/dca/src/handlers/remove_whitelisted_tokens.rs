@@ -0,0 +1,176 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Order, Response};
+
+use crate::{
+    assert_owner::assert_owner, error::ContractError, events::remove_whitelisted_tokens_event,
+    state::WHITELISTED_TOKENS, validation::validate_asset_info,
+};
+
+/// ## Description
+/// Removes tokens from the whitelist that can be used in a hop route for DCA purchases.
+///
+/// Each token is validated in the same way as when it is added, and tokens that are not
+/// whitelisted are rejected, so that a mistyped token is not silently kept in the whitelist. The
+/// last whitelisted token can not be removed.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the factory contract owner.
+///
+/// * `tokens` - The [`Vec<AssetInfo>`] of tokens to remove from the whitelist.
+pub fn remove_whitelisted_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let mut removed = Vec::new();
+    for token in tokens {
+        let token = validate_asset_info(deps.api, token)?;
+
+        if !WHITELISTED_TOKENS.has(deps.storage, token.as_bytes()) {
+            return Err(ContractError::TokenNotWhitelisted {
+                token: token.to_string(),
+            });
+        }
+
+        WHITELISTED_TOKENS.remove(deps.storage, token.as_bytes());
+        removed.push(token.to_string());
+    }
+
+    // check that multi-hop routes are still possible
+    if WHITELISTED_TOKENS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none()
+    {
+        return Err(ContractError::EmptyWhitelist {});
+    }
+
+    Ok(Response::new()
+        .add_event(remove_whitelisted_tokens_event(&removed))
+        .add_attributes(vec![
+            attr("action", "remove_whitelisted_tokens"),
+            attr("removed", removed.join(",")),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::AssetInfo;
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{
+        attr,
+        testing::{mock_env, mock_info},
+        Addr,
+    };
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::is_whitelisted_asset,
+    };
+
+    #[test]
+    fn does_remove_whitelisted_tokens() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::AddWhitelistedTokens {
+                tokens: vec![token.clone()],
+            },
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::RemoveWhitelistedTokens {
+            tokens: vec![token.clone()],
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("removed", "token")));
+        assert!(!is_whitelisted_asset(&deps.storage, &token));
+
+        // does reject tokens that are not whitelisted
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TokenNotWhitelisted {
+                token: "token".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_validate_removed_tokens() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::AddWhitelistedTokens {
+                tokens: vec![AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                }],
+            },
+        )
+        .unwrap();
+
+        // an address in another case does not silently miss the whitelisted token
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::RemoveWhitelistedTokens {
+                tokens: vec![AssetInfo::Token {
+                    contract_addr: Addr::unchecked("TOKEN"),
+                }],
+            },
+        )
+        .unwrap_err();
+        assert!(is_whitelisted_asset(
+            &deps.storage,
+            &AssetInfo::Token {
+                contract_addr: Addr::unchecked("token"),
+            }
+        ));
+    }
+
+    #[test]
+    fn does_not_empty_whitelist() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::RemoveWhitelistedTokens {
+                tokens: vec![AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::EmptyWhitelist {});
+    }
+}
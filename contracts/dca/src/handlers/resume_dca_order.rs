@@ -0,0 +1,200 @@
+use astroport_dca::{dca::OperatorPermission, evaluation::missed_purchases};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::resume_order_event,
+    resolve_owner::resolve_owner,
+    state::{dca_orders, save_order},
+};
+
+/// ## Description
+/// Resumes a paused DCA order.
+///
+/// If `catch_up` is set, the order keeps its schedule, and every purchase that became due while it
+/// was paused is due straight away, up to the purchases its balance can pay for and at most
+/// [`MAX_CATCH_UP_PURCHASES`](astroport_dca::evaluation::MAX_CATCH_UP_PURCHASES) purchases.
+/// Otherwise, the schedule of the order is moved back by the time it was paused for, which does
+/// not count towards its interval.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the owner of the order, or an operator approved to modify
+/// orders for the owner.
+///
+/// * `id` - The id of the order.
+///
+/// * `catch_up` - Whether the order keeps its schedule while it was paused.
+pub fn resume_dca_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    catch_up: bool,
+) -> Result<Response, ContractError> {
+    let mut order = dca_orders().load(deps.storage, U64Key::new(id))?;
+    resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        Some(order.owner.to_string()),
+        OperatorPermission::ModifyOrder,
+    )?;
+
+    let paused_at = order
+        .paused_at
+        .ok_or(ContractError::OrderNotPaused { id })?;
+
+    let now = env.block.time.seconds();
+    if catch_up {
+        order.catch_up_purchases = missed_purchases(&order, now);
+    } else {
+        let paused_for = now
+            .checked_sub(paused_at)
+            .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, now, paused_at))?;
+        order.last_purchase += paused_for;
+        order.start_time += paused_for;
+    }
+    order.paused_at = None;
+    save_order(deps.storage, &order)?;
+
+    Ok(Response::new()
+        .add_event(resume_order_event(&order, catch_up))
+        .add_attributes(vec![
+            attr("action", "resume_dca_order"),
+            attr("order_id", id.to_string()),
+            attr("catch_up", catch_up.to_string()),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{DcaInfo, ExecuteMsg};
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_env, mock_info},
+        DepsMut, Uint128,
+    };
+    use cw_storage_plus::U64Key;
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        mock_querier::{env_at, mock_dependencies_with_owner, setup_contract},
+        state::dca_orders,
+    };
+
+    fn pause_and_resume(mut deps: DepsMut, paused_for: u64, catch_up: bool) -> DcaInfo {
+        execute(
+            deps.branch(),
+            env_at(100),
+            mock_info("alice", &[]),
+            ExecuteMsg::PauseDcaOrder { id: 1 },
+        )
+        .unwrap();
+        execute(
+            deps.branch(),
+            env_at(100 + paused_for),
+            mock_info("alice", &[]),
+            ExecuteMsg::ResumeDcaOrder { id: 1, catch_up },
+        )
+        .unwrap();
+
+        dca_orders().load(deps.storage, U64Key::new(1)).unwrap()
+    }
+
+    #[test]
+    fn does_resume_order() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[]),
+                ExecuteMsg::ResumeDcaOrder {
+                    id: 1,
+                    catch_up: true,
+                },
+            )
+            .unwrap_err(),
+            ContractError::OrderNotPaused { id: 1 }
+        );
+
+        let start_time = mock_env().block.time.seconds();
+
+        // catching up keeps the schedule of the order, making up for the 7 purchases that
+        // became due in the 400 seconds since it was created
+        let order = pause_and_resume(deps.as_mut(), 300, true);
+        assert_eq!(order.paused_at, None);
+        assert_eq!(order.last_purchase, 0);
+        assert_eq!(order.start_time, start_time);
+        assert_eq!(order.catch_up_purchases, 7);
+
+        // otherwise the schedule is moved back by the time the order was paused for
+        let order = pause_and_resume(deps.as_mut(), 300, false);
+        assert_eq!(order.paused_at, None);
+        assert_eq!(order.last_purchase, 300);
+        assert_eq!(order.start_time, start_time + 300);
+        assert_eq!(order.catch_up_purchases, 7);
+
+        // the purchases made up for, including those still to be made up for, are capped
+        let order = pause_and_resume(deps.as_mut(), 3_000, true);
+        assert_eq!(order.catch_up_purchases, 10);
+
+        // a resume before the order was paused does not underflow
+        execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info("alice", &[]),
+            ExecuteMsg::PauseDcaOrder { id: 1 },
+        )
+        .unwrap();
+        assert!(matches!(
+            execute(
+                deps.as_mut(),
+                env_at(100),
+                mock_info("alice", &[]),
+                ExecuteMsg::ResumeDcaOrder {
+                    id: 1,
+                    catch_up: false,
+                },
+            )
+            .unwrap_err(),
+            ContractError::OverflowError(_)
+        ));
+    }
+}
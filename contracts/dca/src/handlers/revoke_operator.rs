@@ -0,0 +1,33 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{error::ContractError, events::revoke_operator_event, state::OPERATORS};
+
+/// ## Description
+/// Revokes the approval of an operator to manage the DCA orders of the sender.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the user who wants to revoke the operator.
+///
+/// * `operator` - The address of the operator as a [`String`].
+pub fn revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = addr_validate_to_lower(deps.api, &operator)?;
+
+    OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_event(revoke_operator_event(&info.sender, &operator))
+        .add_attributes(vec![
+            attr("action", "revoke_operator"),
+            attr("owner", info.sender),
+            attr("operator", operator),
+        ]))
+}
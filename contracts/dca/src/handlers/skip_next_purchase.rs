@@ -0,0 +1,153 @@
+use astroport_dca::dca::OperatorPermission;
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::skip_purchase_event,
+    resolve_owner::resolve_owner,
+    state::{dca_orders, save_order},
+};
+
+/// ## Description
+/// Skips the next DCA purchase of an order without changing the schedule of the purchases after
+/// it.
+///
+/// The skipped purchase is the one due at the end of the current interval, or the purchase that
+/// is currently due if the order is due, in which case the next purchase is due an interval from
+/// now.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the owner of the order, or an operator approved to modify
+/// orders for the owner.
+///
+/// * `id` - The id of the order.
+pub fn skip_next_purchase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut order = dca_orders().load(deps.storage, U64Key::new(id))?;
+    resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        Some(order.owner.to_string()),
+        OperatorPermission::ModifyOrder,
+    )?;
+
+    if order.paused_at.is_some() {
+        return Err(ContractError::OrderPaused { id });
+    }
+
+    // a purchase still to be made up for is skipped first, otherwise the skipped purchase counts
+    // as the last purchase of the order
+    if order.catch_up_purchases > 0 {
+        order.catch_up_purchases -= 1;
+    } else {
+        order.last_purchase = (order.last_purchase + order.interval).max(env.block.time.seconds());
+    }
+    save_order(deps.storage, &order)?;
+
+    Ok(Response::new()
+        .add_event(skip_purchase_event(&order))
+        .add_attributes(vec![
+            attr("action", "skip_next_purchase"),
+            attr("order_id", id.to_string()),
+            attr("last_purchase", order.last_purchase.to_string()),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_env, mock_info},
+        Uint128,
+    };
+    use cw_storage_plus::U64Key;
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::dca_orders,
+    };
+
+    #[test]
+    fn does_skip_next_purchase() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        let skip = ExecuteMsg::SkipNextPurchase { id: 1 };
+        let now = mock_env().block.time.seconds();
+
+        // the due purchase is skipped, so the next one is due an interval from now
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            skip.clone(),
+        )
+        .unwrap();
+        let order = dca_orders().load(&deps.storage, U64Key::new(1)).unwrap();
+        assert_eq!(order.last_purchase, now);
+
+        // a purchase that is not due yet is skipped for the one after it
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            skip.clone(),
+        )
+        .unwrap();
+        let order = dca_orders().load(&deps.storage, U64Key::new(1)).unwrap();
+        assert_eq!(order.last_purchase, now + 60);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::PauseDcaOrder { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), skip).unwrap_err(),
+            ContractError::OrderPaused { id: 1 }
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use astroport::asset::{addr_validate_to_lower, UUSD_DENOM};
+use cosmwasm_std::{attr, coins, BankMsg, DepsMut, MessageInfo, Response, Uint128};
+
+use crate::{
+    assert_owner::assert_owner,
+    error::ContractError,
+    events::slash_bot_event,
+    state::{BOTS, CONFIG},
+};
+
+/// ## Description
+/// Slashes the bond of a registered bot for misbehaviour, such as performing DCA purchases through
+/// poor routes. The slashed amount is sent to the fee collector.
+///
+/// If the bond falls below the minimum bond, the bot stays registered but loses its priority until
+/// the bond is topped up.
+///
+/// Bots that are unregistering can be slashed until they claim their bond.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the factory contract owner.
+///
+/// * `bot` - The address of the bot to slash as a [`String`].
+///
+/// * `amount` - A [`Uint128`] representing the amount of uusd to slash, which is capped at the
+/// bots bond.
+pub fn slash_bot(
+    deps: DepsMut,
+    info: MessageInfo,
+    bot: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let bot_address = addr_validate_to_lower(deps.api, &bot)?;
+
+    let mut bot = BOTS
+        .may_load(deps.storage, &bot_address)?
+        .ok_or(ContractError::BotNotRegistered {})?;
+
+    let slashed = amount.min(bot.bond);
+    if slashed.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    bot.bond = bot.bond.checked_sub(slashed)?;
+    BOTS.save(deps.storage, &bot_address, &bot)?;
+
+    Ok(Response::new()
+        .add_event(slash_bot_event(&bot, slashed))
+        .add_attributes(vec![
+            attr("action", "slash_bot"),
+            attr("bot", bot_address),
+            attr("slashed", slashed),
+        ])
+        .add_message(BankMsg::Send {
+            to_address: config.fee_collector.to_string(),
+            amount: coins(slashed.u128(), UUSD_DENOM),
+        }))
+}
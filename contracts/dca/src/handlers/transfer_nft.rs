@@ -0,0 +1,303 @@
+use astroport::asset::{addr_validate_to_lower, AssetInfo};
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    events::transfer_order_event,
+    state::{load_order, save_order, update_user_stats, user_orders, CONFIG},
+};
+
+/// ## Description
+/// Transfers a DCA order to a new owner, as in the CW721 `transfer_nft` message. The new owner
+/// controls the order, receives its purchases and pays its bot tips.
+///
+/// Native token deposits move with the order. Orders spending a CW20 token can not be transferred,
+/// as their purchases are funded from the allowance of the owner, which the recipient never agreed
+/// to spend on the order. Transferring an order to its owner does nothing.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the owner of the order.
+///
+/// * `recipient` - The address of the new owner as a [`String`].
+///
+/// * `token_id` - The id of the order as a [`String`].
+pub fn transfer_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let mut order = load_order(deps.storage, &token_id)?;
+    if order.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = addr_validate_to_lower(deps.api, &recipient)?;
+
+    let attributes = vec![
+        attr("action", "transfer_nft"),
+        attr("sender", info.sender.as_str()),
+        attr("recipient", recipient.as_str()),
+        attr("token_id", token_id),
+    ];
+
+    // transferring an order to its owner leaves it unchanged
+    if recipient == info.sender {
+        return Ok(Response::new().add_attributes(attributes));
+    }
+
+    if let AssetInfo::Token { .. } = order.initial_asset.info {
+        return Err(ContractError::Cw20OrderNotTransferable {});
+    }
+
+    // check that the recipient can hold the order, as orders are found by owner and initial asset
+    let config = CONFIG.load(deps.storage)?;
+    let recipient_orders = user_orders(deps.storage, &recipient)?;
+
+    if recipient_orders
+        .iter()
+        .any(|existing| existing.initial_asset.info == order.initial_asset.info)
+    {
+        return Err(ContractError::AlreadyDeposited {});
+    }
+
+    if recipient_orders.len() >= config.max_orders_per_user as usize {
+        return Err(ContractError::MaxOrdersReached {
+            max: config.max_orders_per_user,
+        });
+    }
+
+    // saving the order moves it to the recipient in the owner index
+    order.owner = recipient.clone();
+    save_order(deps.storage, &order)?;
+
+    // the order is active for the recipient, the statistics of all users stay the same
+    update_user_stats(
+        deps.storage,
+        &info.sender,
+        &order.initial_asset.info,
+        &order.target_asset,
+        |stats| stats.remove_active_order(),
+    )?;
+    update_user_stats(
+        deps.storage,
+        &recipient,
+        &order.initial_asset.info,
+        &order.target_asset,
+        |stats| {
+            stats.active_orders += 1;
+            Ok(())
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer_order_event(&order, &info.sender))
+        .add_attributes(attributes))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{
+        ExecuteMsg, OwnerOfResponse, QueryMsg, StatsResponse, TokensResponse,
+    };
+    use cosmwasm_std::{
+        coin, from_binary,
+        testing::{mock_env, mock_info},
+        Addr, DepsMut, Response, Uint128,
+    };
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::{save_order, user_orders},
+    };
+
+    fn create_order(deps: DepsMut, user: &str) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(user, &[coin(10_000, "uusd")]),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(10_000u128),
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                interval: 60,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn transfer(deps: DepsMut, sender: &str, recipient: &str) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn does_transfer_order() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        create_order(deps.as_mut(), "alice");
+
+        assert_eq!(
+            transfer(deps.as_mut(), "bob", "bob").unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        transfer(deps.as_mut(), "alice", "bob").unwrap();
+
+        // does move the order in the owner index
+        assert!(user_orders(&deps.storage, &Addr::unchecked("alice"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            user_orders(&deps.storage, &Addr::unchecked("bob")).unwrap()[0].id,
+            1
+        );
+
+        let owner: OwnerOfResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, "bob");
+
+        let tokens: TokensResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Tokens {
+                    owner: "bob".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tokens.tokens, vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn does_move_active_order_in_user_stats() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        create_order(deps.as_mut(), "alice");
+
+        transfer(deps.as_mut(), "alice", "bob").unwrap();
+
+        let user_stats = |user: &str| -> StatsResponse {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::UserStats {
+                        user: user.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // the order stays counted as created by alice
+        let alice_stats = user_stats("alice");
+        assert_eq!(alice_stats.orders_created, 1);
+        assert_eq!(alice_stats.active_orders, 0);
+
+        let bob_stats = user_stats("bob");
+        assert_eq!(bob_stats.orders_created, 0);
+        assert_eq!(bob_stats.active_orders, 1);
+
+        let global_stats: StatsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GlobalStats {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(global_stats.orders_created, 1);
+        assert_eq!(global_stats.active_orders, 1);
+    }
+
+    #[test]
+    fn does_not_transfer_to_owner_of_same_initial_asset() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        create_order(deps.as_mut(), "alice");
+        create_order(deps.as_mut(), "bob");
+
+        assert_eq!(
+            transfer(deps.as_mut(), "alice", "bob").unwrap_err(),
+            ContractError::AlreadyDeposited {}
+        );
+    }
+
+    #[test]
+    fn does_ignore_transfer_to_owner() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        create_order(deps.as_mut(), "alice");
+
+        let res = transfer(deps.as_mut(), "alice", "alice").unwrap();
+        assert!(res.events.is_empty());
+
+        assert_eq!(
+            user_orders(&deps.storage, &Addr::unchecked("alice")).unwrap()[0].id,
+            1
+        );
+    }
+
+    #[test]
+    fn does_not_transfer_cw20_order() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+        create_order(deps.as_mut(), "alice");
+
+        let mut order = user_orders(&deps.storage, &Addr::unchecked("alice")).unwrap()[0].clone();
+        order.initial_asset.info = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+        save_order(&mut deps.storage, &order).unwrap();
+
+        assert_eq!(
+            transfer(deps.as_mut(), "alice", "bob").unwrap_err(),
+            ContractError::Cw20OrderNotTransferable {}
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    events::unregister_bot_event,
+    state::{BOTS, CONFIG},
+};
+
+/// ## Description
+/// Unregisters the sender as a bot, which immediately loses its priority and starts the unbonding
+/// period of its bond.
+///
+/// The bond stays slashable during the unbonding period, after which it can be claimed with
+/// [`crate::handlers::claim_bot_bond`].
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot who wants to unregister.
+pub fn unregister_bot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut bot = BOTS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::BotNotRegistered {})?;
+
+    if bot.unbonding_until.is_some() {
+        return Err(ContractError::BotUnbonding {});
+    }
+
+    let unbonding_until = env.block.time.seconds() + config.bot_unbonding_period;
+    bot.unbonding_until = Some(unbonding_until);
+    BOTS.save(deps.storage, &info.sender, &bot)?;
+
+    Ok(Response::new()
+        .add_event(unregister_bot_event(&bot))
+        .add_attributes(vec![
+            attr("action", "unregister_bot"),
+            attr("bot", info.sender),
+            attr("unbonding_until", unbonding_until.to_string()),
+        ]))
+}
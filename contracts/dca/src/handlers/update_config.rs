@@ -1,7 +1,79 @@
-use astroport::{asset::AssetInfo, querier::query_factory_config};
-use cosmwasm_std::{attr, Decimal, DepsMut, MessageInfo, Response, StdError, Uint128};
+use astroport::asset::{addr_validate_to_lower, AssetInfo};
+use astroport_dca::dca::{MinDcaAmount, ReferralSource, TargetAssetFee};
+use cosmwasm_std::{
+    attr, Addr, Decimal, DepsMut, MessageInfo, Order, Response, StdResult, Uint128,
+};
 
-use crate::{error::ContractError, state::CONFIG};
+use crate::{
+    assert_owner::assert_owner,
+    error::ContractError,
+    state::{CONFIG, WHITELISTED_TOKENS},
+    validation::{
+        validate_config, validate_min_dca_amounts, validate_target_asset_fees,
+        validate_whitelisted_tokens,
+    },
+};
+
+/// Stores the new contract configuration parameters
+#[derive(Default)]
+pub struct UpdateConfigParameters {
+    /// An optional value which represents the new maximum amount of hops per swap if the user does
+    /// not specify a value.
+    pub max_hops: Option<u32>,
+    /// An optional [`Uint128`] which represents the new uusd fee paid to bots per hop executed in a
+    /// DCA purchase.
+    pub per_hop_fee: Option<Uint128>,
+    /// An optional [`Uint128`] which represents the new uusd fee paid to bots for each DCA
+    /// purchase.
+    pub base_tip: Option<Uint128>,
+    /// An optional [`Decimal`] which represents the new share of the uusd value of the
+    /// `dca_amount` paid to bots for each DCA purchase.
+    pub tip_volume_rate: Option<Decimal>,
+    /// An optional [`Uint128`] which represents the new maximum uusd tip paid to bots for a DCA
+    /// purchase.
+    pub max_tip: Option<Uint128>,
+    /// An optional [`Vec<AssetInfo>`] which represents the new whitelisted tokens that can be used
+    /// in a hop route for DCA purchases, replacing the current whitelist.
+    pub whitelisted_tokens: Option<Vec<AssetInfo>>,
+    /// An optional [`Decimal`] which represents the new maximum spread for each DCA purchase if the
+    /// user does not specify a value.
+    pub max_spread: Option<Decimal>,
+    /// An optional address which represents the new receiver of the protocol fee.
+    pub fee_collector: Option<String>,
+    /// An optional value which represents the new protocol fee in basis points.
+    pub protocol_fee_bps: Option<u16>,
+    /// An optional [`Vec<TargetAssetFee>`] which represents the new protocol fee overrides for
+    /// specific target assets.
+    pub target_asset_fees: Option<Vec<TargetAssetFee>>,
+    /// An optional list of addresses which represents the new addresses that are exempt from the
+    /// protocol fee.
+    pub fee_exempt_addresses: Option<Vec<String>>,
+    /// An optional [`Decimal`] which represents the new share of the protocol fee or bot tip
+    /// credited to referrers.
+    pub referral_share: Option<Decimal>,
+    /// An optional [`ReferralSource`] which represents the new source referrers are paid out of.
+    pub referral_source: Option<ReferralSource>,
+    /// An optional value which represents the new time in seconds after an order becomes due
+    /// during which only registered bots can perform the DCA purchase.
+    pub bot_priority_window: Option<u64>,
+    /// An optional [`Uint128`] which represents the new minimum uusd bond for bots to receive
+    /// priority.
+    pub min_bot_bond: Option<Uint128>,
+    /// An optional value which represents the new time in seconds the bond of an unregistering
+    /// bot stays slashable before it can be claimed.
+    pub bot_unbonding_period: Option<u64>,
+    /// An optional value which represents whether only whitelisted bots receive priority.
+    pub bot_whitelist_enabled: Option<bool>,
+    /// An optional value which represents the new minimum interval in seconds between DCA
+    /// purchases of an order.
+    pub min_interval: Option<u64>,
+    /// An optional [`Vec<MinDcaAmount>`] which represents the new minimum `dca_amount` of an
+    /// order for specific initial assets.
+    pub min_dca_amounts: Option<Vec<MinDcaAmount>>,
+    /// An optional value which represents the new maximum amount of DCA orders a user can have
+    /// at once.
+    pub max_orders_per_user: Option<u32>,
+}
 
 /// ## Description
 /// Updates the contract configuration with the specified optional parameters.
@@ -17,52 +89,194 @@ use crate::{error::ContractError, state::CONFIG};
 /// * `info` - A [`MessageInfo`] from the factory contract owner who wants to modify the
 /// configuration of the contract.
 ///
-/// * `max_hops` - An optional value which represents the new maximum amount of hops per swap if the
-/// user does not specify a value.
-///
-/// * `per_hop_fee` - An optional [`Uint128`] which represents the new uusd fee paid to bots per hop
-/// executed in a DCA purchase.
-///
-/// * `whitelisted_tokens` - An optional [`Vec<AssetInfo>`] which represents the new whitelisted
-/// tokens that can be used in a hop route for DCA purchases.
-///
-/// * `max_spread` - An optional [`Decimal`] which represents the new maximum spread for each DCA
-/// purchase if the user does not specify a value.
+/// * `parameters` - The [`UpdateConfigParameters`] containing the new configuration values.
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    max_hops: Option<u32>,
-    per_hop_fee: Option<Uint128>,
-    whitelisted_tokens: Option<Vec<AssetInfo>>,
-    max_spread: Option<Decimal>,
+    parameters: UpdateConfigParameters,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+    let UpdateConfigParameters {
+        max_hops,
+        per_hop_fee,
+        base_tip,
+        tip_volume_rate,
+        max_tip,
+        whitelisted_tokens,
+        max_spread,
+        fee_collector,
+        protocol_fee_bps,
+        target_asset_fees,
+        fee_exempt_addresses,
+        referral_share,
+        referral_source,
+        bot_priority_window,
+        min_bot_bond,
+        bot_unbonding_period,
+        bot_whitelist_enabled,
+        min_interval,
+        min_dca_amounts,
+        max_orders_per_user,
+    } = parameters;
 
-    if info.sender != factory_config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_owner(deps.as_ref(), &info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
 
     // update config
-    CONFIG.update::<_, StdError>(deps.storage, |mut config| {
-        if let Some(new_max_hops) = max_hops {
-            config.max_hops = new_max_hops;
-        }
+    if let Some(new_max_hops) = max_hops {
+        config.max_hops = new_max_hops;
+    }
 
-        if let Some(new_per_hop_fee) = per_hop_fee {
-            config.per_hop_fee = new_per_hop_fee;
-        }
+    if let Some(new_per_hop_fee) = per_hop_fee {
+        config.per_hop_fee = new_per_hop_fee;
+    }
+
+    if let Some(new_base_tip) = base_tip {
+        config.base_tip = new_base_tip;
+    }
+
+    if let Some(new_tip_volume_rate) = tip_volume_rate {
+        config.tip_volume_rate = new_tip_volume_rate;
+    }
 
-        if let Some(new_whitelisted_tokens) = whitelisted_tokens {
-            config.whitelisted_tokens = new_whitelisted_tokens;
+    if let Some(new_max_tip) = max_tip {
+        config.max_tip = new_max_tip;
+    }
+
+    if let Some(new_whitelisted_tokens) = whitelisted_tokens {
+        let new_whitelisted_tokens = validate_whitelisted_tokens(deps.api, new_whitelisted_tokens)?;
+
+        let old_keys = WHITELISTED_TOKENS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<Vec<_>>();
+        for key in old_keys {
+            WHITELISTED_TOKENS.remove(deps.storage, &key);
         }
 
-        if let Some(new_max_spread) = max_spread {
-            config.max_spread = new_max_spread;
+        for token in new_whitelisted_tokens {
+            WHITELISTED_TOKENS.save(deps.storage, token.as_bytes(), &token)?;
         }
+    }
+
+    if let Some(new_max_spread) = max_spread {
+        config.max_spread = new_max_spread;
+    }
+
+    if let Some(new_fee_collector) = fee_collector {
+        config.fee_collector = addr_validate_to_lower(deps.api, &new_fee_collector)?;
+    }
+
+    if let Some(new_protocol_fee_bps) = protocol_fee_bps {
+        config.protocol_fee_bps = new_protocol_fee_bps;
+    }
 
-        Ok(config)
-    })?;
+    if let Some(new_target_asset_fees) = target_asset_fees {
+        config.target_asset_fees = validate_target_asset_fees(deps.api, new_target_asset_fees)?;
+    }
+
+    if let Some(new_fee_exempt_addresses) = fee_exempt_addresses {
+        config.fee_exempt_addresses = new_fee_exempt_addresses
+            .iter()
+            .map(|addr| addr_validate_to_lower(deps.api, addr))
+            .collect::<StdResult<Vec<Addr>>>()?;
+    }
+
+    if let Some(new_referral_share) = referral_share {
+        config.referral_share = new_referral_share;
+    }
+
+    if let Some(new_referral_source) = referral_source {
+        config.referral_source = new_referral_source;
+    }
+
+    if let Some(new_bot_priority_window) = bot_priority_window {
+        config.bot_priority_window = new_bot_priority_window;
+    }
+
+    if let Some(new_min_bot_bond) = min_bot_bond {
+        config.min_bot_bond = new_min_bot_bond;
+    }
+
+    if let Some(new_bot_unbonding_period) = bot_unbonding_period {
+        config.bot_unbonding_period = new_bot_unbonding_period;
+    }
+
+    if let Some(new_bot_whitelist_enabled) = bot_whitelist_enabled {
+        config.bot_whitelist_enabled = new_bot_whitelist_enabled;
+    }
+
+    if let Some(new_min_interval) = min_interval {
+        config.min_interval = new_min_interval;
+    }
+
+    if let Some(new_min_dca_amounts) = min_dca_amounts {
+        config.min_dca_amounts = validate_min_dca_amounts(deps.api, new_min_dca_amounts)?;
+    }
+
+    if let Some(new_max_orders_per_user) = max_orders_per_user {
+        config.max_orders_per_user = new_max_orders_per_user;
+    }
+
+    // validate that the updated config is within the protocol defined bounds
+    validate_config(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default().add_attributes(vec![attr("action", "update_config")]))
 }
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::AssetInfo;
+    use cosmwasm_std::{testing::mock_info, Addr};
+
+    use super::{update_config, UpdateConfigParameters};
+    use crate::{
+        error::ContractError,
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::is_whitelisted_asset,
+    };
+
+    #[test]
+    fn does_replace_whitelist() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+
+        update_config(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            UpdateConfigParameters {
+                whitelisted_tokens: Some(vec![token.clone()]),
+                ..UpdateConfigParameters::default()
+            },
+        )
+        .unwrap();
+
+        assert!(is_whitelisted_asset(&deps.storage, &token));
+        assert!(!is_whitelisted_asset(&deps.storage, &uusd));
+
+        // does validate the new whitelist as when instantiating
+        let err = update_config(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            UpdateConfigParameters {
+                whitelisted_tokens: Some(vec![uusd.clone(), uusd]),
+                ..UpdateConfigParameters::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicateWhitelistedToken {
+                token: "uusd".to_string()
+            }
+        );
+    }
+}
@@ -1,8 +1,12 @@
-use cosmwasm_std::{attr, Decimal, DepsMut, MessageInfo, Response};
+use astroport_dca::dca::{TipMode, UserConfig};
+use cosmwasm_std::{attr, Decimal, DepsMut, MessageInfo, Response, Uint128};
 
 use crate::{
     error::ContractError,
-    state::{UserConfig, USER_CONFIG},
+    events::update_user_config_event,
+    state::{CONFIG, USER_CONFIG},
+    tip::validate_tip_mode,
+    validation::{validate_max_hops, validate_max_spread},
 };
 
 fn serde_option<T>(option: Option<T>) -> String
@@ -30,12 +34,45 @@ where
 ///
 /// * `max_spread` - A [`Decimal`] value wrapped in an [`Option`] which represents the new maximum
 /// spread for each DCA purchase. If `None`, the user will use the config set by the contract.
+///
+/// * `max_tip_per_purchase` - A [`Uint128`] value wrapped in an [`Option`] which represents the
+/// maximum tip the user is willing to pay for each DCA purchase. If `None`, the user will pay any
+/// tip up to the maximum set by the contract.
+///
+/// * `tip_mode` - A [`TipMode`] value wrapped in an [`Option`] which represents how the tip for
+/// each DCA purchase is priced. If `None`, the tip is priced by the contract configuration.
 pub fn update_user_config(
     deps: DepsMut,
     info: MessageInfo,
     max_hops: Option<u32>,
     max_spread: Option<Decimal>,
+    max_tip_per_purchase: Option<Uint128>,
+    tip_mode: Option<TipMode>,
 ) -> Result<Response, ContractError> {
+    let tip_mode = tip_mode.unwrap_or_default();
+
+    // validate that the overrides are within the protocol defined bounds
+    if let Some(max_hops) = max_hops {
+        validate_max_hops(max_hops)?;
+    }
+
+    if let Some(max_spread) = max_spread {
+        validate_max_spread(max_spread)?;
+    }
+
+    // validate that the tip cap still allows purchases to be performed
+    if let Some(max_tip) = max_tip_per_purchase {
+        let min_tip = CONFIG.load(deps.storage)?.min_tip()?;
+        if max_tip < min_tip {
+            return Err(ContractError::MaxTipTooLow { min: min_tip });
+        }
+    }
+
+    // validate that a dutch auction tip is able to pay for purchases
+    if tip_mode != TipMode::Fixed {
+        validate_tip_mode(&CONFIG.load(deps.storage)?, &tip_mode)?;
+    }
+
     let config = USER_CONFIG
         .may_load(deps.storage, &info.sender)?
         .unwrap_or_default();
@@ -43,32 +80,46 @@ pub fn update_user_config(
     let new_config = UserConfig {
         max_hops,
         max_spread,
+        max_tip_per_purchase,
+        tip_mode: tip_mode.clone(),
         ..config
     };
 
     USER_CONFIG.save(deps.storage, &info.sender, &new_config)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "update_user_config"),
-        attr("max_hops", serde_option(max_hops)),
-        attr("max_spread", serde_option(max_spread)),
-    ]))
+    Ok(Response::new()
+        .add_event(update_user_config_event(&info.sender, &new_config)?)
+        .add_attributes(vec![
+            attr("action", "update_user_config"),
+            attr("max_hops", serde_option(max_hops)),
+            attr("max_spread", serde_option(max_spread)),
+            attr("max_tip_per_purchase", serde_option(max_tip_per_purchase)),
+            attr(
+                "tip_mode",
+                match tip_mode {
+                    TipMode::Fixed => "fixed",
+                    TipMode::DutchAuction { .. } => "dutch_auction",
+                },
+            ),
+        ]))
 }
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use astroport_dca::dca::ExecuteMsg;
+    use astroport_dca::dca::{ExecuteMsg, InstantiateMsg, TipMode, UserConfig};
     use cosmwasm_std::{
         attr, coin,
         testing::{mock_dependencies, mock_env, mock_info},
-        Decimal, Response, Uint128,
+        Decimal, Event, Response, Uint128,
     };
 
     use crate::{
         contract::execute,
-        state::{UserConfig, USER_CONFIG},
+        error::ContractError,
+        mock_querier::{instantiate_msg, setup_contract_with},
+        state::{MAX_SPREAD_LIMIT_PERCENT, USER_CONFIG},
     };
 
     #[test]
@@ -79,17 +130,31 @@ mod tests {
         let msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_tip_per_purchase: None,
+            tip_mode: None,
         };
 
         // does send the write response
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         assert_eq!(
             res,
-            Response::new().add_attributes(vec![
-                attr("action", "update_user_config"),
-                attr("max_hops", "6"),
-                attr("max_spread", "0.025")
-            ])
+            Response::new()
+                .add_event(
+                    Event::new("dca_update_user_config")
+                        .add_attribute("owner", "creator")
+                        .add_attribute("max_hops", "6")
+                        .add_attribute("max_spread", "0.025")
+                        .add_attribute("max_tip_per_purchase", "none")
+                        .add_attribute("tip_mode", "\"fixed\"")
+                        .add_attribute("tip_balance", "0")
+                )
+                .add_attributes(vec![
+                    attr("action", "update_user_config"),
+                    attr("max_hops", "6"),
+                    attr("max_spread", "0.025"),
+                    attr("max_tip_per_purchase", "none"),
+                    attr("tip_mode", "fixed"),
+                ])
         );
 
         // does update config
@@ -99,7 +164,9 @@ mod tests {
             UserConfig {
                 max_hops: Some(6),
                 max_spread: Some(Decimal::from_str("0.025").unwrap()),
-                tip_balance: Uint128::zero()
+                tip_balance: Uint128::zero(),
+                max_tip_per_purchase: None,
+                tip_mode: TipMode::Fixed,
             }
         )
     }
@@ -112,6 +179,8 @@ mod tests {
         let msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_tip_per_purchase: None,
+            tip_mode: None,
         };
 
         // add tip
@@ -134,10 +203,14 @@ mod tests {
         let update_msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(4),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_tip_per_purchase: None,
+            tip_mode: None,
         };
         let reset_msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: None,
+            max_tip_per_purchase: None,
+            tip_mode: None,
         };
 
         // does reset the config
@@ -151,8 +224,82 @@ mod tests {
             UserConfig {
                 max_hops: Some(6),
                 max_spread: None,
-                tip_balance: Uint128::zero()
+                tip_balance: Uint128::zero(),
+                max_tip_per_purchase: None,
+                tip_mode: TipMode::Fixed,
             }
         )
     }
+
+    #[test]
+    fn does_require_tip_cap_above_min_tip() {
+        let mut deps = mock_dependencies(&[]);
+
+        setup_contract_with(
+            deps.as_mut(),
+            InstantiateMsg {
+                base_tip: Uint128::from(50u128),
+                bot_priority_window: 0,
+                min_bot_bond: Uint128::zero(),
+                ..instantiate_msg()
+            },
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: None,
+                max_spread: None,
+                max_tip_per_purchase: Some(Uint128::from(100u128)),
+                tip_mode: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MaxTipTooLow {
+                min: Uint128::from(150u128)
+            }
+        );
+    }
+
+    #[test]
+    fn does_bound_overrides() {
+        let mut deps = mock_dependencies(&[]);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: None,
+                max_spread: Some(Decimal::percent(150)),
+                max_tip_per_purchase: None,
+                tip_mode: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidMaxSpread {
+                max: Decimal::percent(MAX_SPREAD_LIMIT_PERCENT)
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateUserConfig {
+                max_hops: Some(0),
+                max_spread: None,
+                max_tip_per_purchase: None,
+                tip_mode: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMaxHops { .. }));
+    }
 }
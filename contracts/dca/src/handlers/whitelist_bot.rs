@@ -0,0 +1,45 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    assert_owner::assert_owner, error::ContractError, events::whitelist_bot_event, state::BOTS,
+};
+
+/// ## Description
+/// Adds a registered bot to the bot whitelist, or removes it. Once the bot whitelist is enabled
+/// in the configuration, only whitelisted bots receive priority.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the factory contract owner.
+///
+/// * `bot` - The address of the bot as a [`String`].
+///
+/// * `whitelisted` - Whether the bot is whitelisted.
+pub fn whitelist_bot(
+    deps: DepsMut,
+    info: MessageInfo,
+    bot: String,
+    whitelisted: bool,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let bot_address = addr_validate_to_lower(deps.api, &bot)?;
+
+    let mut bot = BOTS
+        .may_load(deps.storage, &bot_address)?
+        .ok_or(ContractError::BotNotRegistered {})?;
+    bot.whitelisted = whitelisted;
+    BOTS.save(deps.storage, &bot_address, &bot)?;
+
+    Ok(Response::new()
+        .add_event(whitelist_bot_event(&bot))
+        .add_attributes(vec![
+            attr("action", "whitelist_bot"),
+            attr("bot", bot_address),
+            attr("whitelisted", whitelisted.to_string()),
+        ]))
+}
@@ -1,66 +1,78 @@
 use astroport::asset::UUSD_DENOM;
-use cosmwasm_std::{attr, coins, BankMsg, DepsMut, MessageInfo, Response, Uint128};
+use astroport_dca::dca::OperatorPermission;
+use cosmwasm_std::{attr, coins, BankMsg, DepsMut, Env, MessageInfo, Response, Uint128};
 
-use crate::{error::ContractError, state::USER_CONFIG};
+use crate::{
+    error::ContractError, events::withdraw_tip_event, resolve_owner::resolve_owner,
+    state::USER_CONFIG,
+};
 
 /// ## Description
 /// Withdraws a users bot tip from the contract.
 ///
+/// The tip is always sent to the user, even if it is withdrawn by an operator.
+///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
 /// ## Arguments
 /// * `deps` - A [`DepsMut`] that contains the dependencies.
 ///
-/// * `info` - A [`MessageInfo`] from the sender who wants to withdraw their bot tip.
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the sender who wants to withdraw their bot tip, or an
+/// operator approved to withdraw tips for the owner.
 ///
 /// * `amount`` - A [`Uint128`] representing the amount of uusd to send back to the user.
+///
+/// * `owner` - The address of the user who owns the tip as a [`String`], if the sender is an
+/// approved operator.
 pub fn withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
+    let owner = resolve_owner(
+        deps.as_ref(),
+        &env,
+        &info,
+        owner,
+        OperatorPermission::WithdrawTip,
+    )?;
+
     let mut config = USER_CONFIG
-        .may_load(deps.storage, &info.sender)?
+        .may_load(deps.storage, &owner)?
         .unwrap_or_default();
 
     config.tip_balance = config.tip_balance.checked_sub(amount)?;
 
-    // optimization: if the user is withdrawing all their tip, they are probably never going to
-    // interact with the contract again. In this case, we can delete their config to save space
-    // otherwise, we save their new configuration
-    match config.tip_balance.is_zero() {
-        true => {
-            USER_CONFIG.remove(deps.storage, &info.sender);
-            Ok(())
-        }
-        false => USER_CONFIG.save(deps.storage, &info.sender, &config),
-    }?;
+    // the config is kept even once the tip balance is empty, as it also holds the user settings
+    USER_CONFIG.save(deps.storage, &owner, &config)?;
 
     Ok(Response::new()
+        .add_event(withdraw_tip_event(&owner, amount, config.tip_balance))
         .add_attributes(vec![
             attr("action", "withdraw"),
             attr("tip_removed", amount),
         ])
         .add_message(BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: owner.to_string(),
             amount: coins(amount.u128(), UUSD_DENOM),
         }))
 }
 
 #[cfg(test)]
 mod tests {
-    use astroport_dca::dca::ExecuteMsg;
+    use astroport_dca::dca::{ExecuteMsg, UserConfig};
     use cosmwasm_std::{
         attr, coin,
         testing::{mock_dependencies, mock_env, mock_info},
-        Addr, BankMsg, DepsMut, MessageInfo, OverflowError, OverflowOperation, Response, Uint128,
+        Addr, BankMsg, DepsMut, Event, MessageInfo, OverflowError, OverflowOperation, Response,
+        StdError, Uint128,
     };
 
-    use crate::{
-        contract::execute,
-        error::ContractError,
-        state::{UserConfig, USER_CONFIG},
-    };
+    use crate::{contract::execute, error::ContractError, state::USER_CONFIG};
 
     fn add_tip(deps: DepsMut, info: MessageInfo) {
         execute(deps, mock_env(), info, ExecuteMsg::AddBotTip {}).unwrap();
@@ -75,6 +87,7 @@ mod tests {
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::Withdraw {
             tip: tip_sent.amount,
+            owner: None,
         };
 
         // add bot tip
@@ -85,6 +98,12 @@ mod tests {
         assert_eq!(
             res,
             Response::new()
+                .add_event(
+                    Event::new("dca_withdraw_tip")
+                        .add_attribute("owner", "creator")
+                        .add_attribute("amount", "10000")
+                        .add_attribute("tip_balance", "0")
+                )
                 .add_attributes(vec![
                     attr("action", "withdraw"),
                     attr("tip_removed", tip_sent.amount)
@@ -106,6 +125,7 @@ mod tests {
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::Withdraw {
             tip: tip_withdraw.amount,
+            owner: None,
         };
 
         // add bot tip
@@ -125,6 +145,47 @@ mod tests {
         )
     }
 
+    #[test]
+    fn does_keep_config_when_withdrawing_all() {
+        let mut deps = mock_dependencies(&[]);
+
+        add_tip(deps.as_mut(), mock_info("creator", &[coin(10_000, "uusd")]));
+        USER_CONFIG
+            .update::<_, StdError>(
+                deps.as_mut().storage,
+                &Addr::unchecked("creator"),
+                |config| {
+                    Ok(UserConfig {
+                        max_tip_per_purchase: Some(Uint128::from(500u128)),
+                        ..config.unwrap()
+                    })
+                },
+            )
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {
+                tip: Uint128::from(10_000u128),
+                owner: None,
+            },
+        )
+        .unwrap();
+
+        let config = USER_CONFIG
+            .load(&deps.storage, &Addr::unchecked("creator"))
+            .unwrap();
+        assert_eq!(
+            config,
+            UserConfig {
+                max_tip_per_purchase: Some(Uint128::from(500u128)),
+                ..UserConfig::default()
+            }
+        )
+    }
+
     #[test]
     fn wont_excess_withdraw() {
         let mut deps = mock_dependencies(&[]);
@@ -135,6 +196,7 @@ mod tests {
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::Withdraw {
             tip: tip_withdraw.amount,
+            owner: None,
         };
 
         // add bot tip
@@ -0,0 +1,88 @@
+use astroport::asset::{Asset, AssetInfo, UUSD_DENOM};
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+use cw_storage_plus::U64Key;
+
+use crate::{
+    error::ContractError,
+    events::withdraw_vault_event,
+    send_asset::send_asset,
+    state::{claimable_vault_proceeds, VAULTS, VAULT_EXPIRING_SHARES, VAULT_POSITIONS},
+};
+
+/// ## Description
+/// Withdraws the position of the sender in a vault, returning the `initial_asset` that has not
+/// been spent yet along with the unclaimed `target_asset` and the unused uusd tip deposit.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the participant who wants to withdraw their position.
+///
+/// * `vault_id` - The id of the vault to withdraw from.
+pub fn withdraw_vault(
+    deps: DepsMut,
+    info: MessageInfo,
+    vault_id: u64,
+) -> Result<Response, ContractError> {
+    let mut vault = VAULTS.load(deps.storage, U64Key::new(vault_id))?;
+    let position = VAULT_POSITIONS
+        .may_load(deps.storage, (U64Key::new(vault_id), &info.sender))?
+        .ok_or(ContractError::NonexistentVaultPosition {})?;
+
+    let tip_refund = Asset {
+        info: AssetInfo::NativeToken {
+            denom: UUSD_DENOM.to_string(),
+        },
+        amount: position.tip_deposit,
+    };
+    vault.tip_balance = vault.tip_balance.checked_sub(tip_refund.amount)?;
+
+    let remaining = Asset {
+        info: vault.initial_asset.clone(),
+        amount: position.remaining(&vault),
+    };
+    let proceeds = Asset {
+        info: vault.target_asset.clone(),
+        amount: claimable_vault_proceeds(deps.storage, &vault, &position)?,
+    };
+
+    // remove the shares of the position from the upcoming purchases of the vault
+    if position.is_active(&vault) {
+        vault.total_shares = vault.total_shares.checked_sub(position.shares)?;
+        VAULT_EXPIRING_SHARES.update(
+            deps.storage,
+            (U64Key::new(vault_id), U64Key::new(position.end_epoch + 1)),
+            |shares| -> Result<_, ContractError> {
+                Ok(shares.unwrap_or_default().checked_sub(position.shares)?)
+            },
+        )?;
+    }
+    VAULTS.save(deps.storage, U64Key::new(vault_id), &vault)?;
+
+    VAULT_POSITIONS.remove(deps.storage, (U64Key::new(vault_id), &info.sender));
+
+    let messages = [&remaining, &proceeds, &tip_refund]
+        .iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .map(|asset| send_asset(asset, &info.sender))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(withdraw_vault_event(
+            &vault,
+            &info.sender,
+            &remaining,
+            &proceeds,
+            tip_refund.amount,
+        ))
+        .add_attributes(vec![
+            attr("action", "withdraw_vault"),
+            attr("vault_id", vault_id.to_string()),
+            attr("remaining", remaining.to_string()),
+            attr("proceeds", proceeds.to_string()),
+            attr("tip_refund", tip_refund.amount),
+        ]))
+}
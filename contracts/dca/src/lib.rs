@@ -3,6 +3,17 @@ pub mod error;
 pub mod state;
 
 mod handlers;
+mod migrations;
 mod queries;
 
+mod assert_owner;
+mod events;
 mod get_token_allowance;
+mod resolve_owner;
+mod router_swap;
+mod send_asset;
+mod tip;
+mod validation;
+
+#[cfg(test)]
+mod mock_querier;
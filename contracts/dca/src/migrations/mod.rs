@@ -0,0 +1,8 @@
+//! Storage migrations between released versions of the contract.
+//!
+//! Each module contains the migration to its version, if any, along with the storage layout of
+//! that version where a later version has changed it.
+
+pub mod v1_0_0;
+pub mod v1_1_0;
+pub mod v1_2_0;
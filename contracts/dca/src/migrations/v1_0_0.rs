@@ -0,0 +1,41 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The contract configuration as stored by v1.0.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV100 {
+    pub max_hops: u32,
+    pub max_spread: Decimal,
+    pub per_hop_fee: Uint128,
+    pub whitelisted_tokens: Vec<AssetInfo>,
+    pub factory_addr: Addr,
+    pub router_addr: Addr,
+}
+
+/// The users custom configuration as stored by v1.0.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserConfigV100 {
+    pub max_hops: Option<u32>,
+    pub max_spread: Option<Decimal>,
+    pub tip_balance: Uint128,
+}
+
+/// A DCA order as stored by v1.0.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DcaInfoV100 {
+    pub initial_asset: Asset,
+    pub target_asset: AssetInfo,
+    pub interval: u64,
+    pub last_purchase: u64,
+    pub dca_amount: Uint128,
+}
+
+/// The contract configuration in v1.0.0
+pub const CONFIG_V100: Item<ConfigV100> = Item::new("config");
+/// The configuration set by each user in v1.0.0
+pub const USER_CONFIG_V100: Map<&Addr, UserConfigV100> = Map::new("user_config");
+/// The DCA orders for a user in v1.0.0
+pub const USER_DCA_V100: Map<&Addr, Vec<DcaInfoV100>> = Map::new("user_dca");
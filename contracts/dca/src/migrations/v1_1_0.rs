@@ -0,0 +1,410 @@
+use astroport::asset::{addr_validate_to_lower, AssetInfo};
+use astroport_dca::dca::{
+    DcaInfo, MigrateMsg, ReferralSource, TargetAssetFee, TipMode, UserConfig,
+};
+use cosmwasm_std::{Addr, Decimal, DepsMut, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ContractError,
+    migrations::v1_0_0::{CONFIG_V100, USER_CONFIG_V100, USER_DCA_V100},
+    state::{next_order_id, save_order, MAX_PROTOCOL_FEE_BPS, MAX_TIP_VOLUME_PERCENT, USER_CONFIG},
+    validation::validate_referral_share,
+};
+
+/// The contract configuration as stored by v1.1.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV110 {
+    pub max_hops: u32,
+    pub max_spread: Decimal,
+    pub per_hop_fee: Uint128,
+    pub base_tip: Uint128,
+    pub tip_volume_rate: Decimal,
+    pub max_tip: Uint128,
+    pub whitelisted_tokens: Vec<AssetInfo>,
+    pub factory_addr: Addr,
+    pub router_addr: Addr,
+    pub fee_collector: Addr,
+    pub protocol_fee_bps: u16,
+    pub target_asset_fees: Vec<TargetAssetFee>,
+    pub fee_exempt_addresses: Vec<Addr>,
+    pub referral_share: Decimal,
+    pub referral_source: ReferralSource,
+    pub bot_priority_window: u64,
+    pub min_bot_bond: Uint128,
+}
+
+/// The contract configuration in v1.1.0
+pub const CONFIG_V110: Item<ConfigV110> = Item::new("config");
+
+/// ## Description
+/// Migrates the contract storage from v1.0.0 to v1.1.0.
+///
+/// The configuration is extended with the fee, referral, bot and tip parameters from the
+/// [`MigrateMsg`], user configurations are converted into the new layout and the DCA orders of each
+/// user are moved into the indexed order map, with ids assigned in the order they are migrated.
+///
+/// Returns a [`ContractError`] if a required parameter is missing or a parameter is invalid.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `msg` - The [`MigrateMsg`] containing the new configuration values.
+pub fn migrate(deps: DepsMut, env: Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    // extend the config with the new parameters
+    let old_config = CONFIG_V100.load(deps.storage)?;
+
+    let fee_collector =
+        msg.fee_collector
+            .as_ref()
+            .ok_or_else(|| ContractError::MissingMigrationParameter {
+                name: "fee_collector".to_string(),
+            })?;
+
+    let config = ConfigV110 {
+        max_hops: old_config.max_hops,
+        max_spread: old_config.max_spread,
+        per_hop_fee: old_config.per_hop_fee,
+        base_tip: msg.base_tip.unwrap_or_default(),
+        tip_volume_rate: msg.tip_volume_rate.unwrap_or_else(Decimal::zero),
+        max_tip: match msg.max_tip {
+            Some(max_tip) => max_tip,
+            None => old_config
+                .per_hop_fee
+                .checked_mul(Uint128::from(old_config.max_hops))?,
+        },
+        whitelisted_tokens: old_config.whitelisted_tokens,
+        factory_addr: old_config.factory_addr,
+        router_addr: old_config.router_addr,
+        fee_collector: addr_validate_to_lower(deps.api, fee_collector)?,
+        protocol_fee_bps: msg.protocol_fee_bps.unwrap_or_default(),
+        target_asset_fees: vec![],
+        fee_exempt_addresses: vec![],
+        referral_share: msg.referral_share.unwrap_or_else(Decimal::zero),
+        referral_source: msg
+            .referral_source
+            .clone()
+            .unwrap_or(ReferralSource::ProtocolFee),
+        bot_priority_window: msg.bot_priority_window.unwrap_or_default(),
+        min_bot_bond: msg.min_bot_bond.unwrap_or_default(),
+    };
+
+    // validate the new parameters, the v1.0.0 parameters are kept as they were
+    if config.protocol_fee_bps > MAX_PROTOCOL_FEE_BPS {
+        return Err(ContractError::ProtocolFeeTooHigh {
+            max: MAX_PROTOCOL_FEE_BPS,
+        });
+    }
+
+    validate_referral_share(config.referral_share)?;
+
+    let max_tip_volume_rate = Decimal::percent(MAX_TIP_VOLUME_PERCENT);
+    if config.tip_volume_rate > max_tip_volume_rate {
+        return Err(ContractError::TipVolumeRateTooHigh {
+            max: max_tip_volume_rate,
+        });
+    }
+
+    let min_tip = config.base_tip.checked_add(config.per_hop_fee)?;
+    if config.max_tip < min_tip {
+        return Err(ContractError::MaxTipTooLow { min: min_tip });
+    }
+
+    CONFIG_V110.save(deps.storage, &config)?;
+
+    // convert the user configs into the new layout
+    let user_configs = USER_CONFIG_V100
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (user, old_user_config) in user_configs {
+        let user = Addr::unchecked(String::from_utf8(user).map_err(StdError::invalid_utf8)?);
+
+        USER_CONFIG.save(
+            deps.storage,
+            &user,
+            &UserConfig {
+                max_hops: old_user_config.max_hops,
+                max_spread: old_user_config.max_spread,
+                tip_balance: old_user_config.tip_balance,
+                max_tip_per_purchase: None,
+                tip_mode: TipMode::Fixed,
+            },
+        )?;
+    }
+
+    // move the orders of each user into the indexed order map
+    let user_orders = USER_DCA_V100
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (user, orders) in user_orders {
+        let user = Addr::unchecked(String::from_utf8(user).map_err(StdError::invalid_utf8)?);
+
+        for order in orders {
+            let id = next_order_id(deps.storage)?;
+
+            save_order(
+                deps.storage,
+                &DcaInfo {
+                    id,
+                    owner: user.clone(),
+                    initial_asset: order.initial_asset,
+                    target_asset: order.target_asset,
+                    interval: order.interval,
+                    last_purchase: order.last_purchase,
+                    start_time: env.block.time.seconds(),
+                    dca_amount: order.dca_amount,
+                    referrer: None,
+                    max_hops: None,
+                    max_spread: None,
+                    route_preference: None,
+                    paused_at: None,
+                    catch_up_purchases: 0,
+                },
+            )?;
+        }
+
+        USER_DCA_V100.remove(deps.storage, &user);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{MigrateMsg, ReferralSource, TipMode, UserConfig};
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        Addr, Decimal, DepsMut, Uint128,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+
+    use crate::{
+        contract::migrate,
+        error::ContractError,
+        migrations::v1_0_0::{
+            ConfigV100, DcaInfoV100, UserConfigV100, CONFIG_V100, USER_CONFIG_V100, USER_DCA_V100,
+        },
+        mock_querier::uusd,
+        state::{
+            is_whitelisted_asset, user_orders, CONFIG, DEFAULT_MAX_ORDERS_PER_USER, GLOBAL_STATS,
+            ORDER_COUNT, USER_CONFIG,
+        },
+    };
+
+    fn legacy_order(initial_asset: AssetInfo, last_purchase: u64) -> DcaInfoV100 {
+        DcaInfoV100 {
+            initial_asset: Asset {
+                info: initial_asset,
+                amount: Uint128::from(10_000u128),
+            },
+            target_asset: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            interval: 60,
+            last_purchase,
+            dca_amount: Uint128::from(1_000u128),
+        }
+    }
+
+    fn setup_v100(deps: DepsMut) {
+        set_contract_version(deps.storage, "astroport-dca", "1.0.0").unwrap();
+
+        CONFIG_V100
+            .save(
+                deps.storage,
+                &ConfigV100 {
+                    max_hops: 3,
+                    max_spread: Decimal::percent(5),
+                    per_hop_fee: Uint128::from(100u128),
+                    whitelisted_tokens: vec![uusd()],
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                },
+            )
+            .unwrap();
+
+        USER_CONFIG_V100
+            .save(
+                deps.storage,
+                &Addr::unchecked("alice"),
+                &UserConfigV100 {
+                    max_hops: Some(2),
+                    max_spread: None,
+                    tip_balance: Uint128::from(500u128),
+                },
+            )
+            .unwrap();
+
+        USER_DCA_V100
+            .save(
+                deps.storage,
+                &Addr::unchecked("alice"),
+                &vec![
+                    legacy_order(uusd(), 100),
+                    legacy_order(
+                        AssetInfo::Token {
+                            contract_addr: Addr::unchecked("token"),
+                        },
+                        0,
+                    ),
+                ],
+            )
+            .unwrap();
+        USER_DCA_V100
+            .save(
+                deps.storage,
+                &Addr::unchecked("bob"),
+                &vec![legacy_order(uusd(), 0)],
+            )
+            .unwrap();
+    }
+
+    fn migrate_msg(fee_collector: Option<&str>) -> MigrateMsg {
+        MigrateMsg {
+            fee_collector: fee_collector.map(|addr| addr.to_string()),
+            protocol_fee_bps: None,
+            referral_share: None,
+            referral_source: None,
+            bot_priority_window: None,
+            min_bot_bond: None,
+            bot_unbonding_period: None,
+            base_tip: None,
+            tip_volume_rate: None,
+            max_tip: None,
+            min_interval: None,
+            max_orders_per_user: None,
+        }
+    }
+
+    #[test]
+    fn does_migrate_from_v1_0_0() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v100(deps.as_mut());
+
+        migrate(deps.as_mut(), mock_env(), migrate_msg(Some("treasury"))).unwrap();
+
+        // does keep the old config and default the new parameters to the v1.0.0 behaviour
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.max_hops, 3);
+        assert!(is_whitelisted_asset(&deps.storage, &uusd()));
+        assert_eq!(config.fee_collector, Addr::unchecked("treasury"));
+        assert_eq!(config.protocol_fee_bps, 0);
+        assert_eq!(config.referral_source, ReferralSource::ProtocolFee);
+        assert_eq!(config.base_tip, Uint128::zero());
+        assert_eq!(config.max_tip, Uint128::from(300u128));
+        assert_eq!(config.min_interval, 0);
+        assert_eq!(config.max_orders_per_user, DEFAULT_MAX_ORDERS_PER_USER);
+
+        // does convert the user config
+        let user_config = USER_CONFIG
+            .load(&deps.storage, &Addr::unchecked("alice"))
+            .unwrap();
+        assert_eq!(
+            user_config,
+            UserConfig {
+                max_hops: Some(2),
+                max_spread: None,
+                tip_balance: Uint128::from(500u128),
+                max_tip_per_purchase: None,
+                tip_mode: TipMode::Fixed,
+            }
+        );
+
+        // does move the orders into the indexed map with ids
+        let alice_orders = user_orders(&deps.storage, &Addr::unchecked("alice")).unwrap();
+        assert_eq!(
+            alice_orders
+                .iter()
+                .map(|order| order.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(alice_orders
+            .iter()
+            .all(|order| order.owner == Addr::unchecked("alice")));
+        assert_eq!(alice_orders[0].last_purchase, 100);
+        assert_eq!(alice_orders[0].initial_asset.info, uusd());
+
+        let bob_orders = user_orders(&deps.storage, &Addr::unchecked("bob")).unwrap();
+        assert_eq!(bob_orders.len(), 1);
+        assert_eq!(bob_orders[0].id, 3);
+        assert_eq!(ORDER_COUNT.load(&deps.storage).unwrap(), 3);
+
+        // does count the migrated orders as active
+        let uusd_stats = GLOBAL_STATS
+            .load(
+                &deps.storage,
+                (
+                    uusd().as_bytes(),
+                    AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    }
+                    .as_bytes(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(uusd_stats.orders_created, 2);
+        assert_eq!(uusd_stats.active_orders, 2);
+
+        // does remove the old orders
+        assert!(USER_DCA_V100
+            .may_load(&deps.storage, &Addr::unchecked("alice"))
+            .unwrap()
+            .is_none());
+
+        // does store the new version
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn does_require_fee_collector() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v100(deps.as_mut());
+
+        let err = migrate(deps.as_mut(), mock_env(), migrate_msg(None)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MissingMigrationParameter {
+                name: "fee_collector".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_refuse_downgrade() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v100(deps.as_mut());
+        set_contract_version(deps.as_mut().storage, "astroport-dca", "9.0.0").unwrap();
+
+        let err = migrate(deps.as_mut(), mock_env(), migrate_msg(Some("treasury"))).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MigrationDowngrade {
+                current: "9.0.0".to_string(),
+                new: env!("CARGO_PKG_VERSION").to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_refuse_other_contract() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v100(deps.as_mut());
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "1.0.0").unwrap();
+
+        let err = migrate(deps.as_mut(), mock_env(), migrate_msg(Some("treasury"))).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidMigrationContract {
+                contract: "crates.io:cw20-base".to_string()
+            }
+        );
+    }
+}
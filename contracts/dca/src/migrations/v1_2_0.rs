@@ -0,0 +1,245 @@
+use astroport_dca::dca::{Config, MigrateMsg};
+use cosmwasm_std::{DepsMut, Order, StdResult};
+
+use crate::{
+    error::ContractError,
+    migrations::v1_1_0::CONFIG_V110,
+    state::{dca_orders, update_stats, CONFIG, DEFAULT_MAX_ORDERS_PER_USER, WHITELISTED_TOKENS},
+    tip::validate_tip_config,
+    validation::{validate_max_orders_per_user, validate_protocol_fees, validate_referral_share},
+};
+
+/// ## Description
+/// Migrates the contract storage from v1.1.0 to v1.2.0.
+///
+/// The configuration is extended with the bot unbonding, bot whitelist and order limit parameters
+/// from the [`MigrateMsg`], the whitelisted tokens are moved into their own map and the existing
+/// DCA orders are counted in the order statistics.
+///
+/// Returns a [`ContractError`] if a parameter is invalid.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `msg` - The [`MigrateMsg`] containing the new configuration values.
+pub fn migrate(deps: DepsMut, msg: &MigrateMsg) -> Result<(), ContractError> {
+    // extend the config with the new parameters
+    let old_config = CONFIG_V110.load(deps.storage)?;
+
+    let config = Config {
+        max_hops: old_config.max_hops,
+        max_spread: old_config.max_spread,
+        per_hop_fee: old_config.per_hop_fee,
+        base_tip: old_config.base_tip,
+        tip_volume_rate: old_config.tip_volume_rate,
+        max_tip: old_config.max_tip,
+        factory_addr: old_config.factory_addr,
+        router_addr: old_config.router_addr,
+        fee_collector: old_config.fee_collector,
+        protocol_fee_bps: old_config.protocol_fee_bps,
+        target_asset_fees: old_config.target_asset_fees,
+        fee_exempt_addresses: old_config.fee_exempt_addresses,
+        referral_share: old_config.referral_share,
+        referral_source: old_config.referral_source,
+        bot_priority_window: old_config.bot_priority_window,
+        min_bot_bond: old_config.min_bot_bond,
+        bot_unbonding_period: msg.bot_unbonding_period.unwrap_or_default(),
+        bot_whitelist_enabled: false,
+        min_interval: msg.min_interval.unwrap_or_default(),
+        min_dca_amounts: vec![],
+        max_orders_per_user: msg
+            .max_orders_per_user
+            .unwrap_or(DEFAULT_MAX_ORDERS_PER_USER),
+    };
+
+    // validate the new parameters along with the fee, referral and tip parameters they are
+    // checked against, the v1.0.0 hop and spread parameters are kept as they were
+    validate_protocol_fees(&config)?;
+    validate_referral_share(config.referral_share)?;
+    validate_tip_config(&config)?;
+    validate_max_orders_per_user(config.max_orders_per_user)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    // move the whitelisted tokens into their own map
+    for token in old_config.whitelisted_tokens {
+        WHITELISTED_TOKENS.save(deps.storage, token.as_bytes(), &token)?;
+    }
+
+    // count the existing orders, which were all created and are still active
+    let orders = dca_orders()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|order| Ok(order?.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for order in orders {
+        update_stats(
+            deps.storage,
+            &order.owner,
+            &order.initial_asset.info,
+            &order.target_asset,
+            |stats| {
+                stats.orders_created += 1;
+                stats.active_orders += 1;
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::Asset;
+    use astroport_dca::dca::{DcaInfo, MigrateMsg, ReferralSource};
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        Addr, Decimal, DepsMut, Uint128,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+
+    use crate::{
+        contract::migrate,
+        error::ContractError,
+        migrations::v1_1_0::{ConfigV110, CONFIG_V110},
+        mock_querier::{uluna, uusd},
+        state::{
+            is_whitelisted_asset, save_order, CONFIG, DEFAULT_MAX_ORDERS_PER_USER, GLOBAL_STATS,
+            USER_STATS,
+        },
+    };
+
+    fn order(id: u64, owner: &str) -> DcaInfo {
+        DcaInfo {
+            id,
+            owner: Addr::unchecked(owner),
+            initial_asset: Asset {
+                info: uusd(),
+                amount: Uint128::from(10_000u128),
+            },
+            target_asset: uluna(),
+            interval: 60,
+            last_purchase: 0,
+            start_time: 0,
+            dca_amount: Uint128::from(1_000u128),
+            referrer: None,
+            max_hops: None,
+            max_spread: None,
+            route_preference: None,
+            paused_at: None,
+            catch_up_purchases: 0,
+        }
+    }
+
+    fn setup_v110(deps: DepsMut) {
+        set_contract_version(deps.storage, "astroport-dca", "1.1.0").unwrap();
+
+        CONFIG_V110
+            .save(
+                deps.storage,
+                &ConfigV110 {
+                    max_hops: 3,
+                    max_spread: Decimal::percent(5),
+                    per_hop_fee: Uint128::from(100u128),
+                    base_tip: Uint128::zero(),
+                    tip_volume_rate: Decimal::zero(),
+                    max_tip: Uint128::from(300u128),
+                    whitelisted_tokens: vec![uusd()],
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                    fee_collector: Addr::unchecked("treasury"),
+                    protocol_fee_bps: 10,
+                    target_asset_fees: vec![],
+                    fee_exempt_addresses: vec![],
+                    referral_share: Decimal::percent(50),
+                    referral_source: ReferralSource::BotTip,
+                    bot_priority_window: 30,
+                    min_bot_bond: Uint128::from(1_000u128),
+                },
+            )
+            .unwrap();
+
+        for order in [order(1, "alice"), order(2, "alice"), order(3, "bob")] {
+            save_order(deps.storage, &order).unwrap();
+        }
+    }
+
+    fn migrate_msg() -> MigrateMsg {
+        MigrateMsg {
+            fee_collector: None,
+            protocol_fee_bps: None,
+            referral_share: None,
+            referral_source: None,
+            bot_priority_window: None,
+            min_bot_bond: None,
+            bot_unbonding_period: Some(600),
+            base_tip: None,
+            tip_volume_rate: None,
+            max_tip: None,
+            min_interval: None,
+            max_orders_per_user: None,
+        }
+    }
+
+    #[test]
+    fn does_migrate_from_v1_1_0() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v110(deps.as_mut());
+
+        migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+
+        // does keep the v1.1.0 config and add the new parameters
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.fee_collector, Addr::unchecked("treasury"));
+        assert_eq!(config.protocol_fee_bps, 10);
+        assert_eq!(config.referral_source, ReferralSource::BotTip);
+        assert_eq!(config.bot_priority_window, 30);
+        assert_eq!(config.bot_unbonding_period, 600);
+        assert!(!config.bot_whitelist_enabled);
+        assert_eq!(config.min_interval, 0);
+        assert_eq!(config.max_orders_per_user, DEFAULT_MAX_ORDERS_PER_USER);
+
+        // does move the whitelist into its own map
+        assert!(is_whitelisted_asset(&deps.storage, &uusd()));
+        assert!(!is_whitelisted_asset(&deps.storage, &uluna()));
+
+        // does count the existing orders as active
+        let global_stats = GLOBAL_STATS
+            .load(&deps.storage, (uusd().as_bytes(), uluna().as_bytes()))
+            .unwrap();
+        assert_eq!(global_stats.orders_created, 3);
+        assert_eq!(global_stats.active_orders, 3);
+
+        let alice_stats = USER_STATS
+            .load(
+                &deps.storage,
+                (
+                    &Addr::unchecked("alice"),
+                    uusd().as_bytes(),
+                    uluna().as_bytes(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(alice_stats.active_orders, 2);
+
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn does_validate_new_parameters() {
+        let mut deps = mock_dependencies(&[]);
+        setup_v110(deps.as_mut());
+
+        let err = migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                max_orders_per_user: Some(0),
+                ..migrate_msg()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxOrdersPerUser {});
+    }
+}
@@ -0,0 +1,193 @@
+use astroport::{
+    asset::{AssetInfo, PairInfo},
+    factory::{PairType, QueryMsg as FactoryQueryMsg},
+    pair::{QueryMsg as PairQueryMsg, SimulationResponse},
+};
+use astroport_dca::dca::{InstantiateMsg, ReferralSource};
+use cosmwasm_std::{
+    from_binary, from_slice,
+    testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    to_binary, Addr, Binary, Coin, ContractResult, Decimal, DepsMut, Empty, Env, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+
+use crate::contract::instantiate;
+
+/// ## Description
+/// A mock querier which answers factory pair queries with the `pair` contract or the `pair_error`,
+/// pair simulations at
+/// the `simulation_rate` less the `simulation_spread`, and every other wasm query with the factory
+/// configuration. All other queries are forwarded to the [`MockQuerier`].
+pub struct WasmMockQuerier {
+    base: MockQuerier,
+    factory_owner: String,
+    simulation_rate: Decimal,
+    simulation_spread: Decimal,
+    pair_error: Option<String>,
+}
+
+impl WasmMockQuerier {
+    /// Sets the native token balance of `addr`, as returned by bank queries.
+    pub fn update_balance(&mut self, addr: &str, balance: Vec<Coin>) {
+        self.base.update_balance(addr, balance);
+    }
+
+    /// Sets the amount of the ask asset returned by pair simulations per unit of offer asset.
+    pub fn update_simulation_rate(&mut self, simulation_rate: Decimal) {
+        self.simulation_rate = simulation_rate;
+    }
+
+    /// Sets the share of the ask asset lost to the spread in pair simulations.
+    pub fn update_simulation_spread(&mut self, simulation_spread: Decimal) {
+        self.simulation_spread = simulation_spread;
+    }
+
+    /// Sets the error returned by factory pair queries, or `None` to answer them with the `pair`
+    /// contract.
+    pub fn update_pair_error(&mut self, pair_error: Option<&str>) {
+        self.pair_error = pair_error.map(|error| error.to_string());
+    }
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) => {
+                if let Ok(FactoryQueryMsg::Pair { asset_infos }) = from_binary(&msg) {
+                    if let Some(error) = &self.pair_error {
+                        return SystemResult::Ok(ContractResult::Err(error.clone()));
+                    }
+
+                    return SystemResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .into(),
+                    );
+                }
+
+                if let Ok(PairQueryMsg::Simulation { offer_asset }) = from_binary(&msg) {
+                    let amount = offer_asset.amount * self.simulation_rate;
+                    let spread_amount = amount * self.simulation_spread;
+                    return SystemResult::Ok(
+                        to_binary(&SimulationResponse {
+                            return_amount: amount - spread_amount,
+                            spread_amount,
+                            commission_amount: Uint128::zero(),
+                        })
+                        .into(),
+                    );
+                }
+
+                SystemResult::Ok(ContractResult::Ok(Binary::from(
+                    format!(
+                        r#"{{"owner":"{}","pair_configs":[],"token_code_id":1,"fee_address":null,"generator_address":null,"whitelist_code_id":1}}"#,
+                        self.factory_owner
+                    )
+                    .as_bytes(),
+                )))
+            }
+            _ => self.base.handle_query(&request),
+        }
+    }
+}
+
+/// ## Description
+/// Returns mock dependencies where the factory contract reports `owner` as its owner, so that
+/// owner-only messages can be tested.
+pub fn mock_dependencies_with_owner(
+    owner: &str,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: WasmMockQuerier {
+            base: MockQuerier::new(&[]),
+            factory_owner: owner.to_string(),
+            simulation_rate: Decimal::one(),
+            simulation_spread: Decimal::zero(),
+            pair_error: None,
+        },
+    }
+}
+
+/// ## Description
+/// Returns the [`InstantiateMsg`] used by [`setup_contract`], with `uusd` as the only whitelisted
+/// token. Tests override the fields they depend on with the struct update syntax.
+pub fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        max_hops: 3,
+        per_hop_fee: Uint128::from(100u128),
+        base_tip: Uint128::zero(),
+        tip_volume_rate: Decimal::zero(),
+        max_tip: Uint128::from(1_000u128),
+        whitelisted_tokens: vec![uusd()],
+        max_spread: "0.05".to_string(),
+        factory_addr: "factory".to_string(),
+        router_addr: "router".to_string(),
+        fee_collector: "treasury".to_string(),
+        protocol_fee_bps: 0,
+        referral_share: Decimal::zero(),
+        referral_source: ReferralSource::ProtocolFee,
+        bot_priority_window: 60,
+        min_bot_bond: Uint128::from(1_000u128),
+        bot_unbonding_period: 0,
+        bot_whitelist_enabled: false,
+        min_interval: 0,
+        min_dca_amounts: vec![],
+        max_orders_per_user: 10,
+    }
+}
+
+/// ## Description
+/// Instantiates the contract with the [`instantiate_msg`].
+pub fn setup_contract(deps: DepsMut) {
+    setup_contract_with(deps, instantiate_msg());
+}
+
+/// ## Description
+/// Instantiates the contract with `msg`, sent by `owner`.
+pub fn setup_contract_with(deps: DepsMut, msg: InstantiateMsg) {
+    instantiate(deps, mock_env(), mock_info("owner", &[]), msg).unwrap();
+}
+
+/// ## Description
+/// Returns the [`AssetInfo`] of the native token `denom`.
+pub fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+/// ## Description
+/// Returns the [`AssetInfo`] of uusd.
+pub fn uusd() -> AssetInfo {
+    native("uusd")
+}
+
+/// ## Description
+/// Returns the [`AssetInfo`] of uluna.
+pub fn uluna() -> AssetInfo {
+    native("uluna")
+}
+
+/// ## Description
+/// Returns the [`mock_env`] with its block time moved `offset` seconds forward.
+pub fn env_at(offset: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(offset);
+    env
+}
@@ -0,0 +1,20 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::BotInfo;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::BOTS;
+
+/// ## Description
+/// Returns information about a registered bot.
+///
+/// The result is returned in a [`BotInfo`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `bot` - The bots lowercase address as a [`String`].
+pub fn get_bot(deps: Deps, bot: String) -> StdResult<BotInfo> {
+    let bot_address = addr_validate_to_lower(deps.api, &bot)?;
+
+    BOTS.load(deps.storage, &bot_address)
+}
@@ -0,0 +1,39 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::BotInfo;
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::state::BOTS;
+
+/// The maximum amount of bots that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of bots returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns information about the registered bots, ordered by their address.
+///
+/// The result is returned in a [`Vec<BotInfo>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `start_after` - An optional address as a [`String`] to start returning bots after.
+///
+/// * `limit` - An optional amount of bots to return.
+pub fn get_bots(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<BotInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|bot| addr_validate_to_lower(deps.api, &bot))
+        .transpose()?
+        .map(|bot| Bound::exclusive(bot.as_str()));
+
+    BOTS.range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, bot)| bot))
+        .collect()
+}
@@ -1,6 +1,7 @@
+use astroport_dca::dca::Config;
 use cosmwasm_std::{Deps, StdResult};
 
-use crate::state::{Config, CONFIG};
+use crate::state::CONFIG;
 
 /// ## Description
 /// Returns the contract configuration set by the factory address owner or contract instantiator.
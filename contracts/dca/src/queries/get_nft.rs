@@ -0,0 +1,82 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::{NftInfoResponse, OwnerOfResponse, TokensResponse};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::state::{dca_orders, load_order};
+
+/// The maximum amount of token ids that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of token ids returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the owner of a DCA order, as in the CW721 `owner_of` query.
+///
+/// The result is returned in a [`OwnerOfResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `token_id` - The id of the order as a [`String`].
+pub fn get_owner_of(deps: Deps, token_id: String) -> StdResult<OwnerOfResponse> {
+    let order = load_order(deps.storage, &token_id)?;
+
+    Ok(OwnerOfResponse {
+        owner: order.owner.to_string(),
+    })
+}
+
+/// ## Description
+/// Returns a DCA order, as in the CW721 `nft_info` query.
+///
+/// The result is returned in a [`NftInfoResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `token_id` - The id of the order as a [`String`].
+pub fn get_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: load_order(deps.storage, &token_id)?,
+    })
+}
+
+/// ## Description
+/// Returns the ids of the DCA orders owned by a user, as in the CW721 `tokens` query.
+///
+/// The result is returned in a [`TokensResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `owner` - The address of the user as a [`String`].
+///
+/// * `start_after` - An optional token id as a [`String`] to start returning token ids after.
+///
+/// * `limit` - An optional amount of token ids to return.
+pub fn get_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let owner = addr_validate_to_lower(deps.api, &owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|token_id| load_order(deps.storage, &token_id))
+        .transpose()?
+        .map(|order| Bound::exclusive_int(order.id));
+
+    let tokens = dca_orders()
+        .idx
+        .owner
+        .prefix(owner.as_bytes().to_vec())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, order)| order.id.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
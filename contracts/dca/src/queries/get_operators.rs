@@ -0,0 +1,45 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::OperatorApproval;
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::state::OPERATORS;
+
+/// The maximum amount of operators that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of operators returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the operators approved by a user, ordered by their address.
+///
+/// The result is returned in a [`Vec<OperatorApproval>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `owner` - The address of the user as a [`String`].
+///
+/// * `start_after` - An optional address as a [`String`] to start returning operators after.
+///
+/// * `limit` - An optional amount of operators to return.
+pub fn get_operators(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<OperatorApproval>> {
+    let owner = addr_validate_to_lower(deps.api, &owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|operator| addr_validate_to_lower(deps.api, &operator))
+        .transpose()?
+        .map(|operator| Bound::exclusive(operator.as_str()));
+
+    OPERATORS
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, approval)| approval))
+        .collect()
+}
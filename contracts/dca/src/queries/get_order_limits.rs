@@ -0,0 +1,24 @@
+use astroport::asset::AssetInfo;
+use astroport_dca::dca::OrderLimitsResponse;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::CONFIG;
+
+/// ## Description
+/// Returns the limits that a new or modified DCA order spending `initial_asset` must satisfy.
+///
+/// The result is returned in a [`OrderLimitsResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `initial_asset` - The [`AssetInfo`] being spent by the order.
+pub fn get_order_limits(deps: Deps, initial_asset: AssetInfo) -> StdResult<OrderLimitsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(OrderLimitsResponse {
+        min_interval: config.min_interval,
+        min_dca_amount: config.min_dca_amount_for(&initial_asset),
+        max_orders_per_user: config.max_orders_per_user,
+    })
+}
@@ -0,0 +1,75 @@
+use astroport::{
+    asset::Asset,
+    querier::{query_pair_info, simulate},
+};
+use astroport_dca::dca::{CostBasis, OrderPerformanceResponse};
+use cosmwasm_std::{Decimal, Deps, StdError, StdResult, Uint128};
+use cw_storage_plus::U64Key;
+
+use crate::state::{load_order, CONFIG, COST_BASIS};
+
+/// ## Description
+/// Returns the average cost of the `target_asset` purchased by a DCA order, and its current value
+/// in `initial_asset` from simulating a swap of it back through the pair of the assets.
+///
+/// Only fills whose proceeds were measured by the contract are counted, and their cost includes the
+/// protocol fee but not the tip. The cost basis is kept after the order is cancelled, and restarts
+/// if the order is modified to other assets.
+///
+/// The result is returned in an [`OrderPerformanceResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `token_id` - The id of the order as a [`String`].
+pub fn get_order_performance(deps: Deps, token_id: String) -> StdResult<OrderPerformanceResponse> {
+    let order_id = token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::parse_err("u64", format!("Invalid token id {}", token_id)))?;
+
+    let CostBasis { spent, received } =
+        match COST_BASIS.may_load(deps.storage, U64Key::new(order_id))? {
+            Some(cost_basis) => cost_basis,
+            None => {
+                let order = load_order(deps.storage, &token_id)?;
+                CostBasis {
+                    spent: Asset {
+                        info: order.initial_asset.info,
+                        amount: Uint128::zero(),
+                    },
+                    received: Asset {
+                        info: order.target_asset,
+                        amount: Uint128::zero(),
+                    },
+                }
+            }
+        };
+
+    if received.amount.is_zero() {
+        return Ok(OrderPerformanceResponse {
+            unrealized_loss: spent.amount,
+            spent,
+            received,
+            average_price: None,
+            current_value: Uint128::zero(),
+            unrealized_profit: Uint128::zero(),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pair = query_pair_info(
+        &deps.querier,
+        config.factory_addr,
+        &[received.info.clone(), spent.info.clone()],
+    )?;
+    let current_value = simulate(&deps.querier, pair.contract_addr, &received)?.return_amount;
+
+    Ok(OrderPerformanceResponse {
+        average_price: Some(Decimal::from_ratio(spent.amount, received.amount)),
+        current_value,
+        unrealized_profit: current_value.saturating_sub(spent.amount),
+        unrealized_loss: spent.amount.saturating_sub(current_value),
+        spent,
+        received,
+    })
+}
@@ -0,0 +1,44 @@
+use astroport_dca::dca::PurchaseRecord;
+use cosmwasm_std::{Deps, Order, StdError, StdResult};
+use cw_storage_plus::{Bound, U64Key};
+
+use crate::state::PURCHASE_HISTORY;
+
+/// The maximum amount of fills that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of fills returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the fills of a DCA order, ordered by their id. The history is kept after the order is
+/// cancelled.
+///
+/// The result is returned in a [`Vec<PurchaseRecord>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `token_id` - The token id of the order, which is the order id.
+///
+/// * `start_after` - An optional fill id to start returning fills after.
+///
+/// * `limit` - An optional amount of fills to return.
+pub fn get_purchase_history(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PurchaseRecord>> {
+    let order_id = token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::parse_err("u64", format!("Invalid token id {}", token_id)))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+
+    PURCHASE_HISTORY
+        .prefix(U64Key::new(order_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
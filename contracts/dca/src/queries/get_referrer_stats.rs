@@ -0,0 +1,22 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::ReferrerStats;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::REFERRER_STATS;
+
+/// ## Description
+/// Returns the referral statistics and claimable rewards of a referrer.
+///
+/// The result is returned in a [`ReferrerStats`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `referrer` - The referrers lowercase address as a [`String`].
+pub fn get_referrer_stats(deps: Deps, referrer: String) -> StdResult<ReferrerStats> {
+    let referrer_address = addr_validate_to_lower(deps.api, &referrer)?;
+
+    Ok(REFERRER_STATS
+        .may_load(deps.storage, &referrer_address)?
+        .unwrap_or_default())
+}
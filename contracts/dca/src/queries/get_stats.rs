@@ -0,0 +1,89 @@
+use astroport::asset::{addr_validate_to_lower, AssetInfo};
+use astroport_dca::dca::{PairStats, StatsResponse};
+use cosmwasm_std::{Deps, Order, StdResult, Uint128};
+use cw_storage_plus::{Bound, PrimaryKey};
+
+use crate::state::{GLOBAL_STATS, USER_STATS};
+
+/// The maximum amount of pairs that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of pairs returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the totals of the statistics of each pair along with the pairs themselves.
+/// ## Arguments
+/// * `pairs` - The [`PairStats`] of each pair of initial and target asset.
+fn stats_response(pairs: Vec<PairStats>) -> StdResult<StatsResponse> {
+    let mut response = StatsResponse {
+        orders_created: 0,
+        active_orders: 0,
+        purchases: 0,
+        tips_paid: Uint128::zero(),
+        pairs: vec![],
+    };
+
+    for pair in &pairs {
+        response.orders_created += pair.orders_created;
+        response.active_orders += pair.active_orders;
+        response.purchases += pair.purchases;
+        response.tips_paid = response.tips_paid.checked_add(pair.tips_paid)?;
+    }
+    response.pairs = pairs;
+
+    Ok(response)
+}
+
+/// ## Description
+/// Returns the statistics of all DCA orders, broken down per pair of initial and target asset.
+///
+/// The result is returned in a [`StatsResponse`] object, whose totals only cover the pairs
+/// returned.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `start_after` - An optional pair of initial and target [`AssetInfo`] to start returning
+/// statistics after.
+///
+/// * `limit` - An optional amount of pairs to return.
+pub fn get_global_stats(
+    deps: Deps,
+    start_after: Option<(AssetInfo, AssetInfo)>,
+    limit: Option<u32>,
+) -> StdResult<StatsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|(initial_asset, target_asset)| {
+        Bound::exclusive((initial_asset.as_bytes(), target_asset.as_bytes()).joined_key())
+    });
+
+    let pairs = GLOBAL_STATS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, stats)| stats))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    stats_response(pairs)
+}
+
+/// ## Description
+/// Returns the statistics of the DCA orders of a user, broken down per pair of initial and target
+/// asset.
+///
+/// The result is returned in a [`StatsResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `user` - The users lowercase address as a [`String`].
+pub fn get_user_stats(deps: Deps, user: String) -> StdResult<StatsResponse> {
+    let user_address = addr_validate_to_lower(deps.api, &user)?;
+
+    let pairs = USER_STATS
+        .sub_prefix(&user_address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stats)| stats))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    stats_response(pairs)
+}
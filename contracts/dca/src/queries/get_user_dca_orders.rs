@@ -1,30 +1,50 @@
 use astroport::asset::{addr_validate_to_lower, AssetInfo};
-use astroport_dca::dca::DcaQueryInfo;
+use astroport_dca::{dca::DcaQueryInfo, evaluation::auction_tip};
 use cosmwasm_std::{Deps, Env, StdResult};
 
-use crate::{get_token_allowance::get_token_allowance, state::USER_DCA};
+use crate::{
+    get_token_allowance::get_token_allowance,
+    state::{user_orders, CONFIG, USER_CONFIG},
+};
 
 /// ## Description
-/// Returns a users DCA orders currently set.
+/// Returns a users DCA orders currently set, leaving out paused orders unless `include_paused` is
+/// set.
 ///
 /// The result is returned in a [`Vec<DcaQueryInfo`] object of the users current DCA orders with the
 /// `amount` of each order set to the native token amount that can be spent, or the token allowance.
 ///
+/// If the user prices tips with a Dutch auction, the tip currently offered for each order is also
+/// returned, along with the maximum hops and spread applied to purchases of each order.
+///
 /// ## Arguments
 /// * `deps` - A [`Deps`] that contains the dependencies.
 ///
 /// * `env` - The [`Env`] of the blockchain.
 ///
 /// * `user` - The users lowercase address as a [`String`].
-pub fn get_user_dca_orders(deps: Deps, env: Env, user: String) -> StdResult<Vec<DcaQueryInfo>> {
+///
+/// * `include_paused` - Whether paused orders are returned.
+pub fn get_user_dca_orders(
+    deps: Deps,
+    env: Env,
+    user: String,
+    include_paused: bool,
+) -> StdResult<Vec<DcaQueryInfo>> {
     let user_address = addr_validate_to_lower(deps.api, &user)?;
+    let config = CONFIG.load(deps.storage)?;
+    let user_config = USER_CONFIG
+        .may_load(deps.storage, &user_address)?
+        .unwrap_or_default();
 
-    USER_DCA
-        .load(deps.storage, &user_address)?
+    user_orders(deps.storage, &user_address)?
         .into_iter()
+        .filter(|order| include_paused || order.paused_at.is_none())
         .map(|order| {
             Ok(DcaQueryInfo {
-                info: order,
+                auction_tip: auction_tip(&user_config.tip_mode, &order, env.block.time.seconds()),
+                max_hops: config.max_hops_for(&user_config, &order),
+                max_spread: config.max_spread_for(&user_config, &order),
                 token_allowance: match &order.initial_asset.info {
                     AssetInfo::NativeToken { .. } => order.initial_asset.amount,
                     AssetInfo::Token { contract_addr } => {
@@ -32,6 +52,7 @@ pub fn get_user_dca_orders(deps: Deps, env: Env, user: String) -> StdResult<Vec<
                         get_token_allowance(&deps, &env, &user_address, contract_addr)?
                     }
                 },
+                info: order,
             })
         })
         .collect::<StdResult<Vec<_>>>()
@@ -0,0 +1,110 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::{VaultInfo, VaultPositionResponse, VaultPurchaseRecord};
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::{Bound, U64Key};
+
+use crate::state::{claimable_vault_proceeds, VAULTS, VAULT_POSITIONS, VAULT_PURCHASE_HISTORY};
+
+/// The maximum amount of vaults or vault purchases that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of vaults or vault purchases returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns information about the vault `vault_id`.
+///
+/// The result is returned in a [`VaultInfo`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `vault_id` - The id of the vault.
+pub fn get_vault(deps: Deps, vault_id: u64) -> StdResult<VaultInfo> {
+    VAULTS.load(deps.storage, U64Key::new(vault_id))
+}
+
+/// ## Description
+/// Returns information about the vaults, ordered by their id.
+///
+/// The result is returned in a [`Vec<VaultInfo>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `start_after` - An optional vault id to start returning vaults after.
+///
+/// * `limit` - An optional amount of vaults to return.
+pub fn get_vaults(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<VaultInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+
+    VAULTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, vault)| vault))
+        .collect()
+}
+
+/// ## Description
+/// Returns the position of `user` in the vault `vault_id`, with the `initial_asset` it has not
+/// spent yet, the `target_asset` it can claim and the state of its tip deposit.
+///
+/// The result is returned in a [`VaultPositionResponse`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `vault_id` - The id of the vault.
+///
+/// * `user` - The address of the user as a [`String`].
+pub fn get_vault_position(
+    deps: Deps,
+    vault_id: u64,
+    user: String,
+) -> StdResult<VaultPositionResponse> {
+    let user = addr_validate_to_lower(deps.api, &user)?;
+    let vault = VAULTS.load(deps.storage, U64Key::new(vault_id))?;
+    let position = VAULT_POSITIONS.load(deps.storage, (U64Key::new(vault_id), &user))?;
+
+    Ok(VaultPositionResponse {
+        shares: position.shares,
+        remaining: position.remaining(&vault),
+        claimable: claimable_vault_proceeds(deps.storage, &vault, &position)?,
+        tip_balance: position.tip_deposit,
+        suspended: position.suspended,
+    })
+}
+
+/// ## Description
+/// Returns the aggregated purchases of the vault `vault_id`, ordered by their epoch.
+///
+/// The result is returned in a [`Vec<VaultPurchaseRecord>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `vault_id` - The id of the vault.
+///
+/// * `start_after` - An optional epoch to start returning purchases after.
+///
+/// * `limit` - An optional amount of purchases to return.
+pub fn get_vault_purchase_history(
+    deps: Deps,
+    vault_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<VaultPurchaseRecord>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+
+    VAULT_PURCHASE_HISTORY
+        .prefix(U64Key::new(vault_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
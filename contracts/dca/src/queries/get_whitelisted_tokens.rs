@@ -0,0 +1,36 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::state::WHITELISTED_TOKENS;
+
+/// The maximum amount of whitelisted tokens that can be returned at once
+const MAX_LIMIT: u32 = 30;
+/// The amount of whitelisted tokens returned if no limit is specified
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the tokens that can be used in a hop route for DCA purchases.
+///
+/// The result is returned in a [`Vec<AssetInfo>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `start_after` - An optional [`AssetInfo`] to start returning whitelisted tokens after.
+///
+/// * `limit` - An optional amount of whitelisted tokens to return.
+pub fn get_whitelisted_tokens(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AssetInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|token| Bound::exclusive(token.as_bytes()));
+
+    WHITELISTED_TOKENS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, token)| token))
+        .collect()
+}
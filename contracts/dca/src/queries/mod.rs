@@ -1,7 +1,29 @@
+mod get_bot;
+mod get_bots;
 mod get_config;
+mod get_nft;
+mod get_operators;
+mod get_order_limits;
+mod get_order_performance;
+mod get_purchase_history;
+mod get_referrer_stats;
+mod get_stats;
 mod get_user_config;
 mod get_user_dca_orders;
+mod get_vaults;
+mod get_whitelisted_tokens;
 
+pub use get_bot::get_bot;
+pub use get_bots::get_bots;
 pub use get_config::get_config;
+pub use get_nft::{get_nft_info, get_owner_of, get_tokens};
+pub use get_operators::get_operators;
+pub use get_order_limits::get_order_limits;
+pub use get_order_performance::get_order_performance;
+pub use get_purchase_history::get_purchase_history;
+pub use get_referrer_stats::get_referrer_stats;
+pub use get_stats::{get_global_stats, get_user_stats};
 pub use get_user_config::get_user_config;
 pub use get_user_dca_orders::get_user_dca_orders;
+pub use get_vaults::{get_vault, get_vault_position, get_vault_purchase_history, get_vaults};
+pub use get_whitelisted_tokens::get_whitelisted_tokens;
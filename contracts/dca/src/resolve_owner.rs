@@ -0,0 +1,48 @@
+use astroport::asset::addr_validate_to_lower;
+use astroport_dca::dca::OperatorPermission;
+use cosmwasm_std::{Addr, Deps, Env, MessageInfo};
+
+use crate::{error::ContractError, state::OPERATORS};
+
+/// ## Description
+/// Returns the user whose DCA orders a message manages, which is the `owner` if specified, or the
+/// sender otherwise.
+///
+/// Returns a [`ContractError::Unauthorized`] if the sender manages the orders of another user
+/// without an unexpired approval with the required permission.
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - The [`MessageInfo`] of the message.
+///
+/// * `owner` - The address of the user whose orders are managed as a [`String`], if any.
+///
+/// * `permission` - The [`OperatorPermission`] required to manage the orders of another user.
+pub fn resolve_owner(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    owner: Option<String>,
+    permission: OperatorPermission,
+) -> Result<Addr, ContractError> {
+    let owner = match owner {
+        Some(owner) => addr_validate_to_lower(deps.api, &owner)?,
+        None => return Ok(info.sender.clone()),
+    };
+
+    if owner == info.sender {
+        return Ok(owner);
+    }
+
+    match OPERATORS.may_load(deps.storage, (&owner, &info.sender))? {
+        Some(approval)
+            if !approval.expires.is_expired(&env.block)
+                && approval.permissions.contains(&permission) =>
+        {
+            Ok(owner)
+        }
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
@@ -0,0 +1,59 @@
+use astroport::{
+    asset::{Asset, AssetInfo},
+    router::{Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation},
+};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, StdResult, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+/// ## Description
+/// Returns the message swapping `offer_asset` held by the contract through the router using the
+/// hop route `hops`, sending the proceeds to `to`.
+/// ## Arguments
+/// * `router_addr` - The address of the Astroport router.
+///
+/// * `offer_asset` - The [`Asset`] to swap.
+///
+/// * `hops` - The hop operations of the swap.
+///
+/// * `to` - The address receiving the proceeds of the swap.
+///
+/// * `max_spread` - The maximum spread of the swap.
+pub fn router_swap_message(
+    router_addr: &Addr,
+    offer_asset: &Asset,
+    hops: Vec<SwapOperation>,
+    to: &Addr,
+    max_spread: Decimal,
+) -> StdResult<CosmosMsg> {
+    Ok(match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: router_addr.to_string(),
+            funds: vec![Coin {
+                amount: offer_asset.amount,
+                denom: denom.clone(),
+            }],
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: hops,
+                minimum_receive: None,
+                to: Some(to.clone()),
+                max_spread: Some(max_spread),
+            })?,
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: router_addr.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: hops,
+                    minimum_receive: None,
+                    to: Some(to.to_string()),
+                    max_spread: Some(max_spread),
+                })?,
+            })?,
+        }
+        .into(),
+    })
+}
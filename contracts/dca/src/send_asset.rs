@@ -0,0 +1,31 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, StdResult, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+/// ## Description
+/// Returns the message sending `asset` from the contract to `recipient`.
+/// ## Arguments
+/// * `asset` - The [`Asset`] to send.
+///
+/// * `recipient` - The address receiving the asset.
+pub fn send_asset(asset: &Asset, recipient: &Addr) -> StdResult<CosmosMsg> {
+    Ok(match &asset.info {
+        AssetInfo::NativeToken { denom } => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                amount: asset.amount,
+                denom: denom.clone(),
+            }],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: asset.amount,
+            })?,
+        }
+        .into(),
+    })
+}
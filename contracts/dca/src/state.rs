@@ -1,58 +1,568 @@
-use astroport::asset::AssetInfo;
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::{Item, Map};
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Decimal, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use astroport_dca::dca::DcaInfo;
+use astroport_dca::dca::{
+    BotInfo, Config, CostBasis, DcaInfo, OperatorApproval, PairStats, PurchaseRecord,
+    ReferrerStats, UserConfig, VaultInfo, VaultPurchaseRecord,
+};
 
-/// Stores the main dca module parameters.
+/// Stores the position of a user in a DCA vault
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Config {
-    /// The maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing if the user does not specify
-    pub max_hops: u32,
-    /// The maximum amount of spread when performing a swap from `initial_asset` to `target_asset` when DCAing if the user does not specify
-    pub max_spread: Decimal,
-    /// The fee a user must pay per hop performed in a DCA purchase
-    pub per_hop_fee: Uint128,
-    /// The whitelisted tokens that can be used in a DCA purchase route
-    pub whitelisted_tokens: Vec<AssetInfo>,
-    /// The address of the Astroport factory contract
-    pub factory_addr: Addr,
-    /// The address of the Astroport router contract
-    pub router_addr: Addr,
-}
-
-impl Config {
-    pub fn is_whitelisted_asset(&self, asset: &AssetInfo) -> bool {
-        self.whitelisted_tokens.contains(asset)
+pub struct VaultPosition {
+    /// The amount of `initial_asset` the position spends in each purchase of the vault
+    pub shares: Uint128,
+    /// The epoch of the vault when the position was deposited
+    pub start_epoch: u64,
+    /// The epoch of the last purchase the position takes part in
+    pub end_epoch: u64,
+    /// The epoch up to which the purchases of the position have been claimed
+    pub claimed_epoch: u64,
+    /// The amount of uusd deposited for the tips of the purchases the position takes part in,
+    /// that has not been charged yet
+    #[serde(default)]
+    pub tip_deposit: Uint128,
+    /// The amount of `initial_asset` returned to the position outside of its shares, from the
+    /// protocol fee of an exempt owner or the purchases it was suspended from
+    #[serde(default)]
+    pub refund: Uint128,
+    /// Whether the position was suspended from the purchases of the vault because its tip
+    /// deposit could not cover its share of a tip
+    #[serde(default)]
+    pub suspended: bool,
+}
+
+impl VaultPosition {
+    /// Returns the epoch of the last purchase the position has taken part in so far.
+    pub fn last_epoch(&self, vault: &VaultInfo) -> u64 {
+        vault.epoch.min(self.end_epoch)
+    }
+
+    /// Returns whether the shares of the position are still counted in the vault `total_shares`.
+    pub fn is_active(&self, vault: &VaultInfo) -> bool {
+        vault.epoch <= self.end_epoch
+    }
+
+    /// Returns the amount of `initial_asset` of the position that has not been spent yet.
+    pub fn remaining(&self, vault: &VaultInfo) -> Uint128 {
+        self.shares * Uint128::from(self.end_epoch - self.last_epoch(vault)) + self.refund
+    }
+
+    /// Suspends the position from the purchase of the current epoch of `vault` onwards, returning
+    /// the shares it no longer spends to the position.
+    pub fn suspend(&mut self, vault: &VaultInfo) -> StdResult<()> {
+        let unspent = self.shares * Uint128::from(self.end_epoch + 1 - vault.epoch);
+        self.refund = self.refund.checked_add(unspent)?;
+        self.end_epoch = vault.epoch - 1;
+        self.suspended = true;
+
+        Ok(())
     }
 }
 
-/// Stores the users custom configuration
+/// Stores the state of a vault purchase until the router has returned the proceeds
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct UserConfig {
-    /// An override for the maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing
-    pub max_hops: Option<u32>,
-    /// An override for the maximum amount of spread when performing a swap from `initial_asset` to `target_asset` when DCAing
-    pub max_spread: Option<Decimal>,
-    /// The amount of uusd the user has deposited for their tips when performing DCA purchases
-    pub tip_balance: Uint128,
-}
-
-impl Default for UserConfig {
-    fn default() -> Self {
-        UserConfig {
-            max_hops: None,
-            max_spread: None,
-            tip_balance: Uint128::zero(),
-        }
-    }
+pub struct PendingVaultPurchase {
+    /// The id of the vault performing the purchase
+    pub vault_id: u64,
+    /// The epoch of the vault for the purchase
+    pub epoch: u64,
+    /// The shares that took part in the purchase
+    pub shares: Uint128,
+    /// The amount of `initial_asset` swapped in the purchase, after the protocol fee
+    pub swap_amount: Uint128,
+    /// The uusd tip paid for the purchase
+    pub tip: Uint128,
+    /// The `target_asset` balance of the contract before the purchase
+    pub balance_before: Uint128,
+}
+
+/// Stores the state of a DCA purchase until the router has sent the proceeds to the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingDcaPurchase {
+    /// The id of the DCA order being purchased
+    pub order_id: u64,
+    /// The owner of the DCA order, who receives the proceeds
+    pub owner: Addr,
+    /// The amount of `initial_asset` swapped for the order
+    pub offer_asset: Asset,
+    /// The `target_asset` of the order
+    pub ask_asset: AssetInfo,
+    /// The `target_asset` balance of the owner before the swap
+    pub balance_before: Uint128,
+}
+
+/// Stores a fill of a batch settlement which is routed through the router
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFill {
+    /// The id of the DCA order being filled
+    pub order_id: u64,
+    /// The owner of the DCA order, who receives the proceeds
+    pub owner: Addr,
+    /// The amount of `initial_asset` swapped for the order
+    pub offer_amount: Uint128,
+}
+
+/// Stores the state of a batch settlement until the router has returned the proceeds of the net
+/// imbalance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBatchSettlement {
+    /// The asset swapped through the router
+    pub offer_asset: AssetInfo,
+    /// The asset received from the router
+    pub ask_asset: AssetInfo,
+    /// The fills taking part in the swap
+    pub fills: Vec<PendingFill>,
+    /// The `ask_asset` balance of the contract before the swap
+    pub balance_before: Uint128,
 }
 
+/// The maximum protocol fee in basis points that can be taken from a DCA purchase
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 500;
+/// The maximum share of the uusd value of a purchase that is paid as a tip, in percent
+pub const MAX_TIP_VOLUME_PERCENT: u64 = 5;
+/// The maximum spread that can be set for DCA purchases, in percent
+pub const MAX_SPREAD_LIMIT_PERCENT: u64 = 50;
+/// The maximum amount of hops that can be set for DCA purchases, matching the router limit
+pub const MAX_HOPS_LIMIT: u32 = 50;
+/// The maximum amount of DCA orders a user can have at once if none is set when migrating
+pub const DEFAULT_MAX_ORDERS_PER_USER: u32 = 10;
+/// The id of the reply to the swap of a vault purchase
+pub const VAULT_PURCHASE_REPLY_ID: u64 = 1;
+/// The id of the reply to the swap of the net imbalance of a batch settlement
+pub const BATCH_SETTLEMENT_REPLY_ID: u64 = 2;
+/// The id of the reply to the swap of a DCA purchase
+pub const DCA_PURCHASE_REPLY_ID: u64 = 3;
+
 /// The contract configuration
 pub const CONFIG: Item<Config> = Item::new("config");
 /// The configuration set by each user
 pub const USER_CONFIG: Map<&Addr, UserConfig> = Map::new("user_config");
-/// The DCA orders for a user
-pub const USER_DCA: Map<&Addr, Vec<DcaInfo>> = Map::new("user_dca");
+/// The whitelisted tokens that can be used in a DCA purchase route, keyed by their denom or
+/// contract address
+pub const WHITELISTED_TOKENS: Map<&[u8], AssetInfo> = Map::new("whitelisted_tokens");
+/// The amount of DCA orders that have been created, used to assign order ids
+pub const ORDER_COUNT: Item<u64> = Item::new("order_count");
+/// The referral statistics and rewards for each referrer
+pub const REFERRER_STATS: Map<&Addr, ReferrerStats> = Map::new("referrer_stats");
+/// The registered bots
+pub const BOTS: Map<&Addr, BotInfo> = Map::new("bots");
+/// The operators approved by each user, keyed by the user and operator address
+pub const OPERATORS: Map<(&Addr, &Addr), OperatorApproval> = Map::new("operators");
+/// The amount of vaults that have been created, used to assign vault ids
+pub const VAULT_COUNT: Item<u64> = Item::new("vault_count");
+/// The DCA vaults, keyed by their id
+pub const VAULTS: Map<U64Key, VaultInfo> = Map::new("vaults");
+/// The key of a DCA vault, made of its initial asset, target asset, interval and maximum spread
+pub type VaultKey<'a> = (&'a [u8], &'a [u8], (U64Key, Vec<u8>));
+/// The ids of the DCA vaults, keyed by their [`VaultKey`]
+pub const VAULT_IDS: Map<VaultKey, u64> = Map::new("vault_ids");
+/// The positions of the users in each vault, keyed by the vault id and user address
+pub const VAULT_POSITIONS: Map<(U64Key, &Addr), VaultPosition> = Map::new("vault_positions");
+/// The cumulative amount of `target_asset` purchased per share of a vault after each epoch,
+/// keyed by the vault id and epoch
+pub const VAULT_PROCEEDS_INDEX: Map<(U64Key, U64Key), Decimal> = Map::new("vault_proceeds_index");
+/// The aggregated purchases of each vault, keyed by the vault id and epoch
+pub const VAULT_PURCHASE_HISTORY: Map<(U64Key, U64Key), VaultPurchaseRecord> =
+    Map::new("vault_purchase_history");
+/// The shares that stop taking part in the purchases of a vault from an epoch, keyed by the
+/// vault id and epoch
+pub const VAULT_EXPIRING_SHARES: Map<(U64Key, U64Key), Uint128> = Map::new("vault_expiring_shares");
+/// The amount of fills that have been recorded, used to assign fill ids
+pub const PURCHASE_COUNT: Item<u64> = Item::new("purchase_count");
+/// The fills of each DCA order, keyed by the order id and fill id
+pub const PURCHASE_HISTORY: Map<(U64Key, U64Key), PurchaseRecord> = Map::new("purchase_history");
+/// The amounts spent and received by the measured fills of each DCA order, keyed by the order id
+pub const COST_BASIS: Map<U64Key, CostBasis> = Map::new("cost_basis");
+/// The statistics of the DCA orders of all users, keyed by their initial and target asset
+pub const GLOBAL_STATS: Map<(&[u8], &[u8]), PairStats> = Map::new("global_stats");
+/// The statistics of the DCA orders of each user, keyed by the user address, initial asset and
+/// target asset
+pub const USER_STATS: Map<(&Addr, &[u8], &[u8]), PairStats> = Map::new("user_stats");
+/// The batch settlement awaiting the reply of the router swap
+pub const PENDING_BATCH_SETTLEMENT: Item<PendingBatchSettlement> =
+    Item::new("pending_batch_settlement");
+/// The vault purchase awaiting the reply of the router swap
+pub const PENDING_VAULT_PURCHASE: Item<PendingVaultPurchase> = Item::new("pending_vault_purchase");
+/// The DCA purchase awaiting the reply of the router swap
+pub const PENDING_DCA_PURCHASE: Item<PendingDcaPurchase> = Item::new("pending_dca_purchase");
+
+/// Stores the indexes of the DCA orders
+pub struct DcaOrderIndexes<'a> {
+    /// Indexes the DCA orders by the address of their owner
+    pub owner: MultiIndex<'a, (Vec<u8>, Vec<u8>), DcaInfo>,
+}
+
+impl<'a> IndexList<DcaInfo> for DcaOrderIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DcaInfo>> + '_> {
+        let v: Vec<&dyn Index<DcaInfo>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// The DCA orders of all users, keyed by their id
+pub fn dca_orders<'a>() -> IndexedMap<'a, U64Key, DcaInfo, DcaOrderIndexes<'a>> {
+    IndexedMap::new(
+        "dca_orders",
+        DcaOrderIndexes {
+            owner: MultiIndex::new(
+                |order, pk| (order.owner.as_bytes().to_vec(), pk),
+                "dca_orders",
+                "dca_orders__owner",
+            ),
+        },
+    )
+}
+
+/// ## Description
+/// Returns whether `asset` is whitelisted to be used in a DCA purchase route.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `asset` - The [`AssetInfo`] to check.
+pub fn is_whitelisted_asset(storage: &dyn Storage, asset: &AssetInfo) -> bool {
+    WHITELISTED_TOKENS.has(storage, asset.as_bytes())
+}
+
+/// ## Description
+/// Returns the id to assign to a new DCA order, incrementing the [`ORDER_COUNT`].
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+pub fn next_order_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = ORDER_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    ORDER_COUNT.save(storage, &id)?;
+
+    Ok(id)
+}
+
+/// ## Description
+/// Returns the DCA orders created by `owner`, in the order they were created.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `owner` - The address of the user whose orders are returned.
+pub fn user_orders(storage: &dyn Storage, owner: &Addr) -> StdResult<Vec<DcaInfo>> {
+    dca_orders()
+        .idx
+        .owner
+        .prefix(owner.as_bytes().to_vec())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .collect()
+}
+
+/// ## Description
+/// Returns the DCA order with the CW721 token id `token_id`, which is the order id.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `token_id` - The token id of the order.
+pub fn load_order(storage: &dyn Storage, token_id: &str) -> StdResult<DcaInfo> {
+    let id = token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::parse_err("u64", format!("Invalid token id {}", token_id)))?;
+
+    dca_orders().load(storage, U64Key::new(id))
+}
+
+/// ## Description
+/// Saves a new or updated DCA order under its id.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `order` - The [`DcaInfo`] of the order to save.
+pub fn save_order(storage: &mut dyn Storage, order: &DcaInfo) -> StdResult<()> {
+    dca_orders().save(storage, U64Key::new(order.id), order)
+}
+
+/// ## Description
+/// Returns the id of the vault for `initial_asset`, `target_asset`, `interval` and `max_spread`,
+/// creating the vault if it does not exist yet.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `initial_asset` - The [`AssetInfo`] spent by the vault.
+///
+/// * `target_asset` - The [`AssetInfo`] purchased by the vault.
+///
+/// * `interval` - The time in seconds between the purchases of the vault.
+///
+/// * `max_spread` - The maximum spread of the purchases of the vault, if it overrides the
+/// contract `max_spread`.
+pub fn load_or_create_vault(
+    storage: &mut dyn Storage,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    interval: u64,
+    max_spread: Option<Decimal>,
+) -> StdResult<VaultInfo> {
+    let key = (
+        initial_asset.as_bytes(),
+        target_asset.as_bytes(),
+        (
+            U64Key::new(interval),
+            max_spread.map_or_else(Vec::new, |max_spread| max_spread.to_string().into_bytes()),
+        ),
+    );
+
+    if let Some(id) = VAULT_IDS.may_load(storage, key.clone())? {
+        return VAULTS.load(storage, U64Key::new(id));
+    }
+
+    let id = VAULT_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    VAULT_COUNT.save(storage, &id)?;
+    VAULT_IDS.save(storage, key, &id)?;
+
+    let vault = VaultInfo {
+        id,
+        initial_asset: initial_asset.clone(),
+        target_asset: target_asset.clone(),
+        interval,
+        last_purchase: 0,
+        epoch: 0,
+        total_shares: Uint128::zero(),
+        tip_balance: Uint128::zero(),
+        max_spread,
+    };
+    VAULTS.save(storage, U64Key::new(id), &vault)?;
+
+    Ok(vault)
+}
+
+/// ## Description
+/// Returns the cumulative amount of `target_asset` purchased per share of the vault `vault_id`
+/// after the purchase of `epoch`.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `vault_id` - The id of the vault.
+///
+/// * `epoch` - The epoch of the purchase.
+pub fn vault_proceeds_index(
+    storage: &dyn Storage,
+    vault_id: u64,
+    epoch: u64,
+) -> StdResult<Decimal> {
+    Ok(VAULT_PROCEEDS_INDEX
+        .may_load(storage, (U64Key::new(vault_id), U64Key::new(epoch)))?
+        .unwrap_or_default())
+}
+
+/// ## Description
+/// Returns the amount of `target_asset` purchased for `position` that has not been claimed yet.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `vault` - The [`VaultInfo`] of the vault the position is in.
+///
+/// * `position` - The [`VaultPosition`] of the user.
+pub fn claimable_vault_proceeds(
+    storage: &dyn Storage,
+    vault: &VaultInfo,
+    position: &VaultPosition,
+) -> StdResult<Uint128> {
+    let claimed_index = vault_proceeds_index(storage, vault.id, position.claimed_epoch)?;
+    let last_index = vault_proceeds_index(storage, vault.id, position.last_epoch(vault))?;
+
+    Ok(position.shares * (last_index - claimed_index))
+}
+
+/// ## Description
+/// Returns the positions of the vault `vault_id` with the addresses of their owners.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `vault_id` - The id of the vault.
+pub fn vault_positions(
+    storage: &dyn Storage,
+    vault_id: u64,
+) -> StdResult<Vec<(Addr, VaultPosition)>> {
+    VAULT_POSITIONS
+        .prefix(U64Key::new(vault_id))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (owner, position) = item?;
+            let owner = String::from_utf8(owner).map_err(StdError::invalid_utf8)?;
+
+            Ok((Addr::unchecked(owner), position))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Adds `spent` and `received` to the [`CostBasis`] of the DCA order `order_id`.
+///
+/// The cost basis restarts if the order was modified to spend or purchase other assets.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `order_id` - The id of the order.
+///
+/// * `spent` - The [`Asset`] of `initial_asset` spent.
+///
+/// * `received` - The [`Asset`] of `target_asset` received.
+pub fn add_to_cost_basis(
+    storage: &mut dyn Storage,
+    order_id: u64,
+    spent: &Asset,
+    received: &Asset,
+) -> StdResult<()> {
+    let mut cost_basis = match COST_BASIS.may_load(storage, U64Key::new(order_id))? {
+        Some(cost_basis)
+            if cost_basis.spent.info == spent.info && cost_basis.received.info == received.info =>
+        {
+            cost_basis
+        }
+        _ => CostBasis {
+            spent: Asset {
+                info: spent.info.clone(),
+                amount: Uint128::zero(),
+            },
+            received: Asset {
+                info: received.info.clone(),
+                amount: Uint128::zero(),
+            },
+        },
+    };
+    cost_basis.spent.amount = cost_basis.spent.amount.checked_add(spent.amount)?;
+    cost_basis.received.amount = cost_basis.received.amount.checked_add(received.amount)?;
+
+    COST_BASIS.save(storage, U64Key::new(order_id), &cost_basis)
+}
+
+/// ## Description
+/// Records a fill of the DCA order `order_id` in its purchase history, and adds it to the
+/// [`CostBasis`] of the order if the amount received is known.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `order_id` - The id of the order that was filled.
+///
+/// * `time` - The time of the fill.
+///
+/// * `offer_asset` - The [`Asset`] spent in the fill.
+///
+/// * `received` - The [`Asset`] received in the fill, if it is known.
+///
+/// * `internal_match` - Whether the fill was matched against an opposing order.
+pub fn record_purchase(
+    storage: &mut dyn Storage,
+    order_id: u64,
+    time: u64,
+    offer_asset: Asset,
+    received: Option<Asset>,
+    internal_match: bool,
+) -> StdResult<()> {
+    let id = PURCHASE_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    PURCHASE_COUNT.save(storage, &id)?;
+
+    if let Some(received) = &received {
+        add_to_cost_basis(storage, order_id, &offer_asset, received)?;
+    }
+
+    PURCHASE_HISTORY.save(
+        storage,
+        (U64Key::new(order_id), U64Key::new(id)),
+        &PurchaseRecord {
+            id,
+            order_id,
+            time,
+            offer_asset,
+            received,
+            internal_match,
+        },
+    )
+}
+
+/// ## Description
+/// Updates the statistics of the pair of `initial_asset` and `target_asset` for `owner` with
+/// `update`, creating them if the user has none for the pair yet.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `owner` - The owner of the orders being counted.
+///
+/// * `initial_asset` - The [`AssetInfo`] spent by the orders.
+///
+/// * `target_asset` - The [`AssetInfo`] purchased by the orders.
+///
+/// * `update` - The update applied to the [`PairStats`].
+pub fn update_user_stats<F>(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    update: F,
+) -> StdResult<()>
+where
+    F: Fn(&mut PairStats) -> StdResult<()>,
+{
+    let key = (owner, initial_asset.as_bytes(), target_asset.as_bytes());
+
+    let mut stats = USER_STATS
+        .may_load(storage, key)?
+        .unwrap_or_else(|| PairStats::new(initial_asset, target_asset));
+    update(&mut stats)?;
+
+    USER_STATS.save(storage, key, &stats)
+}
+
+/// ## Description
+/// Updates the statistics of the pair of `initial_asset` and `target_asset` for all users with
+/// `update`, creating them if there are none for the pair yet.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `initial_asset` - The [`AssetInfo`] spent by the purchases.
+///
+/// * `target_asset` - The [`AssetInfo`] purchased by the purchases.
+///
+/// * `update` - The update applied to the [`PairStats`].
+pub fn update_global_stats<F>(
+    storage: &mut dyn Storage,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    update: F,
+) -> StdResult<()>
+where
+    F: Fn(&mut PairStats) -> StdResult<()>,
+{
+    let key = (initial_asset.as_bytes(), target_asset.as_bytes());
+
+    let mut stats = GLOBAL_STATS
+        .may_load(storage, key)?
+        .unwrap_or_else(|| PairStats::new(initial_asset, target_asset));
+    update(&mut stats)?;
+
+    GLOBAL_STATS.save(storage, key, &stats)
+}
+
+/// ## Description
+/// Updates the statistics of the pair of `initial_asset` and `target_asset` with `update`, both
+/// for all users and for `owner`.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `owner` - The owner of the orders being counted.
+///
+/// * `initial_asset` - The [`AssetInfo`] spent by the orders.
+///
+/// * `target_asset` - The [`AssetInfo`] purchased by the orders.
+///
+/// * `update` - The update applied to the [`PairStats`].
+pub fn update_stats<F>(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    update: F,
+) -> StdResult<()>
+where
+    F: Fn(&mut PairStats) -> StdResult<()>,
+{
+    update_global_stats(storage, initial_asset, target_asset, &update)?;
+
+    update_user_stats(storage, owner, initial_asset, target_asset, update)
+}
@@ -0,0 +1,262 @@
+use astroport::{
+    asset::{Asset, AssetInfo, UUSD_DENOM},
+    querier::{query_pair_info, simulate},
+};
+use astroport_dca::{
+    dca::{Config, DcaInfo, TipMode, VaultInfo},
+    evaluation::{auction_tip, fixed_tip},
+};
+use cosmwasm_std::{Decimal, QuerierWrapper, StdError, StdResult, Uint128};
+
+use crate::{error::ContractError, state::MAX_TIP_VOLUME_PERCENT};
+
+/// ## Description
+/// Calculates the uusd tip paid to a bot for performing a DCA purchase of `order` with `hops`
+/// hops.
+///
+/// If the user prices tips with a [`TipMode::DutchAuction`], the tip is the current auction tip.
+/// Otherwise the tip is made up of the `base_tip`, the `per_hop_fee` for each hop and the
+/// `tip_volume_rate` of the uusd value of the `dca_amount`, capped at the `max_tip`.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to value the `dca_amount` in uusd.
+///
+/// * `config` - The contract [`Config`].
+///
+/// * `tip_mode` - The [`TipMode`] of the user who created the order.
+///
+/// * `order` - The [`DcaInfo`] of the order being purchased.
+///
+/// * `hops` - The amount of hops in the purchase route.
+///
+/// * `now` - The current block time in seconds.
+pub fn calculate_tip(
+    querier: &QuerierWrapper,
+    config: &Config,
+    tip_mode: &TipMode,
+    order: &DcaInfo,
+    hops: u32,
+    now: u64,
+) -> StdResult<Uint128> {
+    if let Some(tip) = auction_tip(tip_mode, order, now) {
+        return Ok(tip);
+    }
+
+    // the volume is only valued when it is part of the tip, saving the queries otherwise
+    let volume = if config.tip_volume_rate.is_zero() {
+        Uint128::zero()
+    } else {
+        uusd_value(querier, config, &order.initial_asset.info, order.dca_amount)?
+    };
+
+    fixed_tip(
+        config.base_tip,
+        config.per_hop_fee,
+        hops,
+        config.tip_volume_rate,
+        volume,
+        config.max_tip,
+    )
+}
+
+/// ## Description
+/// Calculates the uusd tip paid to a bot for performing the aggregated purchase of `vault` with
+/// `hops` hops.
+///
+/// The tip is made up of the `base_tip`, the `per_hop_fee` for each hop and the `tip_volume_rate`
+/// of the uusd value of the shares taking part in the purchase, capped at the `max_tip`.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to value the purchase in uusd.
+///
+/// * `config` - The contract [`Config`].
+///
+/// * `vault` - The [`VaultInfo`] of the vault being purchased.
+///
+/// * `hops` - The amount of hops in the purchase route.
+pub fn calculate_vault_tip(
+    querier: &QuerierWrapper,
+    config: &Config,
+    vault: &VaultInfo,
+    hops: u32,
+) -> StdResult<Uint128> {
+    let volume = if config.tip_volume_rate.is_zero() {
+        Uint128::zero()
+    } else {
+        uusd_value(querier, config, &vault.initial_asset, vault.total_shares)?
+    };
+
+    fixed_tip(
+        config.base_tip,
+        config.per_hop_fee,
+        hops,
+        config.tip_volume_rate,
+        volume,
+        config.max_tip,
+    )
+}
+
+/// ## Description
+/// Returns the value in uusd of `amount` of `asset_info`, by simulating a swap through its uusd
+/// pair.
+///
+/// Assets without a uusd pair are valued at zero, so that they do not pay a volume based tip. Any
+/// other error while querying the factory is returned.
+///
+/// The value is taken from the current pool reserves, which the bot performing the purchase can
+/// move within the same transaction. The volume based tip is therefore bounded by the `max_tip`
+/// rather than by the price.
+fn uusd_value(
+    querier: &QuerierWrapper,
+    config: &Config,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    let uusd = AssetInfo::NativeToken {
+        denom: UUSD_DENOM.to_string(),
+    };
+
+    if asset_info == &uusd {
+        return Ok(amount);
+    }
+
+    let pair = match query_pair_info(
+        querier,
+        config.factory_addr.clone(),
+        &[asset_info.clone(), uusd],
+    ) {
+        Ok(pair) => pair,
+        Err(StdError::GenericErr { msg }) if msg.contains("not found") => {
+            return Ok(Uint128::zero())
+        }
+        Err(err) => return Err(err),
+    };
+
+    Ok(simulate(
+        querier,
+        pair.contract_addr,
+        &Asset {
+            info: asset_info.clone(),
+            amount,
+        },
+    )?
+    .return_amount)
+}
+
+/// ## Description
+/// Validates the tip parameters of the contract configuration.
+///
+/// Returns a [`ContractError`] if the `tip_volume_rate` is above the allowed maximum, or if the
+/// `max_tip` is below the minimum tip.
+pub fn validate_tip_config(config: &Config) -> Result<(), ContractError> {
+    let max_tip_volume_rate = Decimal::percent(MAX_TIP_VOLUME_PERCENT);
+    if config.tip_volume_rate > max_tip_volume_rate {
+        return Err(ContractError::TipVolumeRateTooHigh {
+            max: max_tip_volume_rate,
+        });
+    }
+
+    let min_tip = config.min_tip()?;
+    if config.max_tip < min_tip {
+        return Err(ContractError::MaxTipTooLow { min: min_tip });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates the [`TipMode`] chosen by a user.
+///
+/// Returns a [`ContractError`] if a Dutch auction would offer less than the minimum tip, or does
+/// not rise over a non-zero duration.
+pub fn validate_tip_mode(config: &Config, tip_mode: &TipMode) -> Result<(), ContractError> {
+    if let TipMode::DutchAuction {
+        start_tip,
+        max_tip,
+        duration,
+    } = tip_mode
+    {
+        let min_tip = config.min_tip()?;
+        if *start_tip < min_tip {
+            return Err(ContractError::StartTipTooLow { min: min_tip });
+        }
+
+        if max_tip < start_tip || *duration == 0 {
+            return Err(ContractError::InvalidDutchAuction {});
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{DcaInfo, TipMode};
+    use cosmwasm_std::{Addr, Decimal, OwnedDeps, QuerierWrapper, StdError, Uint128};
+
+    use super::calculate_tip;
+    use crate::{
+        mock_querier::{mock_dependencies_with_owner, setup_contract},
+        state::CONFIG,
+    };
+
+    #[test]
+    fn does_value_assets_without_uusd_pair_at_zero() {
+        let mut deps = mock_dependencies_with_owner("owner");
+        setup_contract(deps.as_mut());
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.base_tip = Uint128::zero();
+        config.tip_volume_rate = Decimal::percent(1);
+
+        let order = DcaInfo {
+            id: 1,
+            owner: Addr::unchecked("creator"),
+            initial_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::from(10_000u128),
+            },
+            target_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            interval: 60,
+            last_purchase: 0,
+            start_time: 0,
+            dca_amount: Uint128::from(1_000u128),
+            referrer: None,
+            max_hops: None,
+            max_spread: None,
+            route_preference: None,
+            paused_at: None,
+            catch_up_purchases: 0,
+        };
+
+        let tip = |deps: &OwnedDeps<_, _, _>| {
+            calculate_tip(
+                &QuerierWrapper::new(&deps.querier),
+                &config,
+                &TipMode::Fixed,
+                &order,
+                1,
+                0,
+            )
+        };
+
+        // 1% of the 1_000 uusd the dca_amount is worth, plus the fee of the single hop
+        assert_eq!(
+            tip(&deps).unwrap(),
+            config.per_hop_fee + Uint128::from(10u128)
+        );
+
+        deps.querier
+            .update_pair_error(Some("astroport::asset::PairInfo not found"));
+        assert_eq!(tip(&deps).unwrap(), config.per_hop_fee);
+
+        deps.querier.update_pair_error(Some("out of gas"));
+        assert_eq!(
+            tip(&deps).unwrap_err(),
+            StdError::generic_err("Querier contract error: out of gas")
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use astroport::{
+    asset::{addr_validate_to_lower, AssetInfo},
+    router::SwapOperation,
+};
+use astroport_dca::dca::{Config, MinDcaAmount, RoutePreference, TargetAssetFee};
+use cosmwasm_std::{Api, Decimal, Storage, Uint128};
+
+use crate::{
+    error::ContractError,
+    state::{is_whitelisted_asset, MAX_HOPS_LIMIT, MAX_PROTOCOL_FEE_BPS, MAX_SPREAD_LIMIT_PERCENT},
+    tip::validate_tip_config,
+};
+
+/// ## Description
+/// Validates a complete contract configuration before it is stored.
+///
+/// Returns a [`ContractError`] if any parameter is outside of its protocol defined bounds.
+/// ## Arguments
+/// * `config` - The [`Config`] to validate.
+pub fn validate_config(config: &Config) -> Result<(), ContractError> {
+    validate_max_hops(config.max_hops)?;
+    validate_max_spread(config.max_spread)?;
+
+    validate_protocol_fees(config)?;
+    validate_referral_share(config.referral_share)?;
+    validate_tip_config(config)?;
+
+    validate_max_orders_per_user(config.max_orders_per_user)?;
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates that `max_hops` allows at least one hop, and does not exceed [`MAX_HOPS_LIMIT`].
+pub fn validate_max_hops(max_hops: u32) -> Result<(), ContractError> {
+    if max_hops == 0 || max_hops > MAX_HOPS_LIMIT {
+        return Err(ContractError::InvalidMaxHops {
+            max: MAX_HOPS_LIMIT,
+        });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates that `max_spread` is non-zero, and does not exceed [`MAX_SPREAD_LIMIT_PERCENT`].
+pub fn validate_max_spread(max_spread: Decimal) -> Result<(), ContractError> {
+    let max = Decimal::percent(MAX_SPREAD_LIMIT_PERCENT);
+    if max_spread.is_zero() || max_spread > max {
+        return Err(ContractError::InvalidMaxSpread { max });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates that users can have at least one DCA order.
+pub fn validate_max_orders_per_user(max_orders_per_user: u32) -> Result<(), ContractError> {
+    if max_orders_per_user == 0 {
+        return Err(ContractError::InvalidMaxOrdersPerUser {});
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates a whitelist of tokens that can be used in a hop route, returning the whitelist with
+/// each CW20 address validated.
+///
+/// Returns a [`ContractError`] if the whitelist is empty, contains an invalid address or contains
+/// the same token more than once.
+/// ## Arguments
+/// * `api` - The [`Api`] used to validate CW20 addresses.
+///
+/// * `tokens` - The whitelisted tokens to validate.
+pub fn validate_whitelisted_tokens(
+    api: &dyn Api,
+    tokens: Vec<AssetInfo>,
+) -> Result<Vec<AssetInfo>, ContractError> {
+    if tokens.is_empty() {
+        return Err(ContractError::EmptyWhitelist {});
+    }
+
+    let mut whitelisted_tokens: Vec<AssetInfo> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let token = validate_asset_info(api, token)?;
+
+        if whitelisted_tokens.contains(&token) {
+            return Err(ContractError::DuplicateWhitelistedToken {
+                token: token.to_string(),
+            });
+        }
+
+        whitelisted_tokens.push(token);
+    }
+
+    Ok(whitelisted_tokens)
+}
+
+/// ## Description
+/// Validates an asset, returning a [`ContractError`] if it is a CW20 token with an invalid
+/// address.
+pub fn validate_asset_info(api: &dyn Api, token: AssetInfo) -> Result<AssetInfo, ContractError> {
+    Ok(match token {
+        AssetInfo::Token { contract_addr } => AssetInfo::Token {
+            contract_addr: addr_validate_to_lower(api, contract_addr.as_str())?,
+        },
+        native_token => native_token,
+    })
+}
+
+/// ## Description
+/// Validates the protocol fee overrides for specific target assets, returning the overrides with
+/// each CW20 address validated.
+///
+/// Returns a [`ContractError`] if an override contains an invalid address or if the same target
+/// asset is overridden more than once.
+/// ## Arguments
+/// * `api` - The [`Api`] used to validate CW20 addresses.
+///
+/// * `target_asset_fees` - The protocol fee overrides to validate.
+pub fn validate_target_asset_fees(
+    api: &dyn Api,
+    target_asset_fees: Vec<TargetAssetFee>,
+) -> Result<Vec<TargetAssetFee>, ContractError> {
+    let mut validated: Vec<TargetAssetFee> = Vec::with_capacity(target_asset_fees.len());
+    for fee in target_asset_fees {
+        let target_asset = validate_asset_info(api, fee.target_asset)?;
+
+        if validated.iter().any(|fee| fee.target_asset == target_asset) {
+            return Err(ContractError::DuplicateTargetAssetFee {
+                asset: target_asset.to_string(),
+            });
+        }
+
+        validated.push(TargetAssetFee {
+            target_asset,
+            fee_bps: fee.fee_bps,
+        });
+    }
+
+    Ok(validated)
+}
+
+/// ## Description
+/// Validates the minimum `dca_amount` of orders for specific initial assets, returning the minimums
+/// with each CW20 address validated.
+///
+/// Returns a [`ContractError`] if a minimum contains an invalid address or if the same initial
+/// asset has more than one minimum.
+/// ## Arguments
+/// * `api` - The [`Api`] used to validate CW20 addresses.
+///
+/// * `min_dca_amounts` - The minimum amounts to validate.
+pub fn validate_min_dca_amounts(
+    api: &dyn Api,
+    min_dca_amounts: Vec<MinDcaAmount>,
+) -> Result<Vec<MinDcaAmount>, ContractError> {
+    let mut validated: Vec<MinDcaAmount> = Vec::with_capacity(min_dca_amounts.len());
+    for min_dca_amount in min_dca_amounts {
+        let initial_asset = validate_asset_info(api, min_dca_amount.initial_asset)?;
+
+        if validated
+            .iter()
+            .any(|min_dca_amount| min_dca_amount.initial_asset == initial_asset)
+        {
+            return Err(ContractError::DuplicateMinDcaAmount {
+                asset: initial_asset.to_string(),
+            });
+        }
+
+        validated.push(MinDcaAmount {
+            initial_asset,
+            amount: min_dca_amount.amount,
+        });
+    }
+
+    Ok(validated)
+}
+
+/// ## Description
+/// Validates that neither the default protocol fee nor any target asset override exceeds
+/// [`MAX_PROTOCOL_FEE_BPS`].
+pub fn validate_protocol_fees(config: &Config) -> Result<(), ContractError> {
+    if std::iter::once(config.protocol_fee_bps)
+        .chain(config.target_asset_fees.iter().map(|fee| fee.fee_bps))
+        .any(|fee_bps| fee_bps > MAX_PROTOCOL_FEE_BPS)
+    {
+        return Err(ContractError::ProtocolFeeTooHigh {
+            max: MAX_PROTOCOL_FEE_BPS,
+        });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates that the referral share is at most the whole fee.
+pub fn validate_referral_share(referral_share: Decimal) -> Result<(), ContractError> {
+    if referral_share > Decimal::one() {
+        return Err(ContractError::InvalidReferralShare {});
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates that a new or modified DCA order satisfies the minimum interval and the minimum
+/// `dca_amount` of its initial asset.
+/// ## Arguments
+/// * `config` - The [`Config`] containing the order limits.
+///
+/// * `initial_asset` - The [`AssetInfo`] being spent by the order.
+///
+/// * `interval` - The time in seconds between DCA purchases of the order.
+///
+/// * `dca_amount` - The [`Uint128`] amount of `initial_asset` spent each DCA purchase.
+pub fn validate_order_limits(
+    config: &Config,
+    initial_asset: &AssetInfo,
+    interval: u64,
+    dca_amount: Uint128,
+) -> Result<(), ContractError> {
+    if interval < config.min_interval {
+        return Err(ContractError::IntervalTooShort {
+            min: config.min_interval,
+        });
+    }
+
+    let min_dca_amount = config.min_dca_amount_for(initial_asset);
+    if dca_amount < min_dca_amount {
+        return Err(ContractError::DcaAmountTooSmall {
+            min: min_dca_amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates the route preference of a DCA order. A pinned route must start at `initial_asset`,
+/// end at `target_asset`, have each hop start at the asset the previous hop ended at and not
+/// exceed [`MAX_HOPS_LIMIT`] hops.
+/// ## Arguments
+/// * `route_preference` - The [`RoutePreference`] of the order.
+///
+/// * `initial_asset` - The [`AssetInfo`] being spent by the order.
+///
+/// * `target_asset` - The [`AssetInfo`] being purchased by the order.
+pub fn validate_route_preference(
+    route_preference: &RoutePreference,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+) -> Result<(), ContractError> {
+    if let RoutePreference::PinnedRoute { hops } = route_preference {
+        let (first_hop, last_hop) = match (hops.first(), hops.last()) {
+            (Some(first_hop), Some(last_hop)) => (first_hop, last_hop),
+            _ => return Err(ContractError::InvalidPinnedRoute {}),
+        };
+
+        if hops.len() > MAX_HOPS_LIMIT as usize
+            || &offer_asset_info(first_hop) != initial_asset
+            || &last_hop.get_target_asset_info() != target_asset
+            || hops
+                .windows(2)
+                .any(|pair| pair[0].get_target_asset_info() != offer_asset_info(&pair[1]))
+        {
+            return Err(ContractError::InvalidPinnedRoute {});
+        }
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates a hop route which is not restricted by a DCA order, as used by vault purchases. The
+/// route must start at `initial_asset`, end at `target_asset`, not exceed the contract `max_hops`
+/// and only swap through whitelisted tokens.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `config` - The [`Config`] of the contract.
+///
+/// * `initial_asset` - The [`AssetInfo`] being swapped.
+///
+/// * `target_asset` - The [`AssetInfo`] being purchased.
+///
+/// * `hops` - The hop route of the swap.
+pub fn validate_hop_route(
+    storage: &dyn Storage,
+    config: &Config,
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    hops: &[SwapOperation],
+) -> Result<(), ContractError> {
+    validate_route_assets(initial_asset, target_asset, hops)?;
+    validate_route_for_order(storage, None, config.max_hops, hops)
+}
+
+/// ## Description
+/// Validates that a hop route starts at `initial_asset` and ends at `target_asset`.
+/// ## Arguments
+/// * `initial_asset` - The [`AssetInfo`] being swapped.
+///
+/// * `target_asset` - The [`AssetInfo`] being purchased.
+///
+/// * `hops` - The hop route of the swap.
+pub fn validate_route_assets(
+    initial_asset: &AssetInfo,
+    target_asset: &AssetInfo,
+    hops: &[SwapOperation],
+) -> Result<(), ContractError> {
+    let (first_hop, last_hop) = match (hops.first(), hops.last()) {
+        (Some(first_hop), Some(last_hop)) => (first_hop, last_hop),
+        _ => return Err(ContractError::EmptyHopRoute {}),
+    };
+
+    if &offer_asset_info(first_hop) != initial_asset {
+        return Err(ContractError::InitialAssetAssertion {});
+    }
+
+    if &last_hop.get_target_asset_info() != target_asset {
+        return Err(ContractError::TargetAssetAssertion {});
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Validates a hop route against the limits of a DCA order swapped through it. The route must not
+/// exceed the effective `max_hops` of the order. A pinned route must be followed exactly and is not
+/// subject to the whitelist, otherwise the route may only swap through whitelisted tokens, which
+/// are further restricted by the allowed intermediates of the order.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `route_preference` - The [`RoutePreference`] of the order, if any.
+///
+/// * `max_hops` - The maximum amount of hops of the order, user or contract, in that order.
+///
+/// * `hops` - The [`SwapOperation`]s of the route, which must not be empty.
+pub fn validate_route_for_order(
+    storage: &dyn Storage,
+    route_preference: Option<&RoutePreference>,
+    max_hops: u32,
+    hops: &[SwapOperation],
+) -> Result<(), ContractError> {
+    if hops.is_empty() {
+        return Err(ContractError::EmptyHopRoute {});
+    }
+
+    let hops_len = hops.len() as u32;
+    if hops_len > max_hops {
+        return Err(ContractError::MaxHopsAssertion { hops: hops_len });
+    }
+
+    if let Some(RoutePreference::PinnedRoute { hops: pinned_hops }) = route_preference {
+        if hops != pinned_hops.as_slice() {
+            return Err(ContractError::PinnedRouteMismatch {});
+        }
+
+        return Ok(());
+    }
+
+    // validate that all middle hops (last hop excluded) are whitelisted tokens, and that the order
+    // allows swapping through them
+    for swap in &hops[..hops.len() - 1] {
+        let ask_asset = swap.get_target_asset_info();
+        if !is_whitelisted_asset(storage, &ask_asset) {
+            return Err(ContractError::InvalidHopRoute {
+                token: ask_asset.to_string(),
+            });
+        }
+
+        if let Some(RoutePreference::AllowedIntermediates { tokens }) = route_preference {
+            if !tokens.contains(&ask_asset) {
+                return Err(ContractError::IntermediateNotAllowed {
+                    token: ask_asset.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Returns the [`AssetInfo`] offered in a swap operation.
+fn offer_asset_info(operation: &SwapOperation) -> AssetInfo {
+    match operation {
+        SwapOperation::NativeSwap { offer_denom, .. } => AssetInfo::NativeToken {
+            denom: offer_denom.clone(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info, ..
+        } => offer_asset_info.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{asset::AssetInfo, router::SwapOperation};
+    use astroport_dca::dca::{MinDcaAmount, RoutePreference, TargetAssetFee};
+    use cosmwasm_std::{testing::mock_dependencies, Addr, Decimal, Uint128};
+
+    use super::{
+        validate_max_hops, validate_max_spread, validate_min_dca_amounts,
+        validate_route_preference, validate_target_asset_fees, validate_whitelisted_tokens,
+    };
+    use crate::{
+        error::ContractError,
+        state::{MAX_HOPS_LIMIT, MAX_SPREAD_LIMIT_PERCENT},
+    };
+
+    #[test]
+    fn does_bound_max_hops() {
+        assert!(validate_max_hops(1).is_ok());
+        assert!(validate_max_hops(MAX_HOPS_LIMIT).is_ok());
+
+        for max_hops in [0, MAX_HOPS_LIMIT + 1] {
+            assert_eq!(
+                validate_max_hops(max_hops).unwrap_err(),
+                ContractError::InvalidMaxHops {
+                    max: MAX_HOPS_LIMIT
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn does_bound_max_spread() {
+        assert!(validate_max_spread(Decimal::percent(MAX_SPREAD_LIMIT_PERCENT)).is_ok());
+
+        for max_spread in [Decimal::zero(), Decimal::percent(150)] {
+            assert_eq!(
+                validate_max_spread(max_spread).unwrap_err(),
+                ContractError::InvalidMaxSpread {
+                    max: Decimal::percent(MAX_SPREAD_LIMIT_PERCENT)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn does_validate_whitelist() {
+        let deps = mock_dependencies(&[]);
+
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+
+        assert_eq!(
+            validate_whitelisted_tokens(&deps.api, vec![uusd.clone(), token.clone()]).unwrap(),
+            vec![uusd.clone(), token]
+        );
+
+        // does validate cw20 addresses
+        assert!(validate_whitelisted_tokens(
+            &deps.api,
+            vec![AssetInfo::Token {
+                contract_addr: Addr::unchecked("Token"),
+            }]
+        )
+        .is_err());
+
+        assert_eq!(
+            validate_whitelisted_tokens(&deps.api, vec![]).unwrap_err(),
+            ContractError::EmptyWhitelist {}
+        );
+
+        assert_eq!(
+            validate_whitelisted_tokens(&deps.api, vec![uusd.clone(), uusd]).unwrap_err(),
+            ContractError::DuplicateWhitelistedToken {
+                token: "uusd".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_validate_target_asset_fees() {
+        let deps = mock_dependencies(&[]);
+
+        let fee = |contract_addr: &str, fee_bps| TargetAssetFee {
+            target_asset: AssetInfo::Token {
+                contract_addr: Addr::unchecked(contract_addr),
+            },
+            fee_bps,
+        };
+
+        assert_eq!(
+            validate_target_asset_fees(&deps.api, vec![fee("token", 10), fee("other", 20)])
+                .unwrap(),
+            vec![fee("token", 10), fee("other", 20)]
+        );
+
+        // does validate cw20 addresses
+        assert!(validate_target_asset_fees(&deps.api, vec![fee("Token", 10)]).is_err());
+
+        assert_eq!(
+            validate_target_asset_fees(&deps.api, vec![fee("token", 10), fee("token", 20)])
+                .unwrap_err(),
+            ContractError::DuplicateTargetAssetFee {
+                asset: "token".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_validate_min_dca_amounts() {
+        let deps = mock_dependencies(&[]);
+
+        let min = |contract_addr: &str, amount: u128| MinDcaAmount {
+            initial_asset: AssetInfo::Token {
+                contract_addr: Addr::unchecked(contract_addr),
+            },
+            amount: Uint128::from(amount),
+        };
+
+        assert_eq!(
+            validate_min_dca_amounts(&deps.api, vec![min("token", 10), min("other", 20)]).unwrap(),
+            vec![min("token", 10), min("other", 20)]
+        );
+
+        // does validate cw20 addresses
+        assert!(validate_min_dca_amounts(&deps.api, vec![min("Token", 10)]).is_err());
+
+        assert_eq!(
+            validate_min_dca_amounts(&deps.api, vec![min("token", 10), min("token", 20)])
+                .unwrap_err(),
+            ContractError::DuplicateMinDcaAmount {
+                asset: "token".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn does_validate_pinned_route() {
+        let native = |denom: &str| AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        };
+        let hop = |offer: &str, ask: &str| SwapOperation::NativeSwap {
+            offer_denom: offer.to_string(),
+            ask_denom: ask.to_string(),
+        };
+        let pinned = |hops| RoutePreference::PinnedRoute { hops };
+
+        let (uusd, uluna) = (native("uusd"), native("uluna"));
+
+        assert!(validate_route_preference(
+            &pinned(vec![hop("uusd", "ukrw"), hop("ukrw", "uluna")]),
+            &uusd,
+            &uluna
+        )
+        .is_ok());
+
+        for hops in [
+            vec![],
+            vec![hop("ukrw", "uluna")],
+            vec![hop("uusd", "ukrw")],
+            vec![hop("uusd", "ukrw"), hop("umnt", "uluna")],
+        ] {
+            assert_eq!(
+                validate_route_preference(&pinned(hops), &uusd, &uluna).unwrap_err(),
+                ContractError::InvalidPinnedRoute {}
+            );
+        }
+    }
+}
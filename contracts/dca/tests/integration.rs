@@ -0,0 +1,412 @@
+//! Integration tests running the DCA contract against mock Astroport factory, router and pair
+//! contracts and cw20-base tokens in cw-multi-test, exercising the swaps and token transfers
+//! performed by the messages the contract returns.
+
+mod mocks;
+mod suite;
+
+use astroport::asset::Asset;
+use astroport_dca::dca::{Config, ExecuteMsg, OrderPerformanceResponse, QueryMsg};
+use astroport_dca_module::error::ContractError;
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use cw20::Cw20ExecuteMsg;
+use cw_multi_test::Executor;
+
+use suite::{native, swap, token, Suite, BOT, OWNER, TREASURY, USER};
+
+fn update_max_hops(max_hops: u32) -> ExecuteMsg {
+    ExecuteMsg::UpdateConfig {
+        max_hops: Some(max_hops),
+        per_hop_fee: None,
+        base_tip: None,
+        tip_volume_rate: None,
+        max_tip: None,
+        whitelisted_tokens: None,
+        max_spread: None,
+        fee_collector: None,
+        protocol_fee_bps: None,
+        target_asset_fees: None,
+        fee_exempt_addresses: None,
+        referral_share: None,
+        referral_source: None,
+        bot_priority_window: None,
+        min_bot_bond: None,
+        bot_unbonding_period: None,
+        bot_whitelist_enabled: None,
+        min_interval: None,
+        min_dca_amounts: None,
+        max_orders_per_user: None,
+    }
+}
+
+#[test]
+fn native_order_lifecycle() {
+    let mut suite = Suite::new();
+    let uusd = native("uusd");
+    let uluna = native("uluna");
+
+    suite
+        .create_order(
+            Asset {
+                info: uusd.clone(),
+                amount: Uint128::from(3_000u128),
+            },
+            uluna.clone(),
+            &coins(3_000, "uusd"),
+        )
+        .unwrap();
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+    assert_eq!(
+        suite.balance(suite.dca.as_str(), "uusd"),
+        Uint128::from(4_000u128)
+    );
+
+    // 10 uusd is taken as the protocol fee, and the remaining 990 uusd is swapped for 1_980 uluna
+    suite.purchase(vec![swap(&uusd, &uluna)]).unwrap();
+    assert_eq!(suite.balance(USER, "uluna"), Uint128::from(1_980u128));
+    assert_eq!(suite.balance(TREASURY, "uusd"), Uint128::from(10u128));
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::from(100u128));
+
+    let order = suite.orders().remove(0).info;
+    assert_eq!(order.initial_asset.amount, Uint128::from(2_000u128));
+    assert_eq!(order.last_purchase, suite.app.block_info().time.seconds());
+
+    // the next purchase can only be performed once the interval has passed
+    let err = suite.purchase(vec![swap(&uusd, &uluna)]).unwrap_err();
+    assert_eq!(err, ContractError::PurchaseTooEarly {}.to_string());
+
+    suite.advance_time(100);
+    suite.purchase(vec![swap(&uusd, &uluna)]).unwrap();
+    assert_eq!(suite.balance(USER, "uluna"), Uint128::from(3_960u128));
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::from(200u128));
+
+    // the proceeds sent to the user are measured, and valued back at the price of the pair
+    let performance: OrderPerformanceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.dca,
+            &QueryMsg::OrderPerformance {
+                token_id: order.id.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(performance.spent.amount, Uint128::from(1_980u128));
+    assert_eq!(performance.received.amount, Uint128::from(3_960u128));
+    assert_eq!(performance.average_price, Some(Decimal::percent(50)));
+    assert_eq!(performance.current_value, Uint128::from(1_980u128));
+    assert!(performance.unrealized_profit.is_zero() && performance.unrealized_loss.is_zero());
+
+    // cancelling returns the unspent uusd, while the unused tip stays deposited
+    let before = suite.balance(USER, "uusd");
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::CancelDcaOrder {
+                initial_asset: uusd,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        suite.balance(USER, "uusd"),
+        before + Uint128::from(1_000u128)
+    );
+    assert!(suite.orders().is_empty());
+    assert_eq!(suite.user_config().tip_balance, Uint128::from(800u128));
+    assert_eq!(
+        suite.balance(suite.dca.as_str(), "uusd"),
+        Uint128::from(800u128)
+    );
+}
+
+#[test]
+fn cw20_order_lifecycle() {
+    let mut suite = Suite::new();
+    let astro = token(&suite.astro);
+    let uusd = native("uusd");
+    let uluna = native("uluna");
+
+    suite.increase_allowance(2_000);
+    suite
+        .create_order(
+            Asset {
+                info: astro.clone(),
+                amount: Uint128::from(2_000u128),
+            },
+            uusd.clone(),
+            &[],
+        )
+        .unwrap();
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+
+    // the tokens stay with the user until each purchase pulls dca_amount through the allowance
+    assert_eq!(suite.astro_balance(USER), Uint128::from(10_000u128));
+
+    // 10 ASTRO is taken as the protocol fee, and the remaining 990 ASTRO is swapped through uluna
+    // for 3_960 uluna and then 1_980 uusd
+    let before = suite.balance(USER, "uusd");
+    suite
+        .purchase(vec![swap(&astro, &uluna), swap(&uluna, &uusd)])
+        .unwrap();
+    assert_eq!(
+        suite.balance(USER, "uusd"),
+        before + Uint128::from(1_980u128)
+    );
+    assert_eq!(suite.astro_balance(USER), Uint128::from(9_000u128));
+    assert_eq!(suite.astro_balance(TREASURY), Uint128::from(10u128));
+    assert_eq!(suite.astro_allowance(), Uint128::from(1_000u128));
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::from(200u128));
+
+    // cancelling only removes the order, as the remaining tokens never left the user
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::CancelDcaOrder {
+                initial_asset: astro,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(suite.orders().is_empty());
+    assert_eq!(suite.astro_balance(USER), Uint128::from(9_000u128));
+}
+
+#[test]
+fn rejects_invalid_purchases() {
+    let mut suite = Suite::new();
+    let astro = token(&suite.astro);
+    let uusd = native("uusd");
+    let uluna = native("uluna");
+
+    suite
+        .create_order(
+            Asset {
+                info: uusd.clone(),
+                amount: Uint128::from(3_000u128),
+            },
+            uluna.clone(),
+            &coins(3_000, "uusd"),
+        )
+        .unwrap();
+
+    // the bot cannot be paid without a tip deposit
+    let err = suite.purchase(vec![swap(&uusd, &uluna)]).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientTipBalance {}.to_string());
+
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+
+    // routes must stay within the maximum hops, only swap through whitelisted tokens, and end
+    // in the target asset
+    let err = suite
+        .purchase(vec![
+            swap(&uusd, &uluna),
+            swap(&uluna, &uusd),
+            swap(&uusd, &uluna),
+            swap(&uluna, &uusd),
+        ])
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxHopsAssertion { hops: 4 }.to_string());
+
+    let err = suite
+        .purchase(vec![swap(&uusd, &astro), swap(&astro, &uluna)])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidHopRoute {
+            token: astro.to_string()
+        }
+        .to_string()
+    );
+
+    let err = suite
+        .purchase(vec![swap(&uusd, &uluna), swap(&uluna, &astro)])
+        .unwrap_err();
+    assert_eq!(err, ContractError::TargetAssetAssertion {}.to_string());
+
+    // a route through a whitelisted token without a pair fails in the router, reverting the
+    // whole purchase
+    suite
+        .execute(
+            OWNER,
+            ExecuteMsg::AddWhitelistedTokens {
+                tokens: vec![astro.clone()],
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .purchase(vec![swap(&uusd, &astro), swap(&astro, &uluna)])
+        .unwrap_err();
+
+    assert_eq!(suite.balance(USER, "uluna"), Uint128::zero());
+    assert_eq!(suite.orders()[0].info.last_purchase, 0);
+    assert_eq!(suite.user_config().tip_balance, Uint128::from(1_000u128));
+}
+
+#[test]
+fn rejects_unfunded_cw20_orders() {
+    let mut suite = Suite::new();
+    let astro = token(&suite.astro);
+    let uusd = native("uusd");
+    let uluna = native("uluna");
+
+    // the allowance must match the deposit of the order
+    let err = suite
+        .create_order(
+            Asset {
+                info: astro.clone(),
+                amount: Uint128::from(2_000u128),
+            },
+            uusd.clone(),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTokenDeposit {}.to_string());
+
+    suite.increase_allowance(2_000);
+    suite
+        .create_order(
+            Asset {
+                info: astro.clone(),
+                amount: Uint128::from(2_000u128),
+            },
+            uusd.clone(),
+            &[],
+        )
+        .unwrap();
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+
+    // once the user revokes the allowance, the token transfer of the purchase fails and the
+    // whole purchase is reverted
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.astro.clone(),
+            &Cw20ExecuteMsg::DecreaseAllowance {
+                spender: suite.dca.to_string(),
+                amount: Uint128::from(2_000u128),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    suite
+        .purchase(vec![swap(&astro, &uluna), swap(&uluna, &uusd)])
+        .unwrap_err();
+
+    let order = suite.orders().remove(0).info;
+    assert_eq!(order.initial_asset.amount, Uint128::from(2_000u128));
+    assert_eq!(order.last_purchase, 0);
+    assert_eq!(suite.user_config().tip_balance, Uint128::from(1_000u128));
+    assert_eq!(suite.astro_balance(USER), Uint128::from(10_000u128));
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::zero());
+}
+
+#[test]
+fn update_config_follows_factory_owner() {
+    let mut suite = Suite::new();
+
+    let err = suite.execute(USER, update_max_hops(2), &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    suite.execute(OWNER, update_max_hops(2), &[]).unwrap();
+
+    let config: Config = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.dca, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.max_hops, 2);
+
+    // transferring the ownership of the factory transfers the ownership of the DCA contract
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            suite.factory.clone(),
+            &mocks::factory::ExecuteMsg::UpdateOwner {
+                owner: "new_owner".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = suite.execute(OWNER, update_max_hops(3), &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    suite.execute("new_owner", update_max_hops(3), &[]).unwrap();
+}
+
+#[test]
+fn vault_purchase_swaps_through_router() {
+    let mut suite = Suite::new();
+    let uusd = native("uusd");
+    let uluna = native("uluna");
+
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::DepositVault {
+                initial_asset: Asset {
+                    info: uusd.clone(),
+                    amount: Uint128::from(2_000u128),
+                },
+                target_asset: uluna.clone(),
+                interval: 100,
+                dca_amount: Uint128::from(1_000u128),
+                max_spread: None,
+            },
+            &coins(2_000, "uusd"),
+        )
+        .unwrap();
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::AddVaultTip { vault_id: 1 },
+            &coins(1_000, "uusd"),
+        )
+        .unwrap();
+
+    // 10 uusd is taken as the protocol fee, and the 1_980 uluna bought with the remaining 990 uusd
+    // is measured in the reply and credited to the vault
+    suite
+        .execute(
+            BOT,
+            ExecuteMsg::PerformVaultPurchase {
+                vault_id: 1,
+                hops: vec![swap(&uusd, &uluna)],
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::from(100u128));
+
+    suite
+        .execute(USER, ExecuteMsg::ClaimVaultProceeds { vault_id: 1 }, &[])
+        .unwrap();
+    assert_eq!(suite.balance(USER, "uluna"), Uint128::from(1_980u128));
+    assert_eq!(suite.balance(suite.dca.as_str(), "uluna"), Uint128::zero());
+
+    // withdrawing returns the deposit that was not spent yet, along with the unused tip
+    let before = suite.balance(USER, "uusd");
+    suite
+        .execute(USER, ExecuteMsg::WithdrawVault { vault_id: 1 }, &[])
+        .unwrap();
+    assert_eq!(
+        suite.balance(USER, "uusd"),
+        before + Uint128::from(1_900u128)
+    );
+}
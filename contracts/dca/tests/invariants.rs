@@ -0,0 +1,373 @@
+//! Property-based tests running random sequences of messages against the DCA contract in
+//! cw-multi-test, asserting the accounting invariants of the contract after each step.
+
+mod mocks;
+mod suite;
+
+use std::collections::HashMap;
+
+use astroport::asset::Asset;
+use astroport_dca::dca::{DcaInfo, ExecuteMsg, QueryMsg, StatsResponse, UserConfig};
+use cosmwasm_std::{coin, coins, Addr, Coin, Uint128};
+use proptest::{collection::vec, prelude::*};
+
+use suite::{native, swap, Suite, BOT, USER};
+
+/// The native assets that orders are created with, each purchasing the other
+const DENOMS: [&str; 2] = ["uusd", "uluna"];
+
+#[derive(Clone, Debug)]
+enum Action {
+    Create {
+        denom: usize,
+        purchases: u128,
+        dca_amount: u128,
+    },
+    Modify {
+        denom: usize,
+        new_denom: usize,
+        purchases: u128,
+        dca_amount: u128,
+        interval: u64,
+        reset: bool,
+    },
+    Cancel {
+        denom: usize,
+    },
+    AddTip {
+        amount: u128,
+    },
+    Withdraw {
+        amount: u128,
+    },
+    Purchase {
+        denom: usize,
+    },
+    AdvanceTime {
+        seconds: u64,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (0..2usize, 1..5u128, 1..500u128).prop_map(|(denom, purchases, dca_amount)| {
+            Action::Create {
+                denom,
+                purchases,
+                dca_amount,
+            }
+        }),
+        (
+            0..2usize,
+            0..2usize,
+            1..5u128,
+            1..500u128,
+            50..200u64,
+            any::<bool>()
+        )
+            .prop_map(
+                |(denom, new_denom, purchases, dca_amount, interval, reset)| Action::Modify {
+                    denom,
+                    new_denom,
+                    purchases,
+                    dca_amount,
+                    interval,
+                    reset,
+                }
+            ),
+        (0..2usize).prop_map(|denom| Action::Cancel { denom }),
+        (1..1_000u128).prop_map(|amount| Action::AddTip { amount }),
+        (0..1_000u128).prop_map(|amount| Action::Withdraw { amount }),
+        (0..2usize).prop_map(|denom| Action::Purchase { denom }),
+        (0..250u64).prop_map(|seconds| Action::AdvanceTime { seconds }),
+    ]
+}
+
+/// The balances and contract state that the invariants are checked against
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    /// The native balances held by the DCA contract
+    escrow: Vec<Uint128>,
+    /// The native balances of the user
+    user: Vec<Uint128>,
+    /// The orders of the user
+    orders: Vec<DcaInfo>,
+    /// The tip balance of the user
+    tip_balance: Uint128,
+}
+
+impl Snapshot {
+    fn take(suite: &Suite) -> Self {
+        let tip_balance = suite
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &suite.dca,
+                &QueryMsg::UserConfig {
+                    user: USER.to_string(),
+                },
+            )
+            .map(|config: UserConfig| config.tip_balance)
+            .unwrap_or_default();
+
+        Snapshot {
+            escrow: DENOMS
+                .iter()
+                .map(|denom| suite.balance(suite.dca.as_str(), denom))
+                .collect(),
+            user: DENOMS
+                .iter()
+                .map(|denom| suite.balance(USER, denom))
+                .collect(),
+            orders: suite.orders().into_iter().map(|order| order.info).collect(),
+            tip_balance,
+        }
+    }
+
+    /// Returns the first order of the user spending `denom`, which is the order that messages
+    /// referring to `denom` act on.
+    fn order(&self, denom: &str) -> Option<&DcaInfo> {
+        self.orders
+            .iter()
+            .find(|order| order.initial_asset.info == native(denom))
+    }
+
+    /// Asserts that the contract holds exactly the native deposits of the orders and the tip
+    /// balance that it owes to the user.
+    fn assert_escrow(&self) {
+        for (denom, escrow) in DENOMS.iter().zip(&self.escrow) {
+            let deposits = self
+                .orders
+                .iter()
+                .filter(|order| order.initial_asset.info == native(denom))
+                .map(|order| order.initial_asset.amount)
+                .sum::<Uint128>();
+            let tips = if *denom == "uusd" {
+                self.tip_balance
+            } else {
+                Uint128::zero()
+            };
+
+            assert_eq!(*escrow, deposits + tips, "{} escrow", denom);
+        }
+    }
+}
+
+fn execute(suite: &mut Suite, action: &Action) -> Result<(), String> {
+    let (msg, funds): (ExecuteMsg, Vec<Coin>) = match action.clone() {
+        Action::Create {
+            denom,
+            purchases,
+            dca_amount,
+        } => (
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    info: native(DENOMS[denom]),
+                    amount: Uint128::from(purchases * dca_amount),
+                },
+                target_asset: native(DENOMS[1 - denom]),
+                interval: 100,
+                dca_amount: Uint128::from(dca_amount),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+            coins(purchases * dca_amount, DENOMS[denom]),
+        ),
+        Action::Modify {
+            denom,
+            new_denom,
+            purchases,
+            dca_amount,
+            interval,
+            reset,
+        } => {
+            let amount = purchases * dca_amount;
+
+            // only the increase of the deposit needs to be sent when keeping the same asset
+            let funds = match Snapshot::take(suite).order(DENOMS[denom]) {
+                Some(order) if denom == new_denom => {
+                    let current = order.initial_asset.amount.u128();
+                    if amount > current {
+                        coins(amount - current, DENOMS[denom])
+                    } else {
+                        vec![]
+                    }
+                }
+                _ => coins(amount, DENOMS[new_denom]),
+            };
+
+            (
+                ExecuteMsg::ModifyDcaOrder {
+                    old_initial_asset: native(DENOMS[denom]),
+                    new_initial_asset: Asset {
+                        info: native(DENOMS[new_denom]),
+                        amount: Uint128::from(amount),
+                    },
+                    new_target_asset: native(DENOMS[1 - new_denom]),
+                    new_interval: interval,
+                    new_dca_amount: Uint128::from(dca_amount),
+                    should_reset_purchase_time: reset,
+                    new_max_hops: None,
+                    new_max_spread: None,
+                    new_route_preference: None,
+                    owner: None,
+                },
+                funds,
+            )
+        }
+        Action::Cancel { denom } => (
+            ExecuteMsg::CancelDcaOrder {
+                initial_asset: native(DENOMS[denom]),
+                owner: None,
+            },
+            vec![],
+        ),
+        Action::AddTip { amount } => (ExecuteMsg::AddBotTip {}, coins(amount, "uusd")),
+        Action::Withdraw { amount } => (
+            ExecuteMsg::Withdraw {
+                tip: Uint128::from(amount),
+                owner: None,
+            },
+            vec![],
+        ),
+        Action::Purchase { denom } => {
+            return suite
+                .purchase(vec![swap(
+                    &native(DENOMS[denom]),
+                    &native(DENOMS[1 - denom]),
+                )])
+                .map(|_| ())
+        }
+        Action::AdvanceTime { seconds } => {
+            suite.advance_time(seconds);
+            return Ok(());
+        }
+    };
+
+    suite.execute(USER, msg, &funds).map(|_| ())
+}
+
+fn check_invariants(actions: Vec<Action>) {
+    let mut suite = Suite::new();
+    suite
+        .app
+        .init_bank_balance(
+            &Addr::unchecked(USER),
+            vec![coin(1_000_000, "uusd"), coin(1_000_000, "uluna")],
+        )
+        .unwrap();
+
+    // the last purchase time of each order, cleared when the order resets its purchase time
+    let mut last_purchases: HashMap<u64, u64> = HashMap::new();
+
+    for action in &actions {
+        let before = Snapshot::take(&suite);
+        let result = execute(&mut suite, action);
+        let after = Snapshot::take(&suite);
+        let now = suite.app.block_info().time.seconds();
+
+        // failed messages are reverted entirely
+        if result.is_err() {
+            assert_eq!(before, after, "{:?} failed with {:?}", action, result);
+        }
+
+        // the contract holds exactly what it owes
+        after.assert_escrow();
+
+        // a withdrawal can never exceed the tip balance
+        if let Action::Withdraw { amount } = action {
+            if Uint128::from(*amount) > before.tip_balance {
+                assert!(result.is_err(), "withdrew more than the tip balance");
+            }
+        }
+
+        match (action, &result) {
+            (Action::Purchase { denom }, Ok(())) => {
+                let order = before.order(DENOMS[*denom]).unwrap();
+
+                // an order is purchased at most once per interval
+                if let Some(last_purchase) = last_purchases.get(&order.id) {
+                    assert!(
+                        now >= last_purchase + order.interval,
+                        "order {} was purchased twice within its interval",
+                        order.id
+                    );
+                }
+                last_purchases.insert(order.id, now);
+
+                // and cannot be purchased again straight away
+                let retry = execute(&mut suite, action);
+                assert!(retry.is_err(), "order {} was purchased twice", order.id);
+                assert_eq!(after, Snapshot::take(&suite));
+            }
+            (
+                Action::Modify {
+                    denom, reset: true, ..
+                },
+                Ok(()),
+            ) => {
+                let order = before.order(DENOMS[*denom]).unwrap();
+                last_purchases.remove(&order.id);
+            }
+            _ => {}
+        }
+    }
+
+    // the bot is only ever paid out of tips that were deposited
+    let tips_paid = suite.balance(BOT, "uusd");
+    let tips_deposited = actions
+        .iter()
+        .map(|action| match action {
+            Action::AddTip { amount } => *amount,
+            _ => 0,
+        })
+        .sum::<u128>();
+    assert!(tips_paid.u128() <= tips_deposited);
+
+    // the statistics of the user count each active order and every tip paid to the bot
+    let stats: StatsResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.dca,
+            &QueryMsg::UserStats {
+                user: USER.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(stats.active_orders, suite.orders().len() as u64);
+    assert_eq!(stats.tips_paid, tips_paid);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn preserves_accounting_invariants(actions in vec(action(), 1..40)) {
+        check_invariants(actions);
+    }
+}
+
+#[test]
+fn refunds_old_asset_when_modifying_to_new_asset() {
+    // a sequence found by the invariants above, where the refund of a modified order was never
+    // sent, and was computed in the new asset instead of the old one
+    check_invariants(vec![
+        Action::Create {
+            denom: 0,
+            purchases: 2,
+            dca_amount: 100,
+        },
+        Action::Modify {
+            denom: 0,
+            new_denom: 1,
+            purchases: 1,
+            dca_amount: 50,
+            interval: 100,
+            reset: false,
+        },
+    ]);
+}
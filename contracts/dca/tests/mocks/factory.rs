@@ -0,0 +1,88 @@
+use astroport::{
+    asset::PairInfo,
+    factory::{ConfigResponse, QueryMsg},
+    pair::QueryMsg as PairQueryMsg,
+};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Describes the parameters used for creating a mock factory
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The owner of the factory, who is also the owner of the DCA contract
+    pub owner: String,
+}
+
+/// Describes the execute messages available in the mock factory
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Registers the existing pair contract `pair_addr` so that it can be found by its assets
+    RegisterPair { pair_addr: String },
+    /// Transfers the ownership of the factory to `owner`
+    UpdateOwner { owner: String },
+}
+
+const OWNER: Item<Addr> = Item::new("owner");
+const PAIRS: Map<(&[u8], &[u8]), PairInfo> = Map::new("pairs");
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    OWNER.save(deps.storage, &deps.api.addr_validate(&msg.owner)?)?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    match msg {
+        ExecuteMsg::RegisterPair { pair_addr } => {
+            let pair: PairInfo = deps
+                .querier
+                .query_wasm_smart(&pair_addr, &PairQueryMsg::Pair {})?;
+
+            // pairs can be looked up with their assets in either order
+            let [first, second] = &pair.asset_infos;
+            PAIRS.save(deps.storage, (first.as_bytes(), second.as_bytes()), &pair)?;
+            PAIRS.save(deps.storage, (second.as_bytes(), first.as_bytes()), &pair)?;
+        }
+        ExecuteMsg::UpdateOwner { owner } => {
+            OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+        }
+    }
+
+    Ok(Response::new())
+}
+
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            owner: OWNER.load(deps.storage)?,
+            pair_configs: vec![],
+            token_code_id: 0,
+            fee_address: None,
+            generator_address: None,
+        }),
+        QueryMsg::Pair { asset_infos } => {
+            let [first, second] = &asset_infos;
+            to_binary(&PAIRS.load(deps.storage, (first.as_bytes(), second.as_bytes()))?)
+        }
+        _ => Err(StdError::generic_err("Unsupported query")),
+    }
+}
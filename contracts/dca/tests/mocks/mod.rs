@@ -0,0 +1,57 @@
+//! Minimal Astroport factory, router and pair contracts used to run the DCA contract against in
+//! cw-multi-test.
+//!
+//! The pairs swap at the fixed price given by their reserves, so that the proceeds of a DCA
+//! purchase can be asserted exactly.
+
+use astroport::asset::{Asset, AssetInfo, PairInfo};
+use cosmwasm_std::{to_binary, BankMsg, Coin, CosmosMsg, StdError, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+pub mod factory;
+pub mod pair;
+pub mod router;
+
+/// ## Description
+/// Returns a message sending `asset` from the contract to `recipient`.
+fn send_asset(asset: &Asset, recipient: &str) -> StdResult<CosmosMsg> {
+    Ok(match &asset.info {
+        AssetInfo::NativeToken { denom } => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                amount: asset.amount,
+                denom: denom.clone(),
+            }],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: asset.amount,
+            })?,
+        }
+        .into(),
+    })
+}
+
+/// ## Description
+/// Returns the amount of the other asset of `pair` received for `offer_asset` at the fixed price
+/// given by `reserves`.
+fn swap_return(pair: &PairInfo, reserves: &[Uint128; 2], offer_asset: &Asset) -> StdResult<Asset> {
+    let (offer_reserve, ask_reserve, ask_info) = if offer_asset.info == pair.asset_infos[0] {
+        (reserves[0], reserves[1], &pair.asset_infos[1])
+    } else if offer_asset.info == pair.asset_infos[1] {
+        (reserves[1], reserves[0], &pair.asset_infos[0])
+    } else {
+        return Err(StdError::generic_err("Asset is not part of the pair"));
+    };
+
+    Ok(Asset {
+        info: ask_info.clone(),
+        amount: offer_asset
+            .amount
+            .multiply_ratio(ask_reserve, offer_reserve),
+    })
+}
@@ -0,0 +1,109 @@
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::PairType,
+    pair::{Cw20HookMsg, ExecuteMsg, QueryMsg, SimulationResponse},
+};
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{send_asset, swap_return};
+
+/// Describes the parameters used for creating a mock pair
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The assets traded by the pair
+    pub asset_infos: [AssetInfo; 2],
+    /// The reserves of `asset_infos` which set the fixed price of the pair
+    pub reserves: [Uint128; 2],
+}
+
+const PAIR_INFO: Item<PairInfo> = Item::new("pair_info");
+const RESERVES: Item<[Uint128; 2]> = Item::new("reserves");
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    PAIR_INFO.save(
+        deps.storage,
+        &PairInfo {
+            asset_infos: msg.asset_infos,
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: env.contract.address,
+            pair_type: PairType::Xyk {},
+        },
+    )?;
+    RESERVES.save(deps.storage, &msg.reserves)?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let (offer_asset, sender, to) = match msg {
+        ExecuteMsg::Swap {
+            offer_asset, to, ..
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(StdError::generic_err("Cw20 swaps must be sent with a hook"));
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            (offer_asset, info.sender.to_string(), to)
+        }
+        ExecuteMsg::Receive(cw20_msg) => match from_binary(&cw20_msg.msg)? {
+            Cw20HookMsg::Swap { to, .. } => (
+                Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: info.sender,
+                    },
+                    amount: cw20_msg.amount,
+                },
+                cw20_msg.sender,
+                to,
+            ),
+            Cw20HookMsg::WithdrawLiquidity {} => {
+                return Err(StdError::generic_err("Unsupported hook message"))
+            }
+        },
+        _ => return Err(StdError::generic_err("Unsupported message")),
+    };
+
+    let pair = PAIR_INFO.load(deps.storage)?;
+    let reserves = RESERVES.load(deps.storage)?;
+    let return_asset = swap_return(&pair, &reserves, &offer_asset)?;
+
+    Ok(Response::new()
+        .add_message(send_asset(&return_asset, &to.unwrap_or(sender))?)
+        .add_attribute("action", "swap")
+        .add_attribute("offer_asset", offer_asset.to_string())
+        .add_attribute("return_asset", return_asset.to_string()))
+}
+
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pair {} => to_binary(&PAIR_INFO.load(deps.storage)?),
+        QueryMsg::Simulation { offer_asset } => {
+            let pair = PAIR_INFO.load(deps.storage)?;
+            let reserves = RESERVES.load(deps.storage)?;
+
+            to_binary(&SimulationResponse {
+                return_amount: swap_return(&pair, &reserves, &offer_asset)?.amount,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            })
+        }
+        _ => Err(StdError::generic_err("Unsupported query")),
+    }
+}
@@ -0,0 +1,195 @@
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::QueryMsg as FactoryQueryMsg,
+    pair::{
+        Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
+        SimulationResponse,
+    },
+    router::{Cw20HookMsg, ExecuteMsg, QueryMsg, SwapOperation},
+};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Describes the parameters used for creating a mock router
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The factory used to look up the pair of each swap operation
+    pub factory_addr: String,
+}
+
+const FACTORY: Item<Addr> = Item::new("factory");
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    FACTORY.save(deps.storage, &deps.api.addr_validate(&msg.factory_addr)?)?;
+
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+        } => execute_swap_operations(
+            deps.as_ref(),
+            env,
+            operations,
+            minimum_receive,
+            to.map_or_else(|| info.sender.to_string(), |to| to.to_string()),
+            max_spread,
+        ),
+        ExecuteMsg::Receive(cw20_msg) => match from_binary(&cw20_msg.msg)? {
+            Cw20HookMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive,
+                to,
+                max_spread,
+            } => execute_swap_operations(
+                deps.as_ref(),
+                env,
+                operations,
+                minimum_receive,
+                to.unwrap_or(cw20_msg.sender),
+                max_spread,
+            ),
+        },
+        _ => Err(StdError::generic_err("Unsupported message")),
+    }
+}
+
+/// ## Description
+/// Swaps the whole balance of the router in the offer asset of the first operation through the
+/// pairs of `operations` in order, sending the proceeds of the last swap to `to`.
+///
+/// Like the Astroport router, the offer asset is expected to have been sent to the router along
+/// with or before the message. Each swap is simulated up front so that the intermediate proceeds,
+/// which are sent back to the router, can be offered to the next pair.
+fn execute_swap_operations(
+    deps: Deps,
+    env: Env,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: String,
+    max_spread: Option<Decimal>,
+) -> StdResult<Response> {
+    let mut offer_amount = match operations.first() {
+        Some(SwapOperation::AstroSwap {
+            offer_asset_info, ..
+        }) => offer_asset_info.query_pool(&deps.querier, env.contract.address.clone())?,
+        _ => return Err(StdError::generic_err("Invalid swap operations")),
+    };
+    if offer_amount.is_zero() {
+        return Err(StdError::generic_err("Offer amount must not be zero"));
+    }
+
+    let factory = FACTORY.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    let last_index = operations.len() - 1;
+    for (i, operation) in operations.into_iter().enumerate() {
+        let (offer_asset_info, ask_asset_info) = match operation {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => (offer_asset_info, ask_asset_info),
+            SwapOperation::NativeSwap { .. } => {
+                return Err(StdError::generic_err("Native swaps are not supported"))
+            }
+        };
+
+        let pair: PairInfo = deps.querier.query_wasm_smart(
+            &factory,
+            &FactoryQueryMsg::Pair {
+                asset_infos: [offer_asset_info.clone(), ask_asset_info],
+            },
+        )?;
+
+        let offer_asset = Asset {
+            info: offer_asset_info,
+            amount: offer_amount,
+        };
+        let simulation: SimulationResponse = deps.querier.query_wasm_smart(
+            &pair.contract_addr,
+            &PairQueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )?;
+
+        // intermediate proceeds are sent back to the router to be offered to the next pair
+        let recipient = if i == last_index {
+            to.clone()
+        } else {
+            env.contract.address.to_string()
+        };
+
+        messages.push(
+            match &offer_asset.info {
+                AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                    contract_addr: pair.contract_addr.to_string(),
+                    funds: vec![Coin {
+                        denom: denom.clone(),
+                        amount: offer_asset.amount,
+                    }],
+                    msg: to_binary(&PairExecuteMsg::Swap {
+                        offer_asset: offer_asset.clone(),
+                        belief_price: None,
+                        max_spread,
+                        to: Some(recipient),
+                    })?,
+                },
+                AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: pair.contract_addr.to_string(),
+                        amount: offer_asset.amount,
+                        msg: to_binary(&PairCw20HookMsg::Swap {
+                            belief_price: None,
+                            max_spread,
+                            to: Some(recipient),
+                        })?,
+                    })?,
+                },
+            }
+            .into(),
+        );
+
+        offer_amount = simulation.return_amount;
+    }
+
+    if let Some(minimum_receive) = minimum_receive {
+        if offer_amount < minimum_receive {
+            return Err(StdError::generic_err(format!(
+                "Assertion failed; minimum receive amount: {}, swap amount: {}",
+                minimum_receive, offer_amount
+            )));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "execute_swap_operations")
+        .add_attribute("return_amount", offer_amount))
+}
+
+fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
+    Err(StdError::generic_err("Unsupported query"))
+}
@@ -0,0 +1,49 @@
+//! Checks that the schemas committed in `contracts/dca/schema` match the messages and query
+//! responses of the contract.
+
+#[path = "../examples/dca_schema.rs"]
+#[allow(dead_code)]
+mod dca_schema;
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_dir, read_to_string, remove_dir_all},
+    path::Path,
+};
+
+/// Returns the contents of the schemas in `dir`, keyed by their file name
+fn read_schemas(dir: &Path) -> BTreeMap<String, String> {
+    read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            (
+                path.file_name().unwrap().to_string_lossy().into_owned(),
+                read_to_string(&path).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn schemas_are_up_to_date() {
+    let out_dir = std::env::temp_dir().join(format!("dca_schema_{}", std::process::id()));
+    dca_schema::export_schemas(&out_dir);
+    let exported = read_schemas(&out_dir);
+    remove_dir_all(&out_dir).unwrap();
+
+    let committed = read_schemas(&Path::new(env!("CARGO_MANIFEST_DIR")).join("schema"));
+
+    assert_eq!(
+        committed.keys().collect::<Vec<_>>(),
+        exported.keys().collect::<Vec<_>>(),
+        "the committed schemas are out of date, run `cargo schema` in contracts/dca"
+    );
+    for (name, schema) in exported {
+        assert!(
+            committed[&name] == schema,
+            "{} is out of date, run `cargo schema` in contracts/dca",
+            name
+        );
+    }
+}
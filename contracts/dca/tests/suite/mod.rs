@@ -0,0 +1,374 @@
+//! The deployment shared by the integration test crates, where the DCA contract runs against mock
+//! Astroport contracts and cw20-base tokens in cw-multi-test.
+
+// each test crate only uses part of the suite
+#![allow(dead_code)]
+
+use astroport::{
+    asset::{Asset, AssetInfo},
+    router::SwapOperation,
+};
+use astroport_dca::dca::{
+    DcaQueryInfo, ExecuteMsg, InstantiateMsg, QueryMsg, ReferralSource, UserConfig,
+};
+use astroport_dca_module::contract::{execute, instantiate, query, reply};
+use cosmwasm_std::{
+    coin, coins,
+    testing::{mock_env, MockApi, MockStorage},
+    Addr, Coin, Decimal, Empty, Uint128,
+};
+use cw20::{AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, AppResponse, BankKeeper, Contract, ContractWrapper, Executor};
+
+use crate::mocks;
+
+pub const OWNER: &str = "owner";
+pub const USER: &str = "user";
+pub const BOT: &str = "bot";
+pub const TREASURY: &str = "treasury";
+
+fn contract_dca() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+pub fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+pub fn token(contract_addr: &Addr) -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: contract_addr.clone(),
+    }
+}
+
+pub fn swap(offer_asset_info: &AssetInfo, ask_asset_info: &AssetInfo) -> SwapOperation {
+    SwapOperation::AstroSwap {
+        offer_asset_info: offer_asset_info.clone(),
+        ask_asset_info: ask_asset_info.clone(),
+    }
+}
+
+/// The contracts deployed for a test, where uusd and uluna trade at 1:2, and uluna and the ASTRO
+/// token trade at 4:1
+pub struct Suite {
+    pub app: App,
+    pub dca: Addr,
+    pub factory: Addr,
+    pub astro: Addr,
+}
+
+impl Suite {
+    pub fn new() -> Self {
+        let env = mock_env();
+        let mut app = App::new(
+            Box::new(MockApi::default()),
+            env.block,
+            BankKeeper::new(),
+            Box::new(MockStorage::new()),
+        );
+        let owner = Addr::unchecked(OWNER);
+
+        let cw20_id = app.store_code(contract_cw20());
+        let pair_id = app.store_code(mocks::pair::contract());
+        let factory_id = app.store_code(mocks::factory::contract());
+        let router_id = app.store_code(mocks::router::contract());
+        let dca_id = app.store_code(contract_dca());
+
+        let astro = app
+            .instantiate_contract(
+                cw20_id,
+                owner.clone(),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Astroport".to_string(),
+                    symbol: "ASTRO".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![Cw20Coin {
+                        address: OWNER.to_string(),
+                        amount: Uint128::from(1_000_000_000u128),
+                    }],
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "ASTRO",
+                None,
+            )
+            .unwrap();
+
+        let factory = app
+            .instantiate_contract(
+                factory_id,
+                owner.clone(),
+                &mocks::factory::InstantiateMsg {
+                    owner: OWNER.to_string(),
+                },
+                &[],
+                "factory",
+                None,
+            )
+            .unwrap();
+
+        // deploy the pairs holding their reserves, and register them in the factory
+        let uusd_uluna = app
+            .instantiate_contract(
+                pair_id,
+                owner.clone(),
+                &mocks::pair::InstantiateMsg {
+                    asset_infos: [native("uusd"), native("uluna")],
+                    reserves: [Uint128::from(1_000_000u128), Uint128::from(2_000_000u128)],
+                },
+                &[],
+                "uusd-uluna",
+                None,
+            )
+            .unwrap();
+        app.init_bank_balance(
+            &uusd_uluna,
+            vec![coin(1_000_000, "uusd"), coin(2_000_000, "uluna")],
+        )
+        .unwrap();
+
+        let uluna_astro = app
+            .instantiate_contract(
+                pair_id,
+                owner.clone(),
+                &mocks::pair::InstantiateMsg {
+                    asset_infos: [native("uluna"), token(&astro)],
+                    reserves: [Uint128::from(2_000_000u128), Uint128::from(500_000u128)],
+                },
+                &[],
+                "uluna-astro",
+                None,
+            )
+            .unwrap();
+        app.init_bank_balance(&uluna_astro, coins(2_000_000, "uluna"))
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            astro.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: uluna_astro.to_string(),
+                amount: Uint128::from(500_000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+        for pair in [&uusd_uluna, &uluna_astro] {
+            app.execute_contract(
+                owner.clone(),
+                factory.clone(),
+                &mocks::factory::ExecuteMsg::RegisterPair {
+                    pair_addr: pair.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let router = app
+            .instantiate_contract(
+                router_id,
+                owner.clone(),
+                &mocks::router::InstantiateMsg {
+                    factory_addr: factory.to_string(),
+                },
+                &[],
+                "router",
+                None,
+            )
+            .unwrap();
+
+        let dca = app
+            .instantiate_contract(
+                dca_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    max_hops: 3,
+                    per_hop_fee: Uint128::from(100u128),
+                    base_tip: Uint128::zero(),
+                    tip_volume_rate: Decimal::zero(),
+                    max_tip: Uint128::from(1_000u128),
+                    whitelisted_tokens: vec![native("uusd"), native("uluna")],
+                    max_spread: "0.05".to_string(),
+                    factory_addr: factory.to_string(),
+                    router_addr: router.to_string(),
+                    fee_collector: TREASURY.to_string(),
+                    protocol_fee_bps: 100,
+                    referral_share: Decimal::percent(50),
+                    referral_source: ReferralSource::ProtocolFee,
+                    bot_priority_window: 0,
+                    min_bot_bond: Uint128::zero(),
+                    bot_unbonding_period: 0,
+                    bot_whitelist_enabled: false,
+                    min_interval: 0,
+                    min_dca_amounts: vec![],
+                    max_orders_per_user: 10,
+                },
+                &[],
+                "dca",
+                None,
+            )
+            .unwrap();
+
+        // fund the user with uusd and ASTRO
+        app.init_bank_balance(&Addr::unchecked(USER), coins(100_000, "uusd"))
+            .unwrap();
+        app.execute_contract(
+            owner,
+            astro.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: USER.to_string(),
+                amount: Uint128::from(10_000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+        Suite {
+            app,
+            dca,
+            factory,
+            astro,
+        }
+    }
+
+    pub fn execute(
+        &mut self,
+        sender: &str,
+        msg: ExecuteMsg,
+        funds: &[Coin],
+    ) -> Result<AppResponse, String> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.dca.clone(), &msg, funds)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn create_order(
+        &mut self,
+        initial_asset: Asset,
+        target_asset: AssetInfo,
+        funds: &[Coin],
+    ) -> Result<AppResponse, String> {
+        self.execute(
+            USER,
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset,
+                target_asset,
+                interval: 100,
+                dca_amount: Uint128::from(1_000u128),
+                referrer: None,
+                max_hops: None,
+                max_spread: None,
+                route_preference: None,
+                owner: None,
+            },
+            funds,
+        )
+    }
+
+    pub fn purchase(&mut self, hops: Vec<SwapOperation>) -> Result<AppResponse, String> {
+        self.execute(
+            BOT,
+            ExecuteMsg::PerformDcaPurchase {
+                user: USER.to_string(),
+                hops,
+            },
+            &[],
+        )
+    }
+
+    pub fn increase_allowance(&mut self, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(USER),
+                self.astro.clone(),
+                &Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: self.dca.to_string(),
+                    amount: Uint128::from(amount),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    pub fn advance_time(&mut self, seconds: u64) {
+        self.app
+            .update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
+    pub fn balance(&self, address: &str, denom: &str) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(address, denom)
+            .unwrap()
+            .amount
+    }
+
+    pub fn astro_balance(&self, address: &str) -> Uint128 {
+        let response: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.astro,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+
+        response.balance
+    }
+
+    pub fn astro_allowance(&self) -> Uint128 {
+        let response: AllowanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.astro,
+                &Cw20QueryMsg::Allowance {
+                    owner: USER.to_string(),
+                    spender: self.dca.to_string(),
+                },
+            )
+            .unwrap();
+
+        response.allowance
+    }
+
+    pub fn orders(&self) -> Vec<DcaQueryInfo> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.dca,
+                &QueryMsg::UserDcaOrders {
+                    user: USER.to_string(),
+                    include_paused: true,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn user_config(&self) -> UserConfig {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.dca,
+                &QueryMsg::UserConfig {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap()
+    }
+}
@@ -0,0 +1,30 @@
+use astroport_dca::dca::ExecuteMsg;
+use cosmwasm_std::Coin;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::KeeperError;
+
+/// The access to the chain that the keeper needs, implemented by the client of each chain or test
+/// environment the keeper runs on
+pub trait ChainClient {
+    /// Returns the address that the keeper submits purchases with
+    fn sender(&self) -> String;
+
+    /// Returns the time of the latest block in seconds
+    fn block_time(&self) -> Result<u64, KeeperError>;
+
+    /// Queries the contract `contract` with `msg`
+    fn query<M: Serialize, T: DeserializeOwned>(
+        &self,
+        contract: &str,
+        msg: &M,
+    ) -> Result<T, KeeperError>;
+
+    /// Executes `msg` on the DCA contract `contract` from the sender with `funds`
+    fn execute(
+        &mut self,
+        contract: &str,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> Result<(), KeeperError>;
+}
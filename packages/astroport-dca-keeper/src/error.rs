@@ -0,0 +1,12 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+/// Describes the errors of the keeper and the chain clients it runs on
+#[derive(Error, Debug, PartialEq)]
+pub enum KeeperError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Execution failed: {error}")]
+    Execute { error: String },
+}
@@ -0,0 +1,343 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use astroport::{asset::AssetInfo, router::SwapOperation};
+use astroport_dca::{
+    dca::{
+        BotInfo, Config, DcaQueryInfo, ExecuteMsg, NftInfoResponse, OwnerOfResponse, QueryMsg,
+        UserConfig,
+    },
+    evaluation::{check_order_eligibility, fixed_tip},
+};
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::{client::ChainClient, error::KeeperError, route::RouteFinder};
+
+/// The amount of whitelisted tokens queried at once, which is the maximum the contract returns
+const WHITELIST_PAGE: u32 = 30;
+
+/// Describes the strategy of a keeper
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperConfig {
+    /// The uusd cost of submitting a DCA purchase, such as its gas fee
+    pub purchase_cost: Uint128,
+    /// The additional uusd cost of each hop of a DCA purchase
+    pub hop_cost: Uint128,
+    /// The minimum uusd profit of a DCA purchase for it to be submitted
+    pub min_profit: Uint128,
+    /// The amount of consecutive missing order ids after which the scan for new orders stops,
+    /// as cancelled orders leave gaps in the ids
+    pub scan_gap: u64,
+}
+
+impl Default for KeeperConfig {
+    fn default() -> Self {
+        KeeperConfig {
+            purchase_cost: Uint128::zero(),
+            hop_cost: Uint128::zero(),
+            min_profit: Uint128::zero(),
+            scan_gap: 20,
+        }
+    }
+}
+
+/// Describes a DCA purchase the keeper has planned
+#[derive(Clone, Debug, PartialEq)]
+pub struct Purchase {
+    /// The id of the DCA order being purchased
+    pub order_id: u64,
+    /// The owner of the DCA order
+    pub user: String,
+    /// The hop route of the purchase
+    pub hops: Vec<SwapOperation>,
+    /// The estimated uusd tip paid for the purchase
+    pub tip: Uint128,
+    /// The estimated uusd profit of the purchase after its cost
+    pub profit: Uint128,
+}
+
+/// Describes a DCA purchase the keeper has submitted
+#[derive(Debug, PartialEq)]
+pub struct Submission {
+    /// The purchase that was submitted
+    pub purchase: Purchase,
+    /// The result of executing the purchase
+    pub result: Result<(), KeeperError>,
+}
+
+/// Discovers the due DCA orders of a DCA contract and performs their profitable purchases.
+///
+/// The keeper remembers the orders it has discovered between runs, and only scans the order ids
+/// it has not seen yet for new orders.
+pub struct Keeper {
+    /// The address of the DCA contract
+    pub dca: String,
+    /// The strategy of the keeper
+    pub config: KeeperConfig,
+    /// The owners of the DCA orders discovered so far by order id
+    orders: BTreeMap<u64, String>,
+    /// The first order id that has not been scanned yet
+    next_id: u64,
+}
+
+impl Keeper {
+    pub fn new(dca: &str, config: KeeperConfig) -> Self {
+        Keeper {
+            dca: dca.to_string(),
+            config,
+            orders: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Returns the ids of the DCA orders discovered so far
+    pub fn known_orders(&self) -> Vec<u64> {
+        self.orders.keys().copied().collect()
+    }
+
+    /// Plans the profitable purchases of the due DCA orders and submits them, returning the result
+    /// of each submission
+    pub fn run<C: ChainClient>(&mut self, client: &mut C) -> Result<Vec<Submission>, KeeperError> {
+        let purchases = self.plan(client)?;
+
+        Ok(purchases
+            .into_iter()
+            .map(|purchase| {
+                let result = client.execute(
+                    &self.dca,
+                    &ExecuteMsg::PerformDcaPurchase {
+                        user: purchase.user.clone(),
+                        hops: purchase.hops.clone(),
+                    },
+                    &[],
+                );
+                Submission { purchase, result }
+            })
+            .collect())
+    }
+
+    /// Returns the profitable purchases of the unpaused DCA orders that are currently due
+    pub fn plan<C: ChainClient>(&mut self, client: &C) -> Result<Vec<Purchase>, KeeperError> {
+        let now = client.block_time()?;
+        let config: Config = client.query(&self.dca, &QueryMsg::Config {})?;
+        let whitelist = self.whitelisted_tokens(client)?;
+
+        // registered bots with priority can purchase orders during the priority window
+        let is_priority_bot = client
+            .query::<_, BotInfo>(
+                &self.dca,
+                &QueryMsg::Bot {
+                    bot: client.sender(),
+                },
+            )
+            .map(|bot| config.has_bot_priority(&bot))
+            .unwrap_or(false);
+
+        let mut routes = RouteFinder::new(client, config.factory_addr.as_str());
+        let mut purchases = vec![];
+
+        for (user, orders) in self.discover(client)? {
+            let user_config: UserConfig =
+                client.query(&self.dca, &QueryMsg::UserConfig { user: user.clone() })?;
+            let mut tip_balance = user_config.tip_balance;
+
+            // the contract purchases the first order of the user spending the offered asset that
+            // is not paused, so any later orders spending the same asset cannot be purchased
+            let mut offered_assets: Vec<AssetInfo> = vec![];
+
+            for order in orders {
+                let info = &order.info;
+                if info.paused_at.is_some() || offered_assets.contains(&info.initial_asset.info) {
+                    continue;
+                }
+                offered_assets.push(info.initial_asset.info.clone());
+
+                let eligibility =
+                    check_order_eligibility(info, config.bot_priority_window, is_priority_bot, now);
+                if eligibility.is_err()
+                    || info.initial_asset.amount < info.dca_amount
+                    || order.token_allowance < info.dca_amount
+                {
+                    continue;
+                }
+
+                let best = routes
+                    .find_routes(
+                        &info.initial_asset.info,
+                        &info.target_asset,
+                        &whitelist,
+                        &info.route_preference,
+                        order.max_hops,
+                    )
+                    .into_iter()
+                    .filter_map(|hops| {
+                        let tip = estimate_tip(&config, &order, hops.len() as u32);
+                        if tip > tip_balance
+                            || matches!(user_config.max_tip_per_purchase, Some(max) if tip > max)
+                        {
+                            return None;
+                        }
+
+                        let profit = self.profit(tip, hops.len() as u32)?;
+                        Some(Purchase {
+                            order_id: info.id,
+                            user: user.clone(),
+                            hops,
+                            tip,
+                            profit,
+                        })
+                    })
+                    // the routes are sorted shortest first, so the shortest route wins any tie
+                    .fold(None, |best: Option<Purchase>, purchase| match best {
+                        Some(best) if best.profit >= purchase.profit => Some(best),
+                        _ => Some(purchase),
+                    });
+
+                if let Some(purchase) = best {
+                    tip_balance = tip_balance.saturating_sub(purchase.tip);
+                    purchases.push(purchase);
+                }
+            }
+        }
+
+        Ok(purchases)
+    }
+
+    /// Returns the profit of a purchase with `hops` hops paying `tip`, if it is at least the
+    /// minimum profit
+    fn profit(&self, tip: Uint128, hops: u32) -> Option<Uint128> {
+        let cost = self
+            .config
+            .purchase_cost
+            .checked_add(self.config.hop_cost.checked_mul(Uint128::from(hops)).ok()?)
+            .ok()?;
+        let profit = tip.checked_sub(cost).ok()?;
+
+        if profit >= self.config.min_profit {
+            Some(profit)
+        } else {
+            None
+        }
+    }
+
+    /// Scans for new DCA orders and returns the active orders of all their owners
+    fn discover<C: ChainClient>(
+        &mut self,
+        client: &C,
+    ) -> Result<BTreeMap<String, Vec<DcaQueryInfo>>, KeeperError> {
+        let mut missing = 0;
+        let mut id = self.next_id;
+        while missing < self.config.scan_gap {
+            match client.query::<_, NftInfoResponse>(
+                &self.dca,
+                &QueryMsg::NftInfo {
+                    token_id: id.to_string(),
+                },
+            ) {
+                Ok(order) => {
+                    self.orders.insert(id, order.extension.owner.to_string());
+                    self.next_id = id + 1;
+                    missing = 0;
+                }
+                Err(_) => missing += 1,
+            }
+            id += 1;
+        }
+
+        let owners: BTreeSet<String> = self.orders.values().cloned().collect();
+        let mut orders = BTreeMap::new();
+        let mut found = HashMap::new();
+        for owner in owners {
+            let user_orders: Vec<DcaQueryInfo> = client.query(
+                &self.dca,
+                &QueryMsg::UserDcaOrders {
+                    user: owner.clone(),
+                    include_paused: true,
+                },
+            )?;
+            for order in &user_orders {
+                found.insert(order.info.id, owner.clone());
+            }
+            orders.insert(owner, user_orders);
+        }
+
+        // orders that were not found with their owner have either been transferred or cancelled
+        let moved: Vec<u64> = self
+            .orders
+            .keys()
+            .filter(|id| !found.contains_key(id))
+            .copied()
+            .collect();
+        for id in moved {
+            match client.query::<_, OwnerOfResponse>(
+                &self.dca,
+                &QueryMsg::OwnerOf {
+                    token_id: id.to_string(),
+                },
+            ) {
+                Ok(response) => {
+                    if !orders.contains_key(&response.owner) {
+                        let user_orders: Vec<DcaQueryInfo> = client.query(
+                            &self.dca,
+                            &QueryMsg::UserDcaOrders {
+                                user: response.owner.clone(),
+                                include_paused: true,
+                            },
+                        )?;
+                        orders.insert(response.owner.clone(), user_orders);
+                    }
+                    self.orders.insert(id, response.owner);
+                }
+                Err(_) => {
+                    self.orders.remove(&id);
+                }
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// Returns all the tokens that can be used in a DCA hop route
+    fn whitelisted_tokens<C: ChainClient>(
+        &self,
+        client: &C,
+    ) -> Result<Vec<AssetInfo>, KeeperError> {
+        let mut tokens: Vec<AssetInfo> = vec![];
+        loop {
+            let page: Vec<AssetInfo> = client.query(
+                &self.dca,
+                &QueryMsg::WhitelistedTokens {
+                    start_after: tokens.last().cloned(),
+                    limit: Some(WHITELIST_PAGE),
+                },
+            )?;
+            let is_last = (page.len() as u32) < WHITELIST_PAGE;
+            tokens.extend(page);
+
+            if is_last {
+                return Ok(tokens);
+            }
+        }
+    }
+}
+
+/// Estimates the uusd tip paid for purchasing `order` with `hops` hops.
+///
+/// The tip currently offered by a Dutch auction is used as is. Otherwise the tip is estimated as
+/// the `base_tip` and the `per_hop_fee` of each hop, capped at the `max_tip`. This leaves out the
+/// share of the volume of the purchase, so that the estimate never exceeds the tip paid.
+pub fn estimate_tip(config: &Config, order: &DcaQueryInfo, hops: u32) -> Uint128 {
+    if let Some(tip) = order.auction_tip {
+        return tip;
+    }
+
+    // a tip too large to compute is capped at the maximum tip
+    fixed_tip(
+        config.base_tip,
+        config.per_hop_fee,
+        hops,
+        Decimal::zero(),
+        Uint128::zero(),
+        config.max_tip,
+    )
+    .unwrap_or(config.max_tip)
+}
@@ -0,0 +1,14 @@
+//! Reference keeper logic for the Astroport DCA module.
+//!
+//! A [`keeper::Keeper`] discovers the DCA orders that are due, finds a hop route for each of them
+//! over the whitelisted tokens, estimates whether the tip covers the cost of the purchase and
+//! submits the profitable purchases. It talks to the chain through the [`client::ChainClient`]
+//! trait, which is implemented for a cw-multi-test `App` behind the `multi-test` feature so that
+//! strategies can be tested offline.
+
+pub mod client;
+pub mod error;
+pub mod keeper;
+#[cfg(feature = "multi-test")]
+pub mod multi_test;
+pub mod route;
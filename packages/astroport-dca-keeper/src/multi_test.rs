@@ -0,0 +1,53 @@
+use astroport_dca::dca::ExecuteMsg;
+use cosmwasm_std::{Addr, Coin};
+use cw_multi_test::{App, Executor};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{client::ChainClient, error::KeeperError};
+
+/// A [`ChainClient`] running against a cw-multi-test [`App`], submitting messages from `sender`
+pub struct MultiTestClient<'a> {
+    pub app: &'a mut App,
+    pub sender: Addr,
+}
+
+impl<'a> MultiTestClient<'a> {
+    pub fn new(app: &'a mut App, sender: &str) -> Self {
+        MultiTestClient {
+            app,
+            sender: Addr::unchecked(sender),
+        }
+    }
+}
+
+impl<'a> ChainClient for MultiTestClient<'a> {
+    fn sender(&self) -> String {
+        self.sender.to_string()
+    }
+
+    fn block_time(&self) -> Result<u64, KeeperError> {
+        Ok(self.app.block_info().time.seconds())
+    }
+
+    fn query<M: Serialize, T: DeserializeOwned>(
+        &self,
+        contract: &str,
+        msg: &M,
+    ) -> Result<T, KeeperError> {
+        Ok(self.app.wrap().query_wasm_smart(contract, msg)?)
+    }
+
+    fn execute(
+        &mut self,
+        contract: &str,
+        msg: &ExecuteMsg,
+        funds: &[Coin],
+    ) -> Result<(), KeeperError> {
+        self.app
+            .execute_contract(self.sender.clone(), Addr::unchecked(contract), msg, funds)
+            .map(|_| ())
+            .map_err(|e| KeeperError::Execute {
+                error: e.to_string(),
+            })
+    }
+}
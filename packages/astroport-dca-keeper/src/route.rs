@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use astroport::{
+    asset::{AssetInfo, PairInfo},
+    factory::QueryMsg as FactoryQueryMsg,
+    router::SwapOperation,
+};
+use astroport_dca::dca::RoutePreference;
+
+use crate::client::ChainClient;
+
+/// Finds the hop routes between assets through the pairs of an Astroport factory, remembering
+/// which pairs exist so that each pair is only queried once
+pub struct RouteFinder<'a, C: ChainClient> {
+    client: &'a C,
+    factory: String,
+    pairs: HashMap<(String, String), bool>,
+}
+
+impl<'a, C: ChainClient> RouteFinder<'a, C> {
+    pub fn new(client: &'a C, factory: &str) -> Self {
+        RouteFinder {
+            client,
+            factory: factory.to_string(),
+            pairs: HashMap::new(),
+        }
+    }
+
+    /// Returns whether the factory has a pair between `first` and `second`
+    pub fn has_pair(&mut self, first: &AssetInfo, second: &AssetInfo) -> bool {
+        let key = (first.to_string(), second.to_string());
+        if let Some(exists) = self.pairs.get(&key) {
+            return *exists;
+        }
+
+        let exists = self
+            .client
+            .query::<_, PairInfo>(
+                &self.factory,
+                &FactoryQueryMsg::Pair {
+                    asset_infos: [first.clone(), second.clone()],
+                },
+            )
+            .is_ok();
+
+        self.pairs
+            .insert((second.to_string(), first.to_string()), exists);
+        self.pairs.insert(key, exists);
+
+        exists
+    }
+
+    /// Returns the routes from `from` to `to` of at most `max_hops` hops that a DCA purchase can
+    /// take, shortest first.
+    ///
+    /// A pinned route is the only route allowed. Otherwise the intermediate assets of a route are
+    /// taken from `whitelist`, restricted to the allowed intermediates of the order if it has any.
+    pub fn find_routes(
+        &mut self,
+        from: &AssetInfo,
+        to: &AssetInfo,
+        whitelist: &[AssetInfo],
+        route_preference: &Option<RoutePreference>,
+        max_hops: u32,
+    ) -> Vec<Vec<SwapOperation>> {
+        let intermediates: Vec<AssetInfo> = match route_preference {
+            Some(RoutePreference::PinnedRoute { hops }) => {
+                return if !hops.is_empty() && hops.len() as u32 <= max_hops {
+                    vec![hops.clone()]
+                } else {
+                    vec![]
+                };
+            }
+            Some(RoutePreference::AllowedIntermediates { tokens }) => whitelist
+                .iter()
+                .filter(|token| tokens.contains(token))
+                .cloned()
+                .collect(),
+            None => whitelist.to_vec(),
+        };
+
+        let mut routes = vec![];
+        self.extend_routes(
+            &mut vec![from.clone()],
+            to,
+            &intermediates,
+            max_hops,
+            &mut routes,
+        );
+        routes.sort_by_key(|route| route.len());
+
+        routes
+    }
+
+    /// Adds the routes continuing `path` to `to` with at most `max_hops` hops to `routes`
+    fn extend_routes(
+        &mut self,
+        path: &mut Vec<AssetInfo>,
+        to: &AssetInfo,
+        intermediates: &[AssetInfo],
+        max_hops: u32,
+        routes: &mut Vec<Vec<SwapOperation>>,
+    ) {
+        let hops = path.len() as u32;
+        if hops > max_hops {
+            return;
+        }
+
+        let current = path[path.len() - 1].clone();
+        if self.has_pair(&current, to) {
+            let mut assets = path.clone();
+            assets.push(to.clone());
+            routes.push(
+                assets
+                    .windows(2)
+                    .map(|pair| SwapOperation::AstroSwap {
+                        offer_asset_info: pair[0].clone(),
+                        ask_asset_info: pair[1].clone(),
+                    })
+                    .collect(),
+            );
+        }
+
+        if hops == max_hops {
+            return;
+        }
+
+        for intermediate in intermediates {
+            if intermediate == to
+                || path.contains(intermediate)
+                || !self.has_pair(&current, intermediate)
+            {
+                continue;
+            }
+
+            path.push(intermediate.clone());
+            self.extend_routes(path, to, intermediates, max_hops, routes);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::KeeperError;
+    use astroport::factory::PairType;
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{from_slice, to_vec, Addr, Coin, StdError};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    /// A client whose factory only has a pair between each of `pairs`
+    struct PairsClient {
+        pairs: Vec<[AssetInfo; 2]>,
+    }
+
+    impl ChainClient for PairsClient {
+        fn sender(&self) -> String {
+            "bot".to_string()
+        }
+
+        fn block_time(&self) -> Result<u64, KeeperError> {
+            Ok(0)
+        }
+
+        fn query<M: Serialize, T: DeserializeOwned>(
+            &self,
+            _contract: &str,
+            msg: &M,
+        ) -> Result<T, KeeperError> {
+            let asset_infos = match from_slice(&to_vec(msg)?)? {
+                FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                _ => panic!("unexpected query"),
+            };
+
+            let pair = self
+                .pairs
+                .iter()
+                .find(|pair| pair.contains(&asset_infos[0]) && pair.contains(&asset_infos[1]))
+                .ok_or_else(|| StdError::not_found("pair"))?;
+
+            Ok(from_slice(&to_vec(&PairInfo {
+                asset_infos: pair.clone(),
+                contract_addr: Addr::unchecked("pair"),
+                liquidity_token: Addr::unchecked("lp"),
+                pair_type: PairType::Xyk {},
+            })?)?)
+        }
+
+        fn execute(
+            &mut self,
+            _contract: &str,
+            _msg: &ExecuteMsg,
+            _funds: &[Coin],
+        ) -> Result<(), KeeperError> {
+            panic!("unexpected execution")
+        }
+    }
+
+    fn native(denom: &str) -> AssetInfo {
+        AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        }
+    }
+
+    fn swap(offer: &str, ask: &str) -> SwapOperation {
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(offer),
+            ask_asset_info: native(ask),
+        }
+    }
+
+    fn client() -> PairsClient {
+        PairsClient {
+            pairs: vec![
+                [native("uusd"), native("uluna")],
+                [native("uluna"), native("ukrw")],
+                [native("uusd"), native("umnt")],
+                [native("umnt"), native("ukrw")],
+                [native("uusd"), native("ukrw")],
+            ],
+        }
+    }
+
+    #[test]
+    fn finds_routes_through_whitelisted_tokens() {
+        let client = client();
+        let mut finder = RouteFinder::new(&client, "factory");
+        let whitelist = vec![native("uluna"), native("umnt")];
+
+        assert_eq!(
+            finder.find_routes(&native("uusd"), &native("ukrw"), &whitelist, &None, 2),
+            vec![
+                vec![swap("uusd", "ukrw")],
+                vec![swap("uusd", "uluna"), swap("uluna", "ukrw")],
+                vec![swap("uusd", "umnt"), swap("umnt", "ukrw")],
+            ]
+        );
+
+        // routes are limited to the maximum hops
+        assert_eq!(
+            finder.find_routes(&native("uusd"), &native("ukrw"), &whitelist, &None, 1),
+            vec![vec![swap("uusd", "ukrw")]]
+        );
+
+        // intermediate assets must be whitelisted
+        assert_eq!(
+            finder.find_routes(&native("uluna"), &native("umnt"), &[], &None, 3),
+            Vec::<Vec<SwapOperation>>::new()
+        );
+    }
+
+    #[test]
+    fn follows_route_preference() {
+        let client = client();
+        let mut finder = RouteFinder::new(&client, "factory");
+        let whitelist = vec![native("uluna"), native("umnt")];
+
+        assert_eq!(
+            finder.find_routes(
+                &native("uusd"),
+                &native("ukrw"),
+                &whitelist,
+                &Some(RoutePreference::AllowedIntermediates {
+                    tokens: vec![native("umnt")],
+                }),
+                2,
+            ),
+            vec![
+                vec![swap("uusd", "ukrw")],
+                vec![swap("uusd", "umnt"), swap("umnt", "ukrw")],
+            ]
+        );
+
+        let pinned = vec![swap("uusd", "uluna"), swap("uluna", "ukrw")];
+        let route_preference = Some(RoutePreference::PinnedRoute {
+            hops: pinned.clone(),
+        });
+        assert_eq!(
+            finder.find_routes(&native("uusd"), &native("ukrw"), &[], &route_preference, 2),
+            vec![pinned]
+        );
+        assert_eq!(
+            finder.find_routes(&native("uusd"), &native("ukrw"), &[], &route_preference, 1),
+            Vec::<Vec<SwapOperation>>::new()
+        );
+    }
+}
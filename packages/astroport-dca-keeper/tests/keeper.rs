@@ -0,0 +1,239 @@
+//! Runs the keeper against the DCA contract deployed in cw-multi-test, reusing the deployment of
+//! the contract's own integration tests.
+
+#[path = "../../../contracts/dca/tests/mocks/mod.rs"]
+mod mocks;
+#[path = "../../../contracts/dca/tests/suite/mod.rs"]
+mod suite;
+
+use astroport::asset::Asset;
+use astroport_dca::dca::ExecuteMsg;
+use astroport_dca_keeper::{
+    keeper::{Keeper, KeeperConfig},
+    multi_test::MultiTestClient,
+};
+use cosmwasm_std::{coins, Addr, Uint128};
+
+use suite::{native, swap, token, Suite, BOT, USER};
+
+/// Returns a suite where the user has a uusd order purchasing uluna in one hop, and an ASTRO order
+/// purchasing uusd in two hops through uluna, with `tip` uusd deposited for their tips
+fn suite_with_orders(tip: u128) -> Suite {
+    let mut suite = Suite::new();
+
+    suite
+        .create_order(
+            Asset {
+                info: native("uusd"),
+                amount: Uint128::from(2_000u128),
+            },
+            native("uluna"),
+            &coins(2_000, "uusd"),
+        )
+        .unwrap();
+
+    suite.increase_allowance(2_000);
+    let astro = token(&suite.astro);
+    suite
+        .create_order(
+            Asset {
+                info: astro,
+                amount: Uint128::from(2_000u128),
+            },
+            native("uusd"),
+            &[],
+        )
+        .unwrap();
+
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(tip, "uusd"))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn purchases_due_orders() {
+    let mut suite = suite_with_orders(1_000);
+    let mut keeper = Keeper::new(suite.dca.as_str(), KeeperConfig::default());
+
+    let submissions = keeper
+        .run(&mut MultiTestClient::new(&mut suite.app, BOT))
+        .unwrap();
+    assert_eq!(keeper.known_orders(), vec![1, 2]);
+
+    let purchases: Vec<_> = submissions
+        .into_iter()
+        .map(|submission| {
+            submission.result.unwrap();
+            submission.purchase
+        })
+        .collect();
+    assert_eq!(purchases.len(), 2);
+    assert_eq!(
+        purchases[0].hops,
+        vec![swap(&native("uusd"), &native("uluna"))]
+    );
+    assert_eq!(
+        purchases[1].hops,
+        vec![
+            swap(&token(&suite.astro), &native("uluna")),
+            swap(&native("uluna"), &native("uusd")),
+        ]
+    );
+
+    // the bot is paid the per hop fee for each hop
+    assert_eq!(suite.balance(BOT, "uusd"), Uint128::from(300u128));
+    assert_eq!(suite.user_config().tip_balance, Uint128::from(700u128));
+
+    // the orders are not due again until their interval has passed
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    assert_eq!(keeper.plan(&client).unwrap(), vec![]);
+
+    suite.advance_time(100);
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    assert_eq!(keeper.plan(&client).unwrap().len(), 2);
+}
+
+#[test]
+fn skips_unprofitable_purchases() {
+    let mut suite = suite_with_orders(1_000);
+
+    // a single hop purchase costs 80 for a tip of 100, and a two hop purchase 110 for a tip of 200
+    let mut keeper = Keeper::new(
+        suite.dca.as_str(),
+        KeeperConfig {
+            purchase_cost: Uint128::from(50u128),
+            hop_cost: Uint128::from(30u128),
+            min_profit: Uint128::from(30u128),
+            ..KeeperConfig::default()
+        },
+    );
+
+    let purchases = keeper
+        .plan(&MultiTestClient::new(&mut suite.app, BOT))
+        .unwrap();
+    assert_eq!(purchases.len(), 1);
+    assert_eq!(purchases[0].order_id, 2);
+    assert_eq!(purchases[0].tip, Uint128::from(200u128));
+    assert_eq!(purchases[0].profit, Uint128::from(90u128));
+}
+
+#[test]
+fn skips_purchases_the_user_cannot_tip() {
+    let mut suite = suite_with_orders(250);
+    let mut keeper = Keeper::new(suite.dca.as_str(), KeeperConfig::default());
+
+    // the first order uses up the tip balance needed for the second
+    let purchases = keeper
+        .plan(&MultiTestClient::new(&mut suite.app, BOT))
+        .unwrap();
+    assert_eq!(purchases.len(), 1);
+    assert_eq!(purchases[0].order_id, 1);
+}
+
+#[test]
+fn follows_new_and_transferred_orders() {
+    let mut suite = Suite::new();
+    let mut keeper = Keeper::new(suite.dca.as_str(), KeeperConfig::default());
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    assert_eq!(keeper.plan(&client).unwrap(), vec![]);
+    assert_eq!(keeper.known_orders(), Vec::<u64>::new());
+
+    // orders created after a scan are picked up by the next scan
+    suite
+        .create_order(
+            Asset {
+                info: native("uusd"),
+                amount: Uint128::from(2_000u128),
+            },
+            native("uluna"),
+            &coins(2_000, "uusd"),
+        )
+        .unwrap();
+    suite
+        .execute(USER, ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    let purchases = keeper.plan(&client).unwrap();
+    assert_eq!(keeper.known_orders(), vec![1]);
+    assert_eq!(purchases[0].user, USER);
+
+    // a transferred order is purchased for its new owner, once they deposit tips
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::TransferNft {
+                recipient: "other".to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    assert_eq!(keeper.plan(&client).unwrap(), vec![]);
+
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked("other"), coins(1_000, "uusd"))
+        .unwrap();
+    suite
+        .execute("other", ExecuteMsg::AddBotTip {}, &coins(1_000, "uusd"))
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    let purchases = keeper.plan(&client).unwrap();
+    assert_eq!(purchases.len(), 1);
+    assert_eq!(purchases[0].user, "other");
+
+    // cancelled orders are forgotten
+    suite
+        .execute(
+            "other",
+            ExecuteMsg::CancelDcaOrder {
+                initial_asset: native("uusd"),
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    assert_eq!(keeper.plan(&client).unwrap(), vec![]);
+    assert_eq!(keeper.known_orders(), Vec::<u64>::new());
+}
+
+#[test]
+fn skips_paused_orders() {
+    let mut suite = suite_with_orders(1_000);
+    let mut keeper = Keeper::new(suite.dca.as_str(), KeeperConfig::default());
+
+    suite
+        .execute(USER, ExecuteMsg::PauseDcaOrder { id: 1 }, &[])
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    let purchases = keeper.plan(&client).unwrap();
+    assert_eq!(keeper.known_orders(), vec![1, 2]);
+    assert_eq!(purchases.len(), 1);
+    assert_eq!(purchases[0].order_id, 2);
+
+    // resumed orders are purchased again
+    suite
+        .execute(
+            USER,
+            ExecuteMsg::ResumeDcaOrder {
+                id: 1,
+                catch_up: true,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let client = MultiTestClient::new(&mut suite.app, BOT);
+    let purchases = keeper.plan(&client).unwrap();
+    assert_eq!(purchases.len(), 2);
+}
@@ -0,0 +1,19 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// Describes the errors of the simulator
+#[derive(Error, Debug, PartialEq)]
+pub enum SimulatorError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    /// The `entry` is the line of a CSV series, or the position of a JSON snapshot, from 1
+    #[error("Invalid series entry {entry}: {reason}")]
+    InvalidSeries { entry: usize, reason: String },
+
+    #[error("The series must be ordered by time, but {time} follows {previous}")]
+    UnorderedSeries { time: u64, previous: u64 },
+}
@@ -0,0 +1,11 @@
+//! Backtests DCA orders by replaying a historical series of pool prices or reserves through the
+//! order evaluation of the Astroport DCA module.
+//!
+//! The interval eligibility, the split of the protocol fee, the maximum spread and the tip of each
+//! purchase come from [`astroport_dca::evaluation`], which the contract uses as well, so that a
+//! simulated order is purchased exactly like it would be on chain.
+
+pub mod error;
+pub mod pool;
+pub mod series;
+pub mod simulation;
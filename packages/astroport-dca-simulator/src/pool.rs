@@ -0,0 +1,125 @@
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+
+/// The scale of a [`Decimal`], used to divide by a price
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+/// Describes the pool between the initial asset and the target asset of an order at a point in
+/// time
+#[derive(Clone, Debug, PartialEq)]
+pub enum PoolState {
+    /// A pool deep enough to swap at `price`, the amount of the initial asset paid for one unit of
+    /// the target asset, without any spread
+    Price(Decimal),
+    /// A constant product pool holding `offer` of the initial asset and `ask` of the target asset
+    Reserves { offer: Uint128, ask: Uint128 },
+}
+
+/// Describes the result of swapping in a pool
+#[derive(Clone, Debug, PartialEq)]
+pub struct Swap {
+    /// The amount of the target asset received
+    pub return_amount: Uint128,
+    /// The amount of the target asset lost to the price impact of the swap
+    pub spread_amount: Uint128,
+    /// The amount of the target asset taken as the commission of the pool
+    pub commission_amount: Uint128,
+}
+
+impl PoolState {
+    /// Returns the result of swapping `offer_amount` of the initial asset in the pool, which takes
+    /// `commission_rate` of the return as its commission, like an Astroport XYK pair
+    pub fn swap(&self, offer_amount: Uint128, commission_rate: Decimal) -> StdResult<Swap> {
+        let (return_amount, spread_amount) = match self {
+            PoolState::Price(price) => {
+                let price = *price * Uint128::new(DECIMAL_FRACTIONAL);
+                if price.is_zero() {
+                    return Err(StdError::generic_err("The pool price must be above zero"));
+                }
+
+                (
+                    offer_amount.multiply_ratio(DECIMAL_FRACTIONAL, price),
+                    Uint128::zero(),
+                )
+            }
+            PoolState::Reserves { offer, ask } => {
+                if offer.is_zero() || ask.is_zero() {
+                    return Err(StdError::generic_err(
+                        "The pool reserves must be above zero",
+                    ));
+                }
+
+                let return_amount =
+                    ask.checked_sub(ask.multiply_ratio(*offer, *offer + offer_amount))?;
+                let spread_amount = offer_amount
+                    .multiply_ratio(*ask, *offer)
+                    .saturating_sub(return_amount);
+
+                (return_amount, spread_amount)
+            }
+        };
+
+        let commission_amount = commission_rate * return_amount;
+
+        Ok(Swap {
+            return_amount: return_amount.checked_sub(commission_amount)?,
+            spread_amount,
+            commission_amount,
+        })
+    }
+}
+
+impl Swap {
+    /// Returns whether the spread of the swap exceeds `max_spread`, in which case an Astroport
+    /// pair rejects the swap
+    pub fn exceeds_spread(&self, max_spread: Decimal) -> bool {
+        let expected = self.return_amount + self.commission_amount + self.spread_amount;
+
+        !expected.is_zero() && Decimal::from_ratio(self.spread_amount, expected) > max_spread
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_swap_at_price() {
+        let swap = PoolState::Price(Decimal::from_ratio(5u128, 2u128))
+            .swap(Uint128::from(1_000u128), Decimal::percent(1))
+            .unwrap();
+
+        assert_eq!(
+            swap,
+            Swap {
+                return_amount: Uint128::from(396u128),
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::from(4u128),
+            }
+        );
+        assert!(!swap.exceeds_spread(Decimal::zero()));
+    }
+
+    #[test]
+    fn does_swap_against_reserves() {
+        let pool = PoolState::Reserves {
+            offer: Uint128::from(10_000u128),
+            ask: Uint128::from(20_000u128),
+        };
+
+        // 10_000 * 20_000 / 11_000 leaves 18_181 in the pool, rounded down
+        let swap = pool
+            .swap(Uint128::from(1_000u128), Decimal::zero())
+            .unwrap();
+        assert_eq!(
+            swap,
+            Swap {
+                return_amount: Uint128::from(1_819u128),
+                spread_amount: Uint128::from(181u128),
+                commission_amount: Uint128::zero(),
+            }
+        );
+
+        assert!(swap.exceeds_spread(Decimal::percent(5)));
+        assert!(!swap.exceeds_spread(Decimal::percent(10)));
+    }
+}
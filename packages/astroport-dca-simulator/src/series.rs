@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{from_slice, Decimal, Uint128};
+use serde::Deserialize;
+
+use crate::{error::SimulatorError, pool::PoolState};
+
+/// Describes the pool of an order at the time `time` in seconds
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolSnapshot {
+    pub time: u64,
+    pub pool: PoolState,
+}
+
+/// Describes a snapshot in a JSON series, which has either a `price` or both reserves
+#[derive(Deserialize)]
+struct JsonSnapshot {
+    time: u64,
+    price: Option<Decimal>,
+    offer_reserve: Option<Uint128>,
+    ask_reserve: Option<Uint128>,
+}
+
+/// Parses a CSV series of pool snapshots ordered by time.
+///
+/// The header names the columns, which are either `time,price` or
+/// `time,offer_reserve,ask_reserve`, in any order.
+pub fn parse_csv(csv: &str) -> Result<Vec<PoolSnapshot>, SimulatorError> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let (_, header) = lines.next().ok_or(SimulatorError::InvalidSeries {
+        entry: 1,
+        reason: "missing header".to_string(),
+    })?;
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    let column = |name: &str| columns.iter().position(|column| *column == name);
+
+    let time_column = column("time").ok_or(SimulatorError::InvalidSeries {
+        entry: 1,
+        reason: "missing time column".to_string(),
+    })?;
+    let pool_columns = match (
+        column("price"),
+        column("offer_reserve"),
+        column("ask_reserve"),
+    ) {
+        (Some(price), _, _) => (price, None),
+        (None, Some(offer), Some(ask)) => (offer, Some(ask)),
+        _ => {
+            return Err(SimulatorError::InvalidSeries {
+                entry: 1,
+                reason: "missing price or reserve columns".to_string(),
+            })
+        }
+    };
+
+    let snapshots = lines
+        .map(|(line, row)| {
+            let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+            let field = |index: usize| {
+                fields
+                    .get(index)
+                    .copied()
+                    .ok_or(SimulatorError::InvalidSeries {
+                        entry: line,
+                        reason: format!("missing column {}", columns[index]),
+                    })
+            };
+            let invalid = |reason: String| SimulatorError::InvalidSeries {
+                entry: line,
+                reason,
+            };
+
+            let time = field(time_column)?
+                .parse::<u64>()
+                .map_err(|e| invalid(e.to_string()))?;
+            let pool = match pool_columns {
+                (price, None) => PoolState::Price(
+                    Decimal::from_str(field(price)?).map_err(|e| invalid(e.to_string()))?,
+                ),
+                (offer, Some(ask)) => PoolState::Reserves {
+                    offer: Uint128::from_str(field(offer)?).map_err(|e| invalid(e.to_string()))?,
+                    ask: Uint128::from_str(field(ask)?).map_err(|e| invalid(e.to_string()))?,
+                },
+            };
+
+            Ok(PoolSnapshot { time, pool })
+        })
+        .collect::<Result<Vec<_>, SimulatorError>>()?;
+
+    validate_order(&snapshots)?;
+    Ok(snapshots)
+}
+
+/// Parses a JSON array of pool snapshots ordered by time, each an object with a `time` and either
+/// a `price` or an `offer_reserve` and an `ask_reserve`, given as strings.
+pub fn parse_json(json: &[u8]) -> Result<Vec<PoolSnapshot>, SimulatorError> {
+    let snapshots = from_slice::<Vec<JsonSnapshot>>(json)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, snapshot)| {
+            let pool = match snapshot {
+                JsonSnapshot {
+                    price: Some(price), ..
+                } => PoolState::Price(price),
+                JsonSnapshot {
+                    offer_reserve: Some(offer),
+                    ask_reserve: Some(ask),
+                    ..
+                } => PoolState::Reserves { offer, ask },
+                _ => {
+                    return Err(SimulatorError::InvalidSeries {
+                        entry: index + 1,
+                        reason: "missing price or reserves".to_string(),
+                    })
+                }
+            };
+
+            Ok(PoolSnapshot {
+                time: snapshot.time,
+                pool,
+            })
+        })
+        .collect::<Result<Vec<_>, SimulatorError>>()?;
+
+    validate_order(&snapshots)?;
+    Ok(snapshots)
+}
+
+/// Checks that `snapshots` are ordered by time
+fn validate_order(snapshots: &[PoolSnapshot]) -> Result<(), SimulatorError> {
+    for pair in snapshots.windows(2) {
+        if pair[1].time < pair[0].time {
+            return Err(SimulatorError::UnorderedSeries {
+                time: pair[1].time,
+                previous: pair[0].time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_parse_csv() {
+        assert_eq!(
+            parse_csv("time,price\n0,1.5\n\n60, 2\n").unwrap(),
+            vec![
+                PoolSnapshot {
+                    time: 0,
+                    pool: PoolState::Price(Decimal::from_str("1.5").unwrap()),
+                },
+                PoolSnapshot {
+                    time: 60,
+                    pool: PoolState::Price(Decimal::from_str("2").unwrap()),
+                },
+            ]
+        );
+
+        assert_eq!(
+            parse_csv("ask_reserve,time,offer_reserve\n200,0,100").unwrap(),
+            vec![PoolSnapshot {
+                time: 0,
+                pool: PoolState::Reserves {
+                    offer: Uint128::from(100u128),
+                    ask: Uint128::from(200u128),
+                },
+            }]
+        );
+
+        assert!(matches!(
+            parse_csv("time,price\n0,abc"),
+            Err(SimulatorError::InvalidSeries { entry: 2, .. })
+        ));
+        assert_eq!(
+            parse_csv("time,price\n60,1\n0,1"),
+            Err(SimulatorError::UnorderedSeries {
+                time: 0,
+                previous: 60,
+            })
+        );
+    }
+
+    #[test]
+    fn does_parse_json() {
+        assert_eq!(
+            parse_json(
+                br#"[
+                    {"time": 0, "price": "1.5"},
+                    {"time": 60, "offer_reserve": "100", "ask_reserve": "200"}
+                ]"#
+            )
+            .unwrap(),
+            vec![
+                PoolSnapshot {
+                    time: 0,
+                    pool: PoolState::Price(Decimal::from_str("1.5").unwrap()),
+                },
+                PoolSnapshot {
+                    time: 60,
+                    pool: PoolState::Reserves {
+                        offer: Uint128::from(100u128),
+                        ask: Uint128::from(200u128),
+                    },
+                },
+            ]
+        );
+
+        assert!(matches!(
+            parse_json(br#"[{"time": 0, "offer_reserve": "100"}]"#),
+            Err(SimulatorError::InvalidSeries { entry: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+use astroport_dca::{
+    dca::{DcaInfo, TipMode},
+    evaluation::{
+        auction_tip, check_order_eligibility, fixed_tip, max_spread_for, purchase_amounts,
+    },
+};
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::{error::SimulatorError, series::PoolSnapshot};
+
+/// Describes the contract, user and pool parameters that an order is simulated with
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationConfig {
+    /// The maximum spread of the contract, used unless the order or the user override it
+    pub max_spread: Decimal,
+    /// The maximum spread of the user, used unless the order overrides it
+    pub user_max_spread: Option<Decimal>,
+    /// The protocol fee in basis points taken from the `dca_amount` of each purchase
+    pub protocol_fee_bps: u16,
+    /// The share of the protocol fee credited to the referrer of the order, if it has one
+    pub referral_share: Decimal,
+    /// The base uusd tip of each purchase
+    pub base_tip: Uint128,
+    /// The uusd tip of each hop of a purchase
+    pub per_hop_fee: Uint128,
+    /// The share of the uusd value of the `dca_amount` paid as a tip
+    pub tip_volume_rate: Decimal,
+    /// The maximum uusd tip of a purchase
+    pub max_tip: Uint128,
+    /// How the user prices the tips of their purchases
+    pub tip_mode: TipMode,
+    /// The maximum tip the user is willing to pay per purchase
+    pub max_tip_per_purchase: Option<Uint128>,
+    /// The uusd value of one unit of the initial asset, used to price tips by volume
+    pub uusd_price: Decimal,
+    /// The share of the return of each swap taken as the commission of the pool
+    pub commission_rate: Decimal,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            max_spread: Decimal::percent(5),
+            user_max_spread: None,
+            protocol_fee_bps: 0,
+            referral_share: Decimal::zero(),
+            base_tip: Uint128::zero(),
+            per_hop_fee: Uint128::zero(),
+            tip_volume_rate: Decimal::zero(),
+            max_tip: Uint128::zero(),
+            tip_mode: TipMode::Fixed,
+            max_tip_per_purchase: None,
+            uusd_price: Decimal::one(),
+            commission_rate: Decimal::permille(3),
+        }
+    }
+}
+
+/// Describes a simulated DCA purchase
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fill {
+    /// The time of the purchase in seconds
+    pub time: u64,
+    /// The amount of the initial asset swapped, after the protocol fee
+    pub swap_amount: Uint128,
+    /// The protocol fee taken from the `dca_amount`
+    pub protocol_fee: Uint128,
+    /// The part of the protocol fee credited to the referrer
+    pub referral_fee: Uint128,
+    /// The amount of the target asset received
+    pub return_amount: Uint128,
+    /// The amount of the target asset taken as the commission of the pool
+    pub commission_amount: Uint128,
+    /// The uusd tip paid to the bot
+    pub tip: Uint128,
+}
+
+/// Describes why a due DCA purchase was not performed at a snapshot
+#[derive(Clone, Debug, PartialEq)]
+pub enum Skip {
+    /// The spread of the swap exceeded the maximum spread of the order
+    MaxSpreadExceeded { time: u64, max_spread: Decimal },
+    /// The tip exceeded the maximum tip the user is willing to pay
+    TipCapExceeded { time: u64, tip: Uint128 },
+}
+
+/// Describes the outcome of replaying an order through a series of snapshots
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// The purchases of the order
+    pub fills: Vec<Fill>,
+    /// The due purchases that failed
+    pub skips: Vec<Skip>,
+    /// The total amount of the initial asset spent, including protocol fees
+    pub spent: Uint128,
+    /// The total amount of the target asset received
+    pub received: Uint128,
+    /// The total protocol fees paid in the initial asset
+    pub protocol_fees: Uint128,
+    /// The total uusd tips paid
+    pub tips: Uint128,
+    /// The amount of the initial asset left in the order
+    pub remaining: Uint128,
+}
+
+impl Report {
+    /// Returns the average amount of the initial asset paid per unit of the target asset,
+    /// including protocol fees, if anything was received
+    pub fn average_cost(&self) -> Option<Decimal> {
+        if self.received.is_zero() {
+            None
+        } else {
+            Some(Decimal::from_ratio(self.spent, self.received))
+        }
+    }
+}
+
+/// Replays `order` through `series`, purchasing it with a single hop at the first snapshot at
+/// which it is due, like a registered bot with priority would.
+///
+/// A purchase that the contract or the pool would reject leaves the order unchanged, and is
+/// retried at the next snapshot. The replay ends with the series or once the order cannot fund
+/// another purchase.
+pub fn simulate(
+    config: &SimulationConfig,
+    mut order: DcaInfo,
+    series: &[PoolSnapshot],
+) -> Result<Report, SimulatorError> {
+    let mut report = Report::default();
+    let referral_share = order.referrer.as_ref().map(|_| config.referral_share);
+    let max_spread = max_spread_for(&order, config.user_max_spread, config.max_spread);
+
+    for snapshot in series {
+        let now = snapshot.time;
+        if order.initial_asset.amount < order.dca_amount {
+            break;
+        }
+        if check_order_eligibility(&order, 0, true, now).is_err() {
+            continue;
+        }
+
+        let tip = match auction_tip(&config.tip_mode, &order, now) {
+            Some(tip) => tip,
+            None => fixed_tip(
+                config.base_tip,
+                config.per_hop_fee,
+                1,
+                config.tip_volume_rate,
+                config.uusd_price * order.dca_amount,
+                config.max_tip,
+            )?,
+        };
+        if matches!(config.max_tip_per_purchase, Some(max) if tip > max) {
+            report.skips.push(Skip::TipCapExceeded { time: now, tip });
+            continue;
+        }
+
+        let amounts = purchase_amounts(order.dca_amount, config.protocol_fee_bps, referral_share)?;
+        let swap = snapshot
+            .pool
+            .swap(amounts.swap_amount, config.commission_rate)?;
+        if swap.exceeds_spread(max_spread) {
+            report.skips.push(Skip::MaxSpreadExceeded {
+                time: now,
+                max_spread,
+            });
+            continue;
+        }
+
+        order.initial_asset.amount = order.initial_asset.amount.checked_sub(order.dca_amount)?;
+        order.last_purchase = now;
+        order.catch_up_purchases = order.catch_up_purchases.saturating_sub(1);
+
+        report.spent = report.spent.checked_add(order.dca_amount)?;
+        report.received = report.received.checked_add(swap.return_amount)?;
+        report.protocol_fees = report.protocol_fees.checked_add(amounts.protocol_fee)?;
+        report.tips = report.tips.checked_add(tip)?;
+        report.fills.push(Fill {
+            time: now,
+            swap_amount: amounts.swap_amount,
+            protocol_fee: amounts.protocol_fee,
+            referral_fee: amounts.referral_fee,
+            return_amount: swap.return_amount,
+            commission_amount: swap.commission_amount,
+            tip,
+        });
+    }
+
+    report.remaining = order.initial_asset.amount;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::PoolState, series::parse_csv};
+    use astroport::asset::{Asset, AssetInfo};
+    use cosmwasm_std::Addr;
+
+    fn order(amount: u128, dca_amount: u128, interval: u64) -> DcaInfo {
+        DcaInfo {
+            id: 1,
+            owner: Addr::unchecked("user"),
+            initial_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(amount),
+            },
+            target_asset: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            interval,
+            last_purchase: 0,
+            start_time: 0,
+            dca_amount: Uint128::from(dca_amount),
+            referrer: None,
+            max_hops: None,
+            max_spread: None,
+            route_preference: None,
+            paused_at: None,
+            catch_up_purchases: 0,
+        }
+    }
+
+    #[test]
+    fn does_purchase_once_per_interval() {
+        let series = parse_csv("time,price\n100,2\n150,4\n200,4\n300,5\n400,1\n").unwrap();
+        let config = SimulationConfig {
+            protocol_fee_bps: 100,
+            base_tip: Uint128::from(10u128),
+            per_hop_fee: Uint128::from(5u128),
+            max_tip: Uint128::from(100u128),
+            commission_rate: Decimal::zero(),
+            ..SimulationConfig::default()
+        };
+
+        // the order runs out of funds after three purchases
+        let report = simulate(&config, order(3_000, 1_000, 100), &series).unwrap();
+
+        assert_eq!(
+            report
+                .fills
+                .iter()
+                .map(|fill| fill.time)
+                .collect::<Vec<_>>(),
+            vec![100, 200, 300]
+        );
+        assert_eq!(
+            report
+                .fills
+                .iter()
+                .map(|fill| fill.return_amount.u128())
+                .collect::<Vec<_>>(),
+            vec![495, 247, 198]
+        );
+        assert_eq!(report.spent, Uint128::from(3_000u128));
+        assert_eq!(report.received, Uint128::from(940u128));
+        assert_eq!(report.protocol_fees, Uint128::from(30u128));
+        assert_eq!(report.tips, Uint128::from(45u128));
+        assert_eq!(report.remaining, Uint128::zero());
+        assert_eq!(
+            report.average_cost(),
+            Some(Decimal::from_ratio(3_000u128, 940u128))
+        );
+    }
+
+    #[test]
+    fn does_retry_purchases_beyond_max_spread() {
+        let shallow = PoolState::Reserves {
+            offer: Uint128::from(10_000u128),
+            ask: Uint128::from(10_000u128),
+        };
+        let deep = PoolState::Reserves {
+            offer: Uint128::from(1_000_000u128),
+            ask: Uint128::from(1_000_000u128),
+        };
+        let series = vec![
+            PoolSnapshot {
+                time: 100,
+                pool: shallow,
+            },
+            PoolSnapshot {
+                time: 150,
+                pool: deep,
+            },
+        ];
+
+        let report = simulate(
+            &SimulationConfig::default(),
+            order(2_000, 1_000, 100),
+            &series,
+        )
+        .unwrap();
+
+        assert_eq!(
+            report.skips,
+            vec![Skip::MaxSpreadExceeded {
+                time: 100,
+                max_spread: Decimal::percent(5),
+            }]
+        );
+        assert_eq!(report.fills.len(), 1);
+        assert_eq!(report.fills[0].time, 150);
+        assert_eq!(report.remaining, Uint128::from(1_000u128));
+    }
+}
@@ -6,11 +6,18 @@ use astroport::{
     router::SwapOperation,
 };
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, OverflowError, OverflowOperation, StdResult, Uint128};
+use cw20::Expiration;
+
+use crate::evaluation;
 
 /// Describes information about a DCA order
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DcaInfo {
+    /// The unique id of the order
+    pub id: u64,
+    /// The address of the user who created the order
+    pub owner: Addr,
     /// The starting asset deposited by the user, with the amount representing the users deposited
     /// amount of the token
     pub initial_asset: Asset,
@@ -18,10 +25,437 @@ pub struct DcaInfo {
     pub target_asset: AssetInfo,
     /// The interval in seconds between DCA purchases
     pub interval: u64,
-    /// The last time the `target_asset` was purchased
+    /// The last time the `target_asset` was purchased, or the time of the purchase that was last
+    /// skipped
     pub last_purchase: u64,
+    /// The time the order was created, or its purchase time was last reset, before which it is
+    /// not considered due
+    #[serde(default)]
+    pub start_time: u64,
     /// The amount of `initial_asset` to spend each DCA purchase
     pub dca_amount: Uint128,
+    /// The address that referred the user to create this order
+    #[serde(default)]
+    pub referrer: Option<Addr>,
+    /// An override for the maximum amount of hops of a DCA purchase of this order, taking
+    /// precedence over the user and contract configuration
+    #[serde(default)]
+    pub max_hops: Option<u32>,
+    /// An override for the maximum spread of a DCA purchase of this order, taking precedence over
+    /// the user and contract configuration
+    #[serde(default)]
+    pub max_spread: Option<Decimal>,
+    /// Restricts the hop routes that DCA purchases of this order can use, in addition to the
+    /// contract token whitelist
+    #[serde(default)]
+    pub route_preference: Option<RoutePreference>,
+    /// The time the order was paused, if it is paused, during which it is not purchased
+    #[serde(default)]
+    pub paused_at: Option<u64>,
+    /// The amount of purchases missed while the order was paused that are still to be made up
+    /// for, each of which is due straight away regardless of the interval
+    #[serde(default)]
+    pub catch_up_purchases: u32,
+}
+
+/// Describes how an order restricts the hop routes used for its DCA purchases
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutePreference {
+    /// DCA purchases must use exactly this hop route, which may swap through tokens that are not
+    /// whitelisted
+    PinnedRoute { hops: Vec<SwapOperation> },
+    /// DCA purchases can only swap through these tokens, which must also be whitelisted
+    AllowedIntermediates { tokens: Vec<AssetInfo> },
+}
+
+/// Describes an action that an operator can be approved to perform on behalf of a user
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorPermission {
+    /// Create DCA orders funded by the user
+    CreateOrder,
+    /// Modify, pause and resume the DCA orders of the user, and skip their purchases
+    ModifyOrder,
+    /// Cancel the DCA orders of the user
+    CancelOrder,
+    /// Withdraw the bot tip of the user
+    WithdrawTip,
+}
+
+/// Describes where the rewards paid to referrers are taken from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferralSource {
+    /// Referrers receive a share of the protocol fee, paid in the `initial_asset` of the order
+    ProtocolFee,
+    /// Referrers receive a share of the bot tip, paid in uusd
+    BotTip,
+}
+
+/// Describes how the tip paid to bots for a users DCA purchases is priced
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TipMode {
+    /// The tip is priced by the contract configuration from the hops and volume of the purchase
+    Fixed,
+    /// The tip starts at `start_tip` when an order becomes due and rises linearly to `max_tip`
+    /// over `duration` seconds while the order is left unexecuted
+    DutchAuction {
+        /// The uusd tip offered as soon as an order becomes due
+        start_tip: Uint128,
+        /// The uusd tip offered once the order has been due for `duration` seconds
+        max_tip: Uint128,
+        /// The time in seconds it takes for the tip to rise from `start_tip` to `max_tip`
+        duration: u64,
+    },
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TipMode {
+    fn default() -> Self {
+        TipMode::Fixed
+    }
+}
+
+/// Describes a protocol fee override for DCA purchases into a specific target asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TargetAssetFee {
+    /// The asset being purchased that the override applies to
+    pub target_asset: AssetInfo,
+    /// The protocol fee in basis points taken from `dca_amount` for purchases of `target_asset`
+    pub fee_bps: u16,
+}
+
+/// Describes the minimum amount of a specific initial asset spent in each DCA purchase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinDcaAmount {
+    /// The asset being spent that the minimum applies to
+    pub initial_asset: AssetInfo,
+    /// The minimum `dca_amount` of `initial_asset` for an order
+    pub amount: Uint128,
+}
+
+/// Describes the main dca module parameters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing if the user does not specify
+    pub max_hops: u32,
+    /// The maximum amount of spread when performing a swap from `initial_asset` to `target_asset` when DCAing if the user does not specify
+    pub max_spread: Decimal,
+    /// The fee a user must pay per hop performed in a DCA purchase
+    pub per_hop_fee: Uint128,
+    /// The base fee a user must pay for each DCA purchase
+    pub base_tip: Uint128,
+    /// The share of the uusd value of the `dca_amount` a user must pay for each DCA purchase
+    pub tip_volume_rate: Decimal,
+    /// The maximum tip paid for a DCA purchase
+    pub max_tip: Uint128,
+    /// The address of the Astroport factory contract
+    pub factory_addr: Addr,
+    /// The address of the Astroport router contract
+    pub router_addr: Addr,
+    /// The address that receives the protocol fee taken from DCA purchases
+    pub fee_collector: Addr,
+    /// The protocol fee in basis points taken from the `dca_amount` of each DCA purchase
+    pub protocol_fee_bps: u16,
+    /// Protocol fee overrides for DCA purchases into specific target assets
+    pub target_asset_fees: Vec<TargetAssetFee>,
+    /// The addresses whose DCA purchases are exempt from the protocol fee
+    pub fee_exempt_addresses: Vec<Addr>,
+    /// The share of the protocol fee or bot tip credited to the referrer of an order
+    pub referral_share: Decimal,
+    /// Whether referrers are paid out of the protocol fee or the bot tip
+    pub referral_source: ReferralSource,
+    /// The time in seconds after an order becomes due during which only registered bots can
+    /// perform the DCA purchase
+    pub bot_priority_window: u64,
+    /// The minimum uusd bond a registered bot must have staked to receive priority
+    pub min_bot_bond: Uint128,
+    /// The time in seconds the bond of an unregistering bot stays slashable before it can be
+    /// claimed
+    pub bot_unbonding_period: u64,
+    /// Whether only bots whitelisted by the factory owner receive priority
+    pub bot_whitelist_enabled: bool,
+    /// The minimum interval in seconds between DCA purchases of an order
+    pub min_interval: u64,
+    /// The minimum `dca_amount` of an order for specific initial assets
+    pub min_dca_amounts: Vec<MinDcaAmount>,
+    /// The maximum amount of DCA orders a user can have at once
+    pub max_orders_per_user: u32,
+}
+
+impl Config {
+    /// Returns whether `bot` has priority to perform DCA purchases during the
+    /// `bot_priority_window`, which requires a sufficient bond that is not unbonding, and a
+    /// whitelisting if the bot whitelist is enabled.
+    pub fn has_bot_priority(&self, bot: &BotInfo) -> bool {
+        bot.bond >= self.min_bot_bond
+            && bot.unbonding_until.is_none()
+            && (bot.whitelisted || !self.bot_whitelist_enabled)
+    }
+
+    /// Returns the minimum tip paid for a DCA purchase, which is the tip for a single hop.
+    pub fn min_tip(&self) -> StdResult<Uint128> {
+        Ok(self.base_tip.checked_add(self.per_hop_fee)?)
+    }
+
+    /// Returns the protocol fee in basis points that `user` pays when purchasing `target_asset`.
+    pub fn protocol_fee_bps_for(&self, user: &Addr, target_asset: &AssetInfo) -> u16 {
+        if self.is_fee_exempt(user) {
+            return 0;
+        }
+
+        self.target_fee_bps(target_asset)
+    }
+
+    /// Returns whether `user` is exempt from the protocol fee.
+    pub fn is_fee_exempt(&self, user: &Addr) -> bool {
+        self.fee_exempt_addresses.contains(user)
+    }
+
+    /// Returns the protocol fee in basis points of purchases of `target_asset` by users that are
+    /// not exempt from it.
+    pub fn target_fee_bps(&self, target_asset: &AssetInfo) -> u16 {
+        self.target_asset_fees
+            .iter()
+            .find(|fee| &fee.target_asset == target_asset)
+            .map(|fee| fee.fee_bps)
+            .unwrap_or(self.protocol_fee_bps)
+    }
+
+    /// Returns the maximum amount of hops for a DCA purchase of `order`, preferring the order
+    /// override, then the user override, then the contract value.
+    pub fn max_hops_for(&self, user_config: &UserConfig, order: &DcaInfo) -> u32 {
+        evaluation::max_hops_for(order, user_config.max_hops, self.max_hops)
+    }
+
+    /// Returns the maximum spread for a DCA purchase of `order`, preferring the order override,
+    /// then the user override, then the contract value.
+    pub fn max_spread_for(&self, user_config: &UserConfig, order: &DcaInfo) -> Decimal {
+        evaluation::max_spread_for(order, user_config.max_spread, self.max_spread)
+    }
+
+    /// Returns the minimum `dca_amount` of an order spending `initial_asset`.
+    pub fn min_dca_amount_for(&self, initial_asset: &AssetInfo) -> Uint128 {
+        self.min_dca_amounts
+            .iter()
+            .find(|min| &min.initial_asset == initial_asset)
+            .map(|min| min.amount)
+            .unwrap_or_default()
+    }
+}
+
+/// Describes the users custom configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserConfig {
+    /// An override for the maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing
+    pub max_hops: Option<u32>,
+    /// An override for the maximum amount of spread when performing a swap from `initial_asset` to `target_asset` when DCAing
+    pub max_spread: Option<Decimal>,
+    /// The amount of uusd the user has deposited for their tips when performing DCA purchases
+    pub tip_balance: Uint128,
+    /// The maximum tip the user is willing to pay per DCA purchase
+    #[serde(default)]
+    pub max_tip_per_purchase: Option<Uint128>,
+    /// How the tip paid for the users DCA purchases is priced
+    #[serde(default)]
+    pub tip_mode: TipMode,
+}
+
+impl Default for UserConfig {
+    fn default() -> Self {
+        UserConfig {
+            max_hops: None,
+            max_spread: None,
+            tip_balance: Uint128::zero(),
+            max_tip_per_purchase: None,
+            tip_mode: TipMode::Fixed,
+        }
+    }
+}
+
+/// Describes a registered bot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BotInfo {
+    /// The address of the bot
+    pub address: Addr,
+    /// The amount of uusd the bot has staked as a bond
+    pub bond: Uint128,
+    /// The time the bot was registered
+    pub registered_at: u64,
+    /// The time from which the bond of the bot can be claimed, if the bot is unregistering
+    #[serde(default)]
+    pub unbonding_until: Option<u64>,
+    /// Whether the bot has been whitelisted by the factory owner
+    #[serde(default)]
+    pub whitelisted: bool,
+}
+
+/// Describes the approval of an operator to manage the DCA orders of a user
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorApproval {
+    /// The address of the operator
+    pub operator: Addr,
+    /// The actions the operator can perform on behalf of the user
+    pub permissions: Vec<OperatorPermission>,
+    /// When the approval expires
+    pub expires: Expiration,
+}
+
+/// Describes a DCA vault, which pools the deposits of its participants into one purchase of
+/// `target_asset` every `interval`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfo {
+    /// The id of the vault
+    pub id: u64,
+    /// The asset spent by the vault
+    pub initial_asset: AssetInfo,
+    /// The asset purchased by the vault
+    pub target_asset: AssetInfo,
+    /// The time in seconds between the purchases of the vault
+    pub interval: u64,
+    /// The time of the last purchase of the vault
+    pub last_purchase: u64,
+    /// The amount of purchases the vault has performed
+    pub epoch: u64,
+    /// The shares taking part in the next purchase, each spending one unit of `initial_asset`
+    pub total_shares: Uint128,
+    /// The amount of uusd deposited by the participants to tip the bots performing the purchases
+    /// of the vault
+    pub tip_balance: Uint128,
+    /// The maximum spread of the purchases of the vault, or the contract `max_spread` if not set
+    #[serde(default)]
+    pub max_spread: Option<Decimal>,
+}
+
+/// Describes an aggregated purchase of a DCA vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultPurchaseRecord {
+    /// The epoch of the vault for the purchase
+    pub epoch: u64,
+    /// The time of the purchase
+    pub time: u64,
+    /// The shares that took part in the purchase
+    pub shares: Uint128,
+    /// The amount of `initial_asset` swapped in the purchase, after the protocol fee
+    pub offer_asset: Asset,
+    /// The amount of `target_asset` received in the purchase
+    pub received: Asset,
+    /// The uusd tip paid for the purchase, charged pro rata to the shares
+    pub tip: Uint128,
+}
+
+/// Describes a fill of a DCA order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PurchaseRecord {
+    /// The id of the fill
+    pub id: u64,
+    /// The id of the DCA order that was filled
+    pub order_id: u64,
+    /// The time of the fill
+    pub time: u64,
+    /// The amount of `initial_asset` spent in the fill, after the protocol fee
+    pub offer_asset: Asset,
+    /// The amount of `target_asset` received in the fill, if it was measured by the contract
+    pub received: Option<Asset>,
+    /// Whether the fill was matched internally against an opposing order instead of being swapped
+    pub internal_match: bool,
+}
+
+/// Describes the amounts spent and received by the fills of a DCA order whose proceeds were
+/// measured by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CostBasis {
+    /// The amount of `initial_asset` spent in the fills, including the protocol fee
+    pub spent: Asset,
+    /// The amount of `target_asset` received in the fills
+    pub received: Asset,
+}
+
+/// Describes the referral statistics and rewards of a referrer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReferrerStats {
+    /// The amount of DCA orders created with this referrer
+    pub orders_referred: u64,
+    /// The amount of DCA purchases which credited this referrer with a reward
+    pub purchases_referred: u64,
+    /// The rewards that can currently be claimed by the referrer
+    pub claimable: Vec<Asset>,
+    /// The rewards credited to the referrer over all time
+    pub total_earned: Vec<Asset>,
+}
+
+impl ReferrerStats {
+    /// Credits a referral reward to the claimable and total earned balances.
+    pub fn credit(&mut self, reward: &Asset) -> StdResult<()> {
+        add_asset(&mut self.claimable, reward)?;
+        add_asset(&mut self.total_earned, reward)?;
+        self.purchases_referred += 1;
+
+        Ok(())
+    }
+}
+
+/// Describes the statistics of the DCA orders spending `initial_asset` to purchase `target_asset`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairStats {
+    /// The asset spent by the orders
+    pub initial_asset: AssetInfo,
+    /// The asset purchased by the orders
+    pub target_asset: AssetInfo,
+    /// The amount of DCA orders created for the pair
+    pub orders_created: u64,
+    /// The amount of DCA orders for the pair that have not been cancelled
+    pub active_orders: u64,
+    /// The amount of DCA purchases performed for the pair
+    pub purchases: u64,
+    /// The amount of `initial_asset` spent in DCA purchases, including the protocol fee
+    pub volume: Uint128,
+    /// The amount of `target_asset` bought in DCA purchases, as measured once they are settled
+    #[serde(default)]
+    pub bought: Uint128,
+    /// The uusd tips paid for DCA purchases
+    pub tips_paid: Uint128,
+}
+
+impl PairStats {
+    /// Returns empty statistics for the pair of `initial_asset` and `target_asset`.
+    pub fn new(initial_asset: &AssetInfo, target_asset: &AssetInfo) -> Self {
+        PairStats {
+            initial_asset: initial_asset.clone(),
+            target_asset: target_asset.clone(),
+            orders_created: 0,
+            active_orders: 0,
+            purchases: 0,
+            volume: Uint128::zero(),
+            bought: Uint128::zero(),
+            tips_paid: Uint128::zero(),
+        }
+    }
+
+    /// Removes an order from the active orders, returning an [`OverflowError`] if there are none.
+    pub fn remove_active_order(&mut self) -> StdResult<()> {
+        self.active_orders = self
+            .active_orders
+            .checked_sub(1)
+            .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, self.active_orders, 1))?;
+
+        Ok(())
+    }
+}
+
+/// Adds `asset` to an existing entry of the same asset in `assets`, or appends it otherwise.
+fn add_asset(assets: &mut Vec<Asset>, asset: &Asset) -> StdResult<()> {
+    match assets
+        .iter_mut()
+        .find(|existing| existing.info == asset.info)
+    {
+        Some(existing) => existing.amount = existing.amount.checked_add(asset.amount)?,
+        None => assets.push(asset.clone()),
+    }
+
+    Ok(())
 }
 
 /// Describes the parameters used for creating a contract
@@ -32,6 +466,12 @@ pub struct InstantiateMsg {
     pub max_hops: u32,
     /// The fee a user must pay per hop performed in a DCA purchase
     pub per_hop_fee: Uint128,
+    /// The base fee a user must pay for each DCA purchase
+    pub base_tip: Uint128,
+    /// The share of the uusd value of the `dca_amount` a user must pay for each DCA purchase
+    pub tip_volume_rate: Decimal,
+    /// The maximum tip paid for a DCA purchase
+    pub max_tip: Uint128,
     /// The whitelisted tokens that can be used in a DCA hop route
     pub whitelisted_tokens: Vec<AssetInfo>,
     /// The maximum amount of spread
@@ -40,28 +480,121 @@ pub struct InstantiateMsg {
     pub factory_addr: String,
     /// The address of the Astroport router contract
     pub router_addr: String,
+    /// The address that receives the protocol fee taken from DCA purchases
+    pub fee_collector: String,
+    /// The protocol fee in basis points taken from the `dca_amount` of each DCA purchase
+    pub protocol_fee_bps: u16,
+    /// The share of the protocol fee or bot tip credited to the referrer of an order
+    pub referral_share: Decimal,
+    /// Whether referrers are paid out of the protocol fee or the bot tip
+    pub referral_source: ReferralSource,
+    /// The time in seconds after an order becomes due during which only registered bots can
+    /// perform the DCA purchase
+    pub bot_priority_window: u64,
+    /// The minimum uusd bond a registered bot must have staked to receive priority
+    pub min_bot_bond: Uint128,
+    /// The time in seconds the bond of an unregistering bot stays slashable before it can be
+    /// claimed
+    pub bot_unbonding_period: u64,
+    /// Whether only bots whitelisted by the factory owner receive priority
+    pub bot_whitelist_enabled: bool,
+    /// The minimum interval in seconds between DCA purchases of an order
+    pub min_interval: u64,
+    /// The minimum `dca_amount` of an order for specific initial assets
+    pub min_dca_amounts: Vec<MinDcaAmount>,
+    /// The maximum amount of DCA orders a user can have at once
+    pub max_orders_per_user: u32,
 }
 
 /// This structure describes the execute messages available in the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     /// Add uusd top-up for bots to perform DCA requests
     AddBotTip {},
+    /// Add uusd top-up for bots to perform the aggregated purchases of the vault `vault_id`,
+    /// credited to the position of the sender
+    ///
+    /// The tip of each purchase is charged to the positions pro rata to their shares, each out of
+    /// its own top-up. A position whose top-up can not cover its share is suspended from the
+    /// purchases of the vault, and the unused top-up is refunded when the position is withdrawn
+    AddVaultTip { vault_id: u64 },
+    /// Approves `operator` to manage the DCA orders of the sender with the given `permissions`
+    /// until `expires`, replacing any previous approval of the operator
+    ApproveOperator {
+        operator: String,
+        expires: Option<Expiration>,
+        permissions: Vec<OperatorPermission>,
+    },
+    /// Adds tokens to the whitelist that can be used in a DCA hop route, ignoring tokens that
+    /// are already whitelisted
+    AddWhitelistedTokens { tokens: Vec<AssetInfo> },
+    /// Claims the bond of the sender once the unbonding period of the bot has passed
+    ClaimBotBond {},
     /// Cancels a DCA order, returning any native asset back to the user
-    CancelDcaOrder { initial_asset: AssetInfo },
+    ///
+    /// An approved operator can cancel the order of the `owner`
+    CancelDcaOrder {
+        initial_asset: AssetInfo,
+        owner: Option<String>,
+    },
+    /// Claims the referral rewards credited to the sender
+    ClaimReferralRewards {},
+    /// Claims the `target_asset` purchased for the sender by the vault `vault_id`
+    ClaimVaultProceeds { vault_id: u64 },
     /// Creates a new DCA order where `dca_amount` of token `initial_asset` will purchase
     /// `target_asset` every `interval`
     ///
     /// If `initial_asset` is a Cw20 token, the user needs to have increased the allowance prior to
     /// calling this execution
+    ///
+    /// An optional `referrer` is credited with a share of the fees of each purchase of the order
+    ///
+    /// The optional `max_hops` and `max_spread` override the user and contract configuration for
+    /// purchases of this order, and the optional `route_preference` restricts their hop routes
+    ///
+    /// An approved operator can create an order for the `owner`, which is funded by the owner if
+    /// `initial_asset` is a Cw20 token
     CreateDcaOrder {
         initial_asset: Asset,
         target_asset: AssetInfo,
         interval: u64,
         dca_amount: Uint128,
+        referrer: Option<String>,
+        max_hops: Option<u32>,
+        max_spread: Option<Decimal>,
+        route_preference: Option<RoutePreference>,
+        owner: Option<String>,
+    },
+    /// Deposits `initial_asset` into the vault purchasing `target_asset` every `interval`,
+    /// creating the vault if it does not exist yet
+    ///
+    /// The sender receives `dca_amount` shares, each of which spends one unit of `initial_asset`
+    /// in every aggregated purchase of the vault until the deposit is used up
+    ///
+    /// Vaults with a different `max_spread` are kept apart. If none is given, the `max_spread`
+    /// of the sender's user configuration is used, if set
+    ///
+    /// The position needs a tip added with `AddVaultTip` to take part in the purchases. If the
+    /// sender is exempt from the protocol fee, the part of its shares that would pay the fee is
+    /// returned to the position instead of being swapped
+    ///
+    /// If `initial_asset` is a Cw20 token, the user needs to have increased the allowance prior to
+    /// calling this execution
+    DepositVault {
+        initial_asset: Asset,
+        target_asset: AssetInfo,
+        interval: u64,
+        dca_amount: Uint128,
+        max_spread: Option<Decimal>,
     },
     /// Modifies an existing DCA order, allowing the user to change certain parameters
+    ///
+    /// Setting `new_max_hops`, `new_max_spread` or `new_route_preference` to `None` removes the
+    /// override of the order
+    ///
+    /// An approved operator can modify the order of the `owner`, with any refund sent to the owner
     ModifyDcaOrder {
         old_initial_asset: AssetInfo,
         new_initial_asset: Asset,
@@ -69,12 +602,66 @@ pub enum ExecuteMsg {
         new_interval: u64,
         new_dca_amount: Uint128,
         should_reset_purchase_time: bool,
+        new_max_hops: Option<u32>,
+        new_max_spread: Option<Decimal>,
+        new_route_preference: Option<RoutePreference>,
+        owner: Option<String>,
     },
+    /// Pauses the DCA order with the id `id`, so that it is not purchased until it is resumed
+    ///
+    /// An approved operator with the permission to modify orders can pause the order of its owner
+    PauseDcaOrder { id: u64 },
+    /// Registers the sender as a bot, with any uusd sent being added to the bots bond
+    RegisterBot {},
+    /// Transfers the DCA order with the id `token_id` to `recipient`, who becomes its controller
+    /// and the receiver of its purchases
+    ///
+    /// Orders spending a Cw20 token can not be transferred, as their purchases are funded from
+    /// the allowance of the owner. Transferring an order to its owner does nothing
+    TransferNft { recipient: String, token_id: String },
+    /// Unregisters the sender as a bot, which loses its priority and starts the unbonding period
+    /// of its bond
+    UnregisterBot {},
+    /// Slashes the bond of a registered bot for misbehaviour, sending it to the fee collector
+    SlashBot { bot: String, amount: Uint128 },
+    /// Skips the next DCA purchase of the order with the id `id`, which is the purchase that is
+    /// due if the order is due, keeping the schedule of the purchases after it
+    ///
+    /// An approved operator with the permission to modify orders can skip a purchase of the order
+    /// of its owner
+    SkipNextPurchase { id: u64 },
     /// Performs a DCA purchase for a specified user given a hop route
     PerformDcaPurchase {
         user: String,
         hops: Vec<SwapOperation>,
     },
+    /// Settles the due DCA orders `order_ids` between the same two assets in opposite directions,
+    /// matching them internally at the simulated pool price and swapping only the net imbalance
+    /// through the given hop route, within the limits and route preferences of every order
+    PerformBatchSettlement {
+        order_ids: Vec<u64>,
+        hops: Vec<SwapOperation>,
+    },
+    /// Performs the aggregated purchase of the vault `vault_id` for all its participants given a
+    /// hop route
+    PerformVaultPurchase {
+        vault_id: u64,
+        hops: Vec<SwapOperation>,
+    },
+    /// Revokes the approval of `operator` to manage the DCA orders of the sender
+    RevokeOperator { operator: String },
+    /// Removes tokens from the whitelist that can be used in a DCA hop route, failing if any of
+    /// them is not whitelisted
+    RemoveWhitelistedTokens { tokens: Vec<AssetInfo> },
+    /// Resumes the paused DCA order with the id `id`
+    ///
+    /// If `catch_up` is set, the order keeps its schedule and every purchase missed while it was
+    /// paused is due straight away, up to the balance of the order and at most
+    /// [`MAX_CATCH_UP_PURCHASES`](crate::evaluation::MAX_CATCH_UP_PURCHASES) purchases.
+    /// Otherwise, the time it was paused for does not count towards its interval
+    ///
+    /// An approved operator with the permission to modify orders can resume the order of its owner
+    ResumeDcaOrder { id: u64, catch_up: bool },
     /// Updates the configuration of the contract
     UpdateConfig {
         /// The new maximum amount of hops to perform from `initial_asset` to `target_asset` when
@@ -82,10 +669,45 @@ pub enum ExecuteMsg {
         max_hops: Option<u32>,
         /// The new fee a user must pay per hop performed in a DCA purchase
         per_hop_fee: Option<Uint128>,
-        /// The new whitelisted tokens that can be used in a DCA hop route
+        /// The new base fee a user must pay for each DCA purchase
+        base_tip: Option<Uint128>,
+        /// The new share of the uusd value of the `dca_amount` a user must pay for each DCA
+        /// purchase
+        tip_volume_rate: Option<Decimal>,
+        /// The new maximum tip paid for a DCA purchase
+        max_tip: Option<Uint128>,
+        /// The new whitelisted tokens that can be used in a DCA hop route, replacing the current
+        /// whitelist
         whitelisted_tokens: Option<Vec<AssetInfo>>,
         /// The new maximum spread for DCA purchases
         max_spread: Option<Decimal>,
+        /// The new address that receives the protocol fee
+        fee_collector: Option<String>,
+        /// The new protocol fee in basis points taken from the `dca_amount` of each DCA purchase
+        protocol_fee_bps: Option<u16>,
+        /// The new protocol fee overrides for specific target assets
+        target_asset_fees: Option<Vec<TargetAssetFee>>,
+        /// The new addresses whose DCA purchases are exempt from the protocol fee
+        fee_exempt_addresses: Option<Vec<String>>,
+        /// The new share of the protocol fee or bot tip credited to referrers
+        referral_share: Option<Decimal>,
+        /// The new source that referrers are paid out of
+        referral_source: Option<ReferralSource>,
+        /// The new time in seconds after an order becomes due during which only registered bots
+        /// can perform the DCA purchase
+        bot_priority_window: Option<u64>,
+        /// The new minimum uusd bond a registered bot must have staked to receive priority
+        min_bot_bond: Option<Uint128>,
+        /// The new time in seconds the bond of an unregistering bot stays slashable
+        bot_unbonding_period: Option<u64>,
+        /// Whether only bots whitelisted by the factory owner receive priority
+        bot_whitelist_enabled: Option<bool>,
+        /// The new minimum interval in seconds between DCA purchases of an order
+        min_interval: Option<u64>,
+        /// The new minimum `dca_amount` of an order for specific initial assets
+        min_dca_amounts: Option<Vec<MinDcaAmount>>,
+        /// The new maximum amount of DCA orders a user can have at once
+        max_orders_per_user: Option<u32>,
     },
     /// Update the configuration for a user
     UpdateUserConfig {
@@ -93,27 +715,147 @@ pub enum ExecuteMsg {
         max_hops: Option<u32>,
         /// The maximum spread per token when performing DCA purchases
         max_spread: Option<Decimal>,
+        /// The maximum tip the user is willing to pay per DCA purchase
+        max_tip_per_purchase: Option<Uint128>,
+        /// How the tip paid for DCA purchases is priced
+        tip_mode: Option<TipMode>,
     },
+    /// Adds the registered bot `bot` to the bot whitelist, or removes it if `whitelisted` is not
+    /// set
+    WhitelistBot { bot: String, whitelisted: bool },
     /// Withdraws a users bot tip from the contract.
-    Withdraw { tip: Uint128 },
+    ///
+    /// An approved operator can withdraw the tip of the `owner`, which is sent to the owner
+    Withdraw { tip: Uint128, owner: Option<String> },
+    /// Withdraws the position of the sender in the vault `vault_id`, returning the unspent
+    /// `initial_asset`, the unclaimed `target_asset` and the unused tip top-up
+    WithdrawVault { vault_id: u64 },
 }
 
 /// This structure describes the query messages available in the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    /// Returns information about the users current active DCA orders in a [`Vec<DcaInfo>`] object.
-    UserDcaOrders { user: String },
+    /// Returns information about the users current active DCA orders in a
+    /// [`Vec<DcaQueryInfo>`] object, including paused orders only if `include_paused` is set.
+    UserDcaOrders {
+        user: String,
+        #[serde(default)]
+        include_paused: bool,
+    },
     /// Returns information about the contract configuration in a [`Config`] object.
     Config {},
     /// Returns the users current configuration as a [`UserConfig`] object.
     UserConfig { user: String },
+    /// Returns the referral statistics and claimable rewards of a referrer as a [`ReferrerStats`]
+    /// object.
+    ReferrerStats { referrer: String },
+    /// Returns information about a registered bot in a [`BotInfo`] object.
+    Bot { bot: String },
+    /// Returns information about the registered bots in a [`Vec<BotInfo>`] object.
+    Bots {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the owner of the DCA order with the id `token_id` in an [`OwnerOfResponse`] object.
+    OwnerOf { token_id: String },
+    /// Returns the DCA order with the id `token_id` in an [`NftInfoResponse`] object.
+    NftInfo { token_id: String },
+    /// Returns the ids of the DCA orders owned by `owner` in a [`TokensResponse`] object.
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the operators approved by `owner` in a [`Vec<OperatorApproval>`] object.
+    Operators {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the limits that a new or modified DCA order spending `initial_asset` must satisfy
+    /// in an [`OrderLimitsResponse`] object.
+    OrderLimits { initial_asset: AssetInfo },
+    /// Returns the fills of the DCA order with the id `token_id` in a [`Vec<PurchaseRecord>`]
+    /// object.
+    PurchaseHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the tokens that can be used in a DCA hop route in a [`Vec<AssetInfo>`] object.
+    WhitelistedTokens {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    /// Returns information about the vault `vault_id` in a [`VaultInfo`] object.
+    Vault { vault_id: u64 },
+    /// Returns information about the vaults in a [`Vec<VaultInfo>`] object.
+    Vaults {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the position of `user` in the vault `vault_id` in a [`VaultPositionResponse`]
+    /// object.
+    VaultPosition { vault_id: u64, user: String },
+    /// Returns the aggregated purchases of the vault `vault_id` in a
+    /// [`Vec<VaultPurchaseRecord>`] object.
+    VaultPurchaseHistory {
+        vault_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the statistics of all DCA orders, broken down per asset pair, in a
+    /// [`StatsResponse`] object. The pairs are paginated by their `[initial_asset, target_asset]`,
+    /// and the totals only cover the pairs returned
+    GlobalStats {
+        start_after: Option<(AssetInfo, AssetInfo)>,
+        limit: Option<u32>,
+    },
+    /// Returns the statistics of the DCA orders of `user`, broken down per asset pair, in a
+    /// [`StatsResponse`] object.
+    UserStats { user: String },
+    /// Returns the average cost and current value of the `target_asset` purchased by the DCA order
+    /// with the id `token_id` in an [`OrderPerformanceResponse`] object.
+    OrderPerformance { token_id: String },
 }
 
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
+///
+/// The parameters set the configuration values introduced since the version being migrated from,
+/// and are ignored if the contract already has them configured.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The address that receives the protocol fee, required when migrating from v1.0.0
+    pub fee_collector: Option<String>,
+    /// The protocol fee in basis points, defaulting to no fee
+    pub protocol_fee_bps: Option<u16>,
+    /// The share of the protocol fee or bot tip credited to referrers, defaulting to no share
+    pub referral_share: Option<Decimal>,
+    /// Whether referrers are paid out of the protocol fee or the bot tip, defaulting to the
+    /// protocol fee
+    pub referral_source: Option<ReferralSource>,
+    /// The time in seconds registered bots have priority after an order becomes due, defaulting to
+    /// no priority window
+    pub bot_priority_window: Option<u64>,
+    /// The minimum uusd bond for bots to receive priority, defaulting to no bond
+    pub min_bot_bond: Option<Uint128>,
+    /// The time in seconds the bond of an unregistering bot stays slashable, defaulting to no
+    /// unbonding period
+    pub bot_unbonding_period: Option<u64>,
+    /// The base fee a user must pay for each DCA purchase, defaulting to no base fee
+    pub base_tip: Option<Uint128>,
+    /// The share of the uusd value of the `dca_amount` paid as a tip, defaulting to no share
+    pub tip_volume_rate: Option<Decimal>,
+    /// The maximum tip paid for a DCA purchase, defaulting to the tip for a purchase with the
+    /// maximum amount of hops
+    pub max_tip: Option<Uint128>,
+    /// The minimum interval in seconds between DCA purchases of an order, defaulting to no
+    /// minimum
+    pub min_interval: Option<u64>,
+    /// The maximum amount of DCA orders a user can have at once, defaulting to 10 orders
+    pub max_orders_per_user: Option<u32>,
+}
 
 /// Describes information for a UserDcaOrders query
 ///
@@ -122,7 +864,103 @@ pub struct MigrateMsg {}
 ///
 /// This is useful for bots and front-end to distinguish between a users token allowance (which may
 /// have changed) for the DCA contract, and the created DCA order size.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DcaQueryInfo {
-    token_allowance: Uint128,
-    info: DcaInfo,
+    /// The cw20 token allowance, or native token balance, available to the order
+    pub token_allowance: Uint128,
+    /// The DCA order
+    pub info: DcaInfo,
+    /// The uusd tip currently offered for the order if the user prices tips with a
+    /// [`TipMode::DutchAuction`]
+    pub auction_tip: Option<Uint128>,
+    /// The maximum amount of hops applied to DCA purchases of the order, taken from the order,
+    /// then the user configuration, then the contract configuration
+    pub max_hops: u32,
+    /// The maximum spread applied to DCA purchases of the order, taken from the order, then the
+    /// user configuration, then the contract configuration
+    pub max_spread: Decimal,
+}
+
+/// Describes the limits that a new or modified DCA order must satisfy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrderLimitsResponse {
+    /// The minimum interval in seconds between DCA purchases
+    pub min_interval: u64,
+    /// The minimum `dca_amount` for the queried initial asset
+    pub min_dca_amount: Uint128,
+    /// The maximum amount of DCA orders a user can have at once
+    pub max_orders_per_user: u32,
+}
+
+/// Describes the owner of a DCA order, as in the CW721 `owner_of` query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    /// The address of the owner of the order
+    pub owner: String,
+}
+
+/// Describes a DCA order, as in the CW721 `nft_info` query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse {
+    /// A URI describing the order, which is not set for DCA orders
+    pub token_uri: Option<String>,
+    /// The DCA order
+    pub extension: DcaInfo,
+}
+
+/// Describes the ids of DCA orders, as in the CW721 `tokens` query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    /// The ids of the orders as token ids
+    pub tokens: Vec<String>,
+}
+
+/// Describes the position of a user in a DCA vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultPositionResponse {
+    /// The amount of `initial_asset` the position spends in each aggregated purchase
+    pub shares: Uint128,
+    /// The amount of `initial_asset` of the position that has not been spent yet
+    pub remaining: Uint128,
+    /// The amount of `target_asset` purchased for the position that can be claimed
+    pub claimable: Uint128,
+    /// The uusd deposited for the tips of the position that has not been charged yet
+    pub tip_balance: Uint128,
+    /// Whether the position was suspended from the purchases of the vault because its tip
+    /// balance could not cover its share of a tip
+    pub suspended: bool,
+}
+
+/// Describes the statistics of all DCA orders, or of the DCA orders of a user
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    /// The amount of DCA orders created
+    pub orders_created: u64,
+    /// The amount of DCA orders that have not been cancelled
+    pub active_orders: u64,
+    /// The amount of DCA purchases performed
+    pub purchases: u64,
+    /// The uusd tips paid for DCA purchases
+    pub tips_paid: Uint128,
+    /// The statistics of each pair of initial and target asset
+    pub pairs: Vec<PairStats>,
+}
+
+/// Describes the average cost and current value of the `target_asset` purchased by a DCA order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrderPerformanceResponse {
+    /// The amount of `initial_asset` spent in the measured fills of the order, including the
+    /// protocol fee
+    pub spent: Asset,
+    /// The amount of `target_asset` received in the measured fills of the order
+    pub received: Asset,
+    /// The average amount of `initial_asset` spent per unit of `target_asset` received, including
+    /// the protocol fee, if any was received
+    pub average_price: Option<Decimal>,
+    /// The amount of `initial_asset` returned by simulating a swap of `received` through the pair
+    pub current_value: Uint128,
+    /// The amount by which `current_value` exceeds `spent`
+    pub unrealized_profit: Uint128,
+    /// The amount by which `current_value` falls short of `spent`
+    pub unrealized_loss: Uint128,
 }
@@ -0,0 +1,308 @@
+//! The evaluation of DCA purchases shared by the contract and the off-chain tooling, so that
+//! simulations price purchases exactly like the contract does.
+
+use cosmwasm_std::{Decimal, StdResult, Uint128};
+
+use crate::dca::{DcaInfo, TipMode};
+
+/// The denominator used for basis point calculations
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// The maximum amount of purchases missed while an order was paused that can be made up for
+pub const MAX_CATCH_UP_PURCHASES: u32 = 10;
+
+/// Describes why a DCA purchase cannot be performed yet
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ineligible {
+    /// The interval since the last purchase has not passed
+    TooEarly,
+    /// The purchase only recently became due, and can only be performed by a priority bot until
+    /// `until`
+    PriorityWindowActive { until: u64 },
+}
+
+/// Describes how the `dca_amount` of a DCA purchase is split
+#[derive(Clone, Debug, PartialEq)]
+pub struct PurchaseAmounts {
+    /// The protocol fee taken out of the `dca_amount`
+    pub protocol_fee: Uint128,
+    /// The part of the protocol fee credited to the referrer
+    pub referral_fee: Uint128,
+    /// The part of the protocol fee sent to the fee collector
+    pub collector_fee: Uint128,
+    /// The remainder of the `dca_amount` that is swapped
+    pub swap_amount: Uint128,
+}
+
+/// Checks that a purchase last performed at `last_purchase` and repeated every `interval` can be
+/// performed at `now`.
+///
+/// Once due, only a priority bot can perform the purchase during the `bot_priority_window`.
+pub fn check_eligibility(
+    last_purchase: u64,
+    interval: u64,
+    bot_priority_window: u64,
+    is_priority_bot: bool,
+    now: u64,
+) -> Result<(), Ineligible> {
+    let due_at = last_purchase + interval;
+    if due_at > now {
+        return Err(Ineligible::TooEarly);
+    }
+
+    let priority_until = due_at + bot_priority_window;
+    if !is_priority_bot && now < priority_until {
+        return Err(Ineligible::PriorityWindowActive {
+            until: priority_until,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the next purchase of `order` can be performed at `now`.
+///
+/// A purchase made up for after the order was resumed is due straight away, otherwise the
+/// purchase is checked with [`check_eligibility`].
+pub fn check_order_eligibility(
+    order: &DcaInfo,
+    bot_priority_window: u64,
+    is_priority_bot: bool,
+    now: u64,
+) -> Result<(), Ineligible> {
+    if order.catch_up_purchases > 0 {
+        return Ok(());
+    }
+
+    check_eligibility(
+        order.last_purchase,
+        order.interval,
+        bot_priority_window,
+        is_priority_bot,
+        now,
+    )
+}
+
+/// Returns the amount of purchases of `order` to make up for at `now`, which are the purchases
+/// still to be made up for and those that became due without being performed, capped by the
+/// purchases its balance can pay for and by [`MAX_CATCH_UP_PURCHASES`].
+pub fn missed_purchases(order: &DcaInfo, now: u64) -> u32 {
+    let due_at = (order.last_purchase + order.interval).max(order.start_time);
+    let intervals = if order.interval == 0 || due_at > now {
+        0
+    } else {
+        (now - due_at) / order.interval + 1
+    };
+    let affordable = order
+        .initial_asset
+        .amount
+        .checked_div(order.dca_amount)
+        .unwrap_or_default()
+        .u128();
+
+    (order.catch_up_purchases as u128)
+        .saturating_add(intervals as u128)
+        .min(affordable)
+        .min(MAX_CATCH_UP_PURCHASES as u128) as u32
+}
+
+/// Splits `amount` spent by a purchase into the protocol fee of `fee_bps` basis points and the
+/// swapped remainder, crediting `referral_share` of the protocol fee to the referrer if the
+/// referrer is paid out of the protocol fee.
+pub fn purchase_amounts(
+    amount: Uint128,
+    fee_bps: u16,
+    referral_share: Option<Decimal>,
+) -> StdResult<PurchaseAmounts> {
+    let protocol_fee = amount.multiply_ratio(fee_bps, BPS_DENOMINATOR);
+    let referral_fee = referral_share
+        .map(|share| share * protocol_fee)
+        .unwrap_or_default();
+
+    Ok(PurchaseAmounts {
+        protocol_fee,
+        referral_fee,
+        collector_fee: protocol_fee.checked_sub(referral_fee)?,
+        swap_amount: amount.checked_sub(protocol_fee)?,
+    })
+}
+
+/// Returns the maximum amount of hops for a DCA purchase of `order`, preferring the order
+/// override, then the user override, then the contract value.
+pub fn max_hops_for(order: &DcaInfo, user_max_hops: Option<u32>, max_hops: u32) -> u32 {
+    order.max_hops.or(user_max_hops).unwrap_or(max_hops)
+}
+
+/// Returns the maximum spread for a DCA purchase of `order`, preferring the order override, then
+/// the user override, then the contract value.
+pub fn max_spread_for(
+    order: &DcaInfo,
+    user_max_spread: Option<Decimal>,
+    max_spread: Decimal,
+) -> Decimal {
+    order.max_spread.or(user_max_spread).unwrap_or(max_spread)
+}
+
+/// Returns the tip made up of the `base_tip`, the `per_hop_fee` for each of `hops` hops and the
+/// `tip_volume_rate` of the uusd value `volume` of the purchase, capped at the `max_tip`.
+pub fn fixed_tip(
+    base_tip: Uint128,
+    per_hop_fee: Uint128,
+    hops: u32,
+    tip_volume_rate: Decimal,
+    volume: Uint128,
+    max_tip: Uint128,
+) -> StdResult<Uint128> {
+    let hop_fees = per_hop_fee.checked_mul(Uint128::from(hops))?;
+
+    Ok(base_tip
+        .checked_add(hop_fees)?
+        .checked_add(tip_volume_rate * volume)?
+        .min(max_tip))
+}
+
+/// Returns the tip currently offered for `order` if the user prices tips with a
+/// [`TipMode::DutchAuction`], otherwise returns `None`.
+///
+/// The tip rises linearly from `start_tip` when the order becomes due at
+/// `last_purchase + interval`, or at its `start_time` if it has not been purchased since, reaching
+/// `max_tip` after `duration` seconds.
+pub fn auction_tip(tip_mode: &TipMode, order: &DcaInfo, now: u64) -> Option<Uint128> {
+    match tip_mode {
+        TipMode::Fixed => None,
+        TipMode::DutchAuction {
+            start_tip,
+            max_tip,
+            duration,
+        } => {
+            let due_at = (order.last_purchase + order.interval).max(order.start_time);
+            let elapsed = now.saturating_sub(due_at).min(*duration);
+
+            Some(
+                *start_tip
+                    + max_tip
+                        .saturating_sub(*start_tip)
+                        .multiply_ratio(elapsed, (*duration).max(1)),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astroport::asset::{Asset, AssetInfo};
+    use cosmwasm_std::Addr;
+
+    #[test]
+    fn does_check_eligibility() {
+        assert_eq!(
+            check_eligibility(100, 50, 10, false, 149),
+            Err(Ineligible::TooEarly)
+        );
+        assert_eq!(
+            check_eligibility(100, 50, 10, false, 150),
+            Err(Ineligible::PriorityWindowActive { until: 160 })
+        );
+        assert_eq!(check_eligibility(100, 50, 10, true, 150), Ok(()));
+        assert_eq!(check_eligibility(100, 50, 10, false, 160), Ok(()));
+    }
+
+    #[test]
+    fn does_cap_missed_purchases() {
+        let order = |amount: u128| DcaInfo {
+            id: 1,
+            owner: Addr::unchecked("user"),
+            initial_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(amount),
+            },
+            target_asset: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            interval: 50,
+            last_purchase: 100,
+            start_time: 0,
+            dca_amount: Uint128::from(10u128),
+            referrer: None,
+            max_hops: None,
+            max_spread: None,
+            route_preference: None,
+            paused_at: None,
+            catch_up_purchases: 0,
+        };
+
+        assert_eq!(missed_purchases(&order(1_000), 149), 0);
+        assert_eq!(missed_purchases(&order(1_000), 150), 1);
+        assert_eq!(missed_purchases(&order(1_000), 299), 3);
+        assert_eq!(missed_purchases(&order(20), 299), 2);
+        assert_eq!(
+            missed_purchases(&order(1_000), 10_000),
+            MAX_CATCH_UP_PURCHASES
+        );
+        assert_eq!(
+            missed_purchases(
+                &DcaInfo {
+                    catch_up_purchases: 2,
+                    ..order(1_000)
+                },
+                150
+            ),
+            3
+        );
+        assert_eq!(
+            check_order_eligibility(
+                &DcaInfo {
+                    catch_up_purchases: 1,
+                    ..order(1_000)
+                },
+                10,
+                false,
+                100
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn does_split_purchase_amounts() {
+        assert_eq!(
+            purchase_amounts(Uint128::from(10_000u128), 150, Some(Decimal::percent(20))).unwrap(),
+            PurchaseAmounts {
+                protocol_fee: Uint128::from(150u128),
+                referral_fee: Uint128::from(30u128),
+                collector_fee: Uint128::from(120u128),
+                swap_amount: Uint128::from(9_850u128),
+            }
+        );
+        assert_eq!(
+            purchase_amounts(Uint128::from(10_000u128), 0, None).unwrap(),
+            PurchaseAmounts {
+                protocol_fee: Uint128::zero(),
+                referral_fee: Uint128::zero(),
+                collector_fee: Uint128::zero(),
+                swap_amount: Uint128::from(10_000u128),
+            }
+        );
+    }
+
+    #[test]
+    fn does_cap_fixed_tip() {
+        let tip = |volume: u128| {
+            fixed_tip(
+                Uint128::from(100u128),
+                Uint128::from(50u128),
+                2,
+                Decimal::percent(1),
+                Uint128::from(volume),
+                Uint128::from(500u128),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(tip(10_000), Uint128::from(300u128));
+        assert_eq!(tip(100_000), Uint128::from(500u128));
+    }
+}
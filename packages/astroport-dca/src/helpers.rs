@@ -0,0 +1,715 @@
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use astroport::{
+    asset::{Asset, AssetInfo, UUSD_DENOM},
+    router::SwapOperation,
+};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Expiration};
+
+use crate::dca::{
+    DcaInfo, DcaQueryInfo, ExecuteMsg, NftInfoResponse, OperatorPermission, OrderLimitsResponse,
+    OrderPerformanceResponse, OwnerOfResponse, QueryMsg, RoutePreference, StatsResponse, TipMode,
+    TokensResponse, VaultPositionResponse, VaultPurchaseRecord,
+};
+
+/// DcaContract is a wrapper around Addr that provides helpers for building the messages and
+/// queries of a DCA contract.
+///
+/// Messages that deposit an asset into the contract are built together with the funds or cw20
+/// allowance changes they need, so that the returned messages can be added to a response or
+/// transaction as they are.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DcaContract(pub Addr);
+
+/// Describes the optional overrides of a DCA order for its DCA purchases
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OrderOverrides {
+    /// The maximum amount of hops of a DCA purchase of the order
+    pub max_hops: Option<u32>,
+    /// The maximum spread of a DCA purchase of the order
+    pub max_spread: Option<Decimal>,
+    /// The restriction on the hop routes used for DCA purchases of the order
+    pub route_preference: Option<RoutePreference>,
+}
+
+impl DcaContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    /// Builds a message executing `msg` on the contract without any funds
+    pub fn call(&self, msg: ExecuteMsg) -> StdResult<CosmosMsg> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    /// Builds a message executing `msg` on the contract with `funds`
+    pub fn call_with_funds(&self, msg: ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg)?,
+            funds,
+        }
+        .into())
+    }
+
+    /// Builds the messages executing `msg` on the contract while depositing `asset`, which is sent
+    /// along as funds if it is a native token, or approved for the contract to transfer beforehand
+    /// if it is a cw20 token
+    pub fn call_with_deposit(&self, msg: ExecuteMsg, asset: &Asset) -> StdResult<Vec<CosmosMsg>> {
+        match &asset.info {
+            AssetInfo::NativeToken { denom } => Ok(vec![
+                self.call_with_funds(msg, coins_of(denom, asset.amount))?
+            ]),
+            AssetInfo::Token { contract_addr } => Ok(vec![
+                self.allowance_msg(contract_addr, asset.amount, true)?,
+                self.call(msg)?,
+            ]),
+        }
+    }
+
+    /// Builds a message increasing or decreasing the allowance of the contract for the cw20 token
+    /// `token` by `amount`
+    fn allowance_msg(&self, token: &Addr, amount: Uint128, increase: bool) -> StdResult<CosmosMsg> {
+        let spender = self.addr().into();
+        let msg = if increase {
+            Cw20ExecuteMsg::IncreaseAllowance {
+                spender,
+                amount,
+                expires: None,
+            }
+        } else {
+            Cw20ExecuteMsg::DecreaseAllowance {
+                spender,
+                amount,
+                expires: None,
+            }
+        };
+
+        Ok(WasmMsg::Execute {
+            contract_addr: token.into(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    /// Builds the messages creating a DCA order of the sender, depositing `initial_asset`
+    ///
+    /// If `initial_asset` is a cw20 token, the sender must not have an existing allowance for the
+    /// contract, as the contract requires the allowance to equal the deposit
+    pub fn create_dca_order(
+        &self,
+        initial_asset: Asset,
+        target_asset: AssetInfo,
+        interval: u64,
+        dca_amount: Uint128,
+        referrer: Option<String>,
+        overrides: OrderOverrides,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        let deposit = initial_asset.clone();
+        self.call_with_deposit(
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset,
+                target_asset,
+                interval,
+                dca_amount,
+                referrer,
+                max_hops: overrides.max_hops,
+                max_spread: overrides.max_spread,
+                route_preference: overrides.route_preference,
+                owner: None,
+            },
+            &deposit,
+        )
+    }
+
+    /// Builds the messages modifying the DCA order `order` of the sender to `modified`, sending or
+    /// approving any increase of the deposit and revoking the allowance of any cw20 deposit that
+    /// is no longer needed
+    pub fn modify_dca_order(
+        &self,
+        order: &DcaInfo,
+        modified: &DcaInfo,
+        should_reset_purchase_time: bool,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        let msg = ExecuteMsg::ModifyDcaOrder {
+            old_initial_asset: order.initial_asset.info.clone(),
+            new_initial_asset: modified.initial_asset.clone(),
+            new_target_asset: modified.target_asset.clone(),
+            new_interval: modified.interval,
+            new_dca_amount: modified.dca_amount,
+            should_reset_purchase_time,
+            new_max_hops: modified.max_hops,
+            new_max_spread: modified.max_spread,
+            new_route_preference: modified.route_preference.clone(),
+            owner: None,
+        };
+
+        let old = &order.initial_asset;
+        let new = &modified.initial_asset;
+
+        // when switching assets, the new deposit is made in full
+        if old.info != new.info {
+            let mut messages = vec![];
+            if let AssetInfo::Token { contract_addr } = &old.info {
+                messages.push(self.allowance_msg(contract_addr, old.amount, false)?);
+            }
+            messages.extend(self.call_with_deposit(msg, new)?);
+
+            return Ok(messages);
+        }
+
+        // otherwise only the difference is deposited or, for cw20 tokens, revoked
+        match &new.info {
+            AssetInfo::NativeToken { denom } => {
+                Ok(vec![self.call_with_funds(
+                    msg,
+                    coins_of(denom, new.amount.saturating_sub(old.amount)),
+                )?])
+            }
+            AssetInfo::Token { contract_addr } => {
+                let mut messages = vec![];
+                if new.amount > old.amount {
+                    messages.push(self.allowance_msg(
+                        contract_addr,
+                        new.amount - old.amount,
+                        true,
+                    )?);
+                } else if new.amount < old.amount {
+                    messages.push(self.allowance_msg(
+                        contract_addr,
+                        old.amount - new.amount,
+                        false,
+                    )?);
+                }
+                messages.push(self.call(msg)?);
+
+                Ok(messages)
+            }
+        }
+    }
+
+    /// Builds the messages cancelling the DCA order `order` of the sender, revoking the allowance
+    /// of its remaining deposit if it is a cw20 token
+    pub fn cancel_dca_order(&self, order: &DcaInfo) -> StdResult<Vec<CosmosMsg>> {
+        let mut messages = vec![self.call(ExecuteMsg::CancelDcaOrder {
+            initial_asset: order.initial_asset.info.clone(),
+            owner: None,
+        })?];
+
+        if let AssetInfo::Token { contract_addr } = &order.initial_asset.info {
+            if !order.initial_asset.amount.is_zero() {
+                messages.push(self.allowance_msg(
+                    contract_addr,
+                    order.initial_asset.amount,
+                    false,
+                )?);
+            }
+        }
+
+        Ok(messages)
+    }
+
+    /// Builds a message transferring the DCA order `token_id` of the sender to `recipient`, which
+    /// must spend a native token
+    pub fn transfer_order<T: Into<String>>(
+        &self,
+        recipient: T,
+        token_id: u64,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::TransferNft {
+            recipient: recipient.into(),
+            token_id: token_id.to_string(),
+        })
+    }
+
+    /// Builds a message pausing the DCA order `id` of the sender
+    pub fn pause_dca_order(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::PauseDcaOrder { id })
+    }
+
+    /// Builds a message resuming the paused DCA order `id` of the sender, purchasing the missed
+    /// interval immediately if `catch_up` is set
+    pub fn resume_dca_order(&self, id: u64, catch_up: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ResumeDcaOrder { id, catch_up })
+    }
+
+    /// Builds a message skipping the next purchase of the DCA order `id` of the sender
+    pub fn skip_next_purchase(&self, id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SkipNextPurchase { id })
+    }
+
+    /// Builds a message depositing `amount` uusd for the tips of the DCA purchases of the sender
+    pub fn add_bot_tip(&self, amount: Uint128) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::AddBotTip {}, coins_of(UUSD_DENOM, amount))
+    }
+
+    /// Builds a message withdrawing `tip` uusd from the tip balance of the sender
+    pub fn withdraw(&self, tip: Uint128) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Withdraw { tip, owner: None })
+    }
+
+    /// Builds a message updating the configuration of the sender
+    pub fn update_user_config(
+        &self,
+        max_hops: Option<u32>,
+        max_spread: Option<Decimal>,
+        max_tip_per_purchase: Option<Uint128>,
+        tip_mode: Option<TipMode>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateUserConfig {
+            max_hops,
+            max_spread,
+            max_tip_per_purchase,
+            tip_mode,
+        })
+    }
+
+    /// Builds a message approving `operator` to manage the DCA orders of the sender
+    pub fn approve_operator<T: Into<String>>(
+        &self,
+        operator: T,
+        expires: Option<Expiration>,
+        permissions: Vec<OperatorPermission>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ApproveOperator {
+            operator: operator.into(),
+            expires,
+            permissions,
+        })
+    }
+
+    /// Builds a message revoking the approval of `operator`
+    pub fn revoke_operator<T: Into<String>>(&self, operator: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RevokeOperator {
+            operator: operator.into(),
+        })
+    }
+
+    /// Builds a message claiming the referral rewards of the sender
+    pub fn claim_referral_rewards(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClaimReferralRewards {})
+    }
+
+    /// Builds a message registering the sender as a bot with a uusd `bond`
+    pub fn register_bot(&self, bond: Uint128) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::RegisterBot {}, coins_of(UUSD_DENOM, bond))
+    }
+
+    /// Builds a message unregistering the sender as a bot, which starts the unbonding of its bond
+    pub fn unregister_bot(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UnregisterBot {})
+    }
+
+    /// Builds a message claiming the bond of the sender once it has unbonded
+    pub fn claim_bot_bond(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClaimBotBond {})
+    }
+
+    /// Builds a message performing the DCA purchase of `user` through `hops`
+    pub fn perform_dca_purchase<T: Into<String>>(
+        &self,
+        user: T,
+        hops: Vec<SwapOperation>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::PerformDcaPurchase {
+            user: user.into(),
+            hops,
+        })
+    }
+
+    /// Builds a message settling the opposing DCA orders `order_ids`, swapping their imbalance
+    /// through `hops`
+    pub fn perform_batch_settlement(
+        &self,
+        order_ids: Vec<u64>,
+        hops: Vec<SwapOperation>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::PerformBatchSettlement { order_ids, hops })
+    }
+
+    /// Builds the messages depositing `initial_asset` into the vault purchasing `target_asset`
+    /// every `interval` with `max_spread`
+    pub fn deposit_vault(
+        &self,
+        initial_asset: Asset,
+        target_asset: AssetInfo,
+        interval: u64,
+        dca_amount: Uint128,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        let deposit = initial_asset.clone();
+        self.call_with_deposit(
+            ExecuteMsg::DepositVault {
+                initial_asset,
+                target_asset,
+                interval,
+                dca_amount,
+                max_spread,
+            },
+            &deposit,
+        )
+    }
+
+    /// Builds a message depositing `amount` uusd for the tips of the position of the sender in a
+    /// vault
+    pub fn add_vault_tip(&self, vault_id: u64, amount: Uint128) -> StdResult<CosmosMsg> {
+        self.call_with_funds(
+            ExecuteMsg::AddVaultTip { vault_id },
+            coins_of(UUSD_DENOM, amount),
+        )
+    }
+
+    /// Builds a message performing the aggregated purchase of a vault through `hops`
+    pub fn perform_vault_purchase(
+        &self,
+        vault_id: u64,
+        hops: Vec<SwapOperation>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::PerformVaultPurchase { vault_id, hops })
+    }
+
+    /// Builds a message claiming the proceeds of the position of the sender in a vault
+    pub fn claim_vault_proceeds(&self, vault_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClaimVaultProceeds { vault_id })
+    }
+
+    /// Builds a message withdrawing the position of the sender in a vault
+    pub fn withdraw_vault(&self, vault_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::WithdrawVault { vault_id })
+    }
+
+    /// Queries the contract with `msg`, for queries without a typed wrapper
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: &QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(self.addr(), msg)
+    }
+
+    /// Get the active DCA orders of `user`
+    pub fn user_dca_orders<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        user: T,
+    ) -> StdResult<Vec<DcaQueryInfo>> {
+        self.query(
+            querier,
+            &QueryMsg::UserDcaOrders {
+                user: user.into(),
+                include_paused: false,
+            },
+        )
+    }
+
+    /// Get the owner of the DCA order `token_id`
+    pub fn owner_of(&self, querier: &QuerierWrapper, token_id: u64) -> StdResult<Addr> {
+        let res: OwnerOfResponse = self.query(
+            querier,
+            &QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+            },
+        )?;
+        Ok(Addr::unchecked(res.owner))
+    }
+
+    /// Get the DCA order `token_id`
+    pub fn order(&self, querier: &QuerierWrapper, token_id: u64) -> StdResult<DcaInfo> {
+        let res: NftInfoResponse = self.query(
+            querier,
+            &QueryMsg::NftInfo {
+                token_id: token_id.to_string(),
+            },
+        )?;
+        Ok(res.extension)
+    }
+
+    /// Get the ids of the DCA orders owned by `owner`
+    pub fn tokens<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<String>> {
+        let res: TokensResponse = self.query(
+            querier,
+            &QueryMsg::Tokens {
+                owner: owner.into(),
+                start_after: start_after.map(|id| id.to_string()),
+                limit,
+            },
+        )?;
+        Ok(res.tokens)
+    }
+
+    /// Get the limits that a DCA order spending `initial_asset` must satisfy
+    pub fn order_limits(
+        &self,
+        querier: &QuerierWrapper,
+        initial_asset: AssetInfo,
+    ) -> StdResult<OrderLimitsResponse> {
+        self.query(querier, &QueryMsg::OrderLimits { initial_asset })
+    }
+
+    /// Get the average cost and current value of the asset purchased by the order `token_id`
+    pub fn order_performance(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: u64,
+    ) -> StdResult<OrderPerformanceResponse> {
+        self.query(
+            querier,
+            &QueryMsg::OrderPerformance {
+                token_id: token_id.to_string(),
+            },
+        )
+    }
+
+    /// Get the tokens that can be used in a DCA hop route
+    pub fn whitelisted_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<AssetInfo>> {
+        self.query(querier, &QueryMsg::WhitelistedTokens { start_after, limit })
+    }
+
+    /// Get the position of `user` in a vault
+    pub fn vault_position<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        vault_id: u64,
+        user: T,
+    ) -> StdResult<VaultPositionResponse> {
+        self.query(
+            querier,
+            &QueryMsg::VaultPosition {
+                vault_id,
+                user: user.into(),
+            },
+        )
+    }
+
+    /// Get the aggregated purchases of a vault
+    pub fn vault_purchase_history(
+        &self,
+        querier: &QuerierWrapper,
+        vault_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<VaultPurchaseRecord>> {
+        self.query(
+            querier,
+            &QueryMsg::VaultPurchaseHistory {
+                vault_id,
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    /// Get the statistics of all DCA orders per asset pair
+    pub fn global_stats(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<(AssetInfo, AssetInfo)>,
+        limit: Option<u32>,
+    ) -> StdResult<StatsResponse> {
+        self.query(querier, &QueryMsg::GlobalStats { start_after, limit })
+    }
+
+    /// Get the statistics of the DCA orders of `user` per asset pair
+    pub fn user_stats<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        user: T,
+    ) -> StdResult<StatsResponse> {
+        self.query(querier, &QueryMsg::UserStats { user: user.into() })
+    }
+}
+
+/// Returns `amount` of the native token `denom` as funds, which are empty for a zero amount
+fn coins_of(denom: &str, amount: Uint128) -> Vec<Coin> {
+    if amount.is_zero() {
+        vec![]
+    } else {
+        vec![Coin {
+            denom: denom.to_string(),
+            amount,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, from_binary};
+
+    fn dca() -> DcaContract {
+        DcaContract(Addr::unchecked("dca"))
+    }
+
+    fn order(initial_asset: Asset) -> DcaInfo {
+        DcaInfo {
+            id: 1,
+            owner: Addr::unchecked("user"),
+            initial_asset,
+            target_asset: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            interval: 100,
+            last_purchase: 0,
+            start_time: 0,
+            dca_amount: Uint128::from(100u128),
+            referrer: None,
+            max_hops: None,
+            max_spread: None,
+            route_preference: None,
+            paused_at: None,
+            catch_up_purchases: 0,
+        }
+    }
+
+    fn uusd(amount: u128) -> Asset {
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn token(amount: u128) -> Asset {
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("token"),
+            },
+            amount: Uint128::from(amount),
+        }
+    }
+
+    /// Returns the contract, the cw20 message if any, and the funds of each message
+    fn decode(messages: Vec<CosmosMsg>) -> Vec<(String, Option<Cw20ExecuteMsg>, Vec<Coin>)> {
+        messages
+            .into_iter()
+            .map(|msg| match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) => {
+                    let cw20_msg = if contract_addr == "token" {
+                        Some(from_binary(&msg).unwrap())
+                    } else {
+                        None
+                    };
+                    (contract_addr, cw20_msg, funds)
+                }
+                _ => panic!("unexpected message"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn does_bundle_deposits() {
+        let messages = dca()
+            .create_dca_order(
+                uusd(1_000),
+                order(uusd(0)).target_asset,
+                100,
+                Uint128::from(100u128),
+                None,
+                OrderOverrides::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            decode(messages),
+            vec![("dca".to_string(), None, coins(1_000, "uusd"))]
+        );
+
+        let messages = dca()
+            .create_dca_order(
+                token(1_000),
+                order(uusd(0)).target_asset,
+                100,
+                Uint128::from(100u128),
+                None,
+                OrderOverrides::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            decode(messages),
+            vec![
+                (
+                    "token".to_string(),
+                    Some(Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: "dca".to_string(),
+                        amount: Uint128::from(1_000u128),
+                        expires: None,
+                    }),
+                    vec![]
+                ),
+                ("dca".to_string(), None, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_bundle_modifications() {
+        // increasing a native deposit sends the difference
+        let messages = dca()
+            .modify_dca_order(&order(uusd(1_000)), &order(uusd(1_500)), false)
+            .unwrap();
+        assert_eq!(
+            decode(messages),
+            vec![("dca".to_string(), None, coins(500, "uusd"))]
+        );
+
+        // decreasing a cw20 deposit revokes the difference
+        let messages = dca()
+            .modify_dca_order(&order(token(1_000)), &order(token(600)), false)
+            .unwrap();
+        assert_eq!(
+            decode(messages),
+            vec![
+                (
+                    "token".to_string(),
+                    Some(Cw20ExecuteMsg::DecreaseAllowance {
+                        spender: "dca".to_string(),
+                        amount: Uint128::from(400u128),
+                        expires: None,
+                    }),
+                    vec![]
+                ),
+                ("dca".to_string(), None, vec![]),
+            ]
+        );
+
+        // switching from a cw20 deposit revokes it and deposits the new asset in full
+        let messages = dca()
+            .modify_dca_order(&order(token(1_000)), &order(uusd(800)), false)
+            .unwrap();
+        assert_eq!(
+            decode(messages),
+            vec![
+                (
+                    "token".to_string(),
+                    Some(Cw20ExecuteMsg::DecreaseAllowance {
+                        spender: "dca".to_string(),
+                        amount: Uint128::from(1_000u128),
+                        expires: None,
+                    }),
+                    vec![]
+                ),
+                ("dca".to_string(), None, coins(800, "uusd")),
+            ]
+        );
+    }
+}
@@ -1 +1,3 @@
 pub mod dca;
+pub mod evaluation;
+pub mod helpers;